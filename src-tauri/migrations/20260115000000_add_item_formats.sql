@@ -0,0 +1,14 @@
+-- Store every representation (MIME type -> bytes) offered for a clipboard item
+CREATE TABLE IF NOT EXISTS item_formats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id INTEGER NOT NULL,
+    mime_type TEXT NOT NULL,
+    data BLOB NOT NULL,
+    FOREIGN KEY (item_id) REFERENCES items (id) ON DELETE CASCADE,
+    UNIQUE (item_id, mime_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_formats_item_id ON item_formats(item_id);
+
+-- The representation the UI should render for the item
+ALTER TABLE items ADD COLUMN preferred_format TEXT;
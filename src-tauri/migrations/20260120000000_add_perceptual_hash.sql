@@ -0,0 +1,8 @@
+-- Perceptual hash of image items, used to find near-duplicates
+ALTER TABLE items ADD COLUMN perceptual_hash INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_items_perceptual_hash ON items(perceptual_hash);
+
+-- Maximum Hamming distance between two perceptual hashes for images to count as the same
+INSERT OR IGNORE INTO settings (key, value)
+VALUES ('image_similarity_threshold', '5');
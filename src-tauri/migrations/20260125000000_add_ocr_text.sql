@@ -0,0 +1,2 @@
+-- Text recognised in image items; NULL until OCR has run, empty when it found none
+ALTER TABLE items ADD COLUMN ocr_text TEXT;
@@ -0,0 +1,3 @@
+-- How often and when an item was copied back from the history
+ALTER TABLE items ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE items ADD COLUMN last_used_at DATETIME;
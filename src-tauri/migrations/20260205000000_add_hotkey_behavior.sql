@@ -0,0 +1,10 @@
+-- The hold, hybrid and cycle switches become one setting. Cycle took over from hybrid and hybrid from hold.
+INSERT OR IGNORE INTO settings (key, value)
+SELECT 'hotkey_behavior', CASE
+    WHEN EXISTS (SELECT 1 FROM settings WHERE key = 'cycle_behavior' AND value = 'true') THEN 'cycle'
+    WHEN EXISTS (SELECT 1 FROM settings WHERE key = 'hybrid_behavior' AND value = 'true') THEN 'hybrid'
+    WHEN EXISTS (SELECT 1 FROM settings WHERE key = 'hold_behavior' AND value = 'true') THEN 'hold'
+    ELSE 'toggle'
+END;
+
+DELETE FROM settings WHERE key IN ('hold_behavior', 'hybrid_behavior', 'cycle_behavior');
@@ -0,0 +1,721 @@
+// Pastes a picked item straight into the application that was focused before YZZO was shown:
+// the window is hidden, focus is handed back and the paste shortcut is typed for the user.
+use crate::commands::hotkeys::parse_hotkey;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{Code, Modifiers};
+
+pub const DEFAULT_PASTE_SHORTCUT: &str = "Ctrl+V";
+
+// Typing into other applications is only implemented for X11 and Wayland.
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+// terminals take Ctrl+V as a control character and paste with Shift added
+const TERMINALS: [&str; 9] = [
+    "gnome-terminal-server",
+    "org.gnome.Console",
+    "konsole",
+    "xfce4-terminal",
+    "tilix",
+    "kitty",
+    "Alacritty",
+    "foot",
+    "org.wezfurlong.wezterm",
+];
+
+// how long the window manager gets to unmap YZZO before focus is moved
+const HIDE_DELAY: Duration = Duration::from_millis(80);
+
+// The paste shortcut for an application, matched against its X11 WM_CLASS or Wayland app id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PasteTarget {
+    pub app: String,
+    pub shortcut: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoPasteOptions {
+    pub enabled: bool,
+    // used for applications without a target of their own
+    pub shortcut: String,
+    pub targets: Vec<PasteTarget>,
+}
+
+impl Default for AutoPasteOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            shortcut: DEFAULT_PASTE_SHORTCUT.to_string(),
+            targets: TERMINALS
+                .iter()
+                .map(|app| PasteTarget {
+                    app: app.to_string(),
+                    shortcut: "Ctrl+Shift+V".to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl AutoPasteOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        for shortcut in
+            std::iter::once(&self.shortcut).chain(self.targets.iter().map(|t| &t.shortcut))
+        {
+            let parsed = parse_hotkey(shortcut)
+                .map_err(|e| format!("Invalid paste shortcut {}: {}", shortcut, e))?;
+            if key_symbol(parsed.key).is_none() {
+                return Err(format!("Paste shortcut {} cannot be typed", shortcut));
+            }
+        }
+        if self.targets.iter().any(|t| t.app.trim().is_empty()) {
+            return Err("Paste targets need an application name".to_string());
+        }
+        Ok(())
+    }
+
+    // Application names are compared without case, so `alacritty` matches `Alacritty`.
+    pub fn shortcut_for(&self, app: Option<&str>) -> &str {
+        app.and_then(|app| {
+            self.targets
+                .iter()
+                .find(|t| t.app.trim().eq_ignore_ascii_case(app))
+        })
+        .map_or(&self.shortcut, |t| &t.shortcut)
+    }
+}
+
+// The window that had focus when YZZO was shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusedApp {
+    // the X11 window, Wayland gives no handle on other clients' surfaces
+    pub window: Option<u32>,
+    pub app: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct PreviousFocus(Arc<Mutex<Option<FocusedApp>>>);
+
+impl PreviousFocus {
+    pub fn set(&self, focus: Option<FocusedApp>) {
+        *self.0.lock().unwrap() = focus;
+    }
+
+    pub fn take(&self) -> Option<FocusedApp> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+// Called right before the main window is shown from the hotkey or the tray.
+pub fn remember_focus(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+
+    match focused_app() {
+        Ok(focus) => state.previous_focus.set(focus),
+        Err(e) => {
+            eprintln!("[X] Failed to read the focused window: {}", e);
+            state.previous_focus.set(None);
+        }
+    }
+}
+
+// Gives focus back to `target` and types the paste shortcut configured for it. Blocks for a
+// fraction of a second, call it off the async runtime.
+pub fn paste(target: Option<&FocusedApp>, options: &AutoPasteOptions) -> Result<(), String> {
+    let app = target.and_then(|t| t.app.as_deref());
+    let shortcut = parse_hotkey(options.shortcut_for(app))
+        .map_err(|e| format!("Invalid paste shortcut: {}", e))?;
+    println!(
+        "[I] Auto-pasting into {} with {:?}",
+        app.unwrap_or("the focused window"),
+        shortcut
+    );
+
+    thread::sleep(HIDE_DELAY);
+    platform::paste(target.and_then(|t| t.window), &shortcut)
+}
+
+fn focused_app() -> Result<Option<FocusedApp>, String> {
+    platform::focused_app()
+}
+
+// The X11 keysym typing the key on a US layout.
+pub fn key_symbol(code: Code) -> Option<u32> {
+    let symbol = match code {
+        Code::KeyA => 'a',
+        Code::KeyB => 'b',
+        Code::KeyC => 'c',
+        Code::KeyD => 'd',
+        Code::KeyE => 'e',
+        Code::KeyF => 'f',
+        Code::KeyG => 'g',
+        Code::KeyH => 'h',
+        Code::KeyI => 'i',
+        Code::KeyJ => 'j',
+        Code::KeyK => 'k',
+        Code::KeyL => 'l',
+        Code::KeyM => 'm',
+        Code::KeyN => 'n',
+        Code::KeyO => 'o',
+        Code::KeyP => 'p',
+        Code::KeyQ => 'q',
+        Code::KeyR => 'r',
+        Code::KeyS => 's',
+        Code::KeyT => 't',
+        Code::KeyU => 'u',
+        Code::KeyV => 'v',
+        Code::KeyW => 'w',
+        Code::KeyX => 'x',
+        Code::KeyY => 'y',
+        Code::KeyZ => 'z',
+        Code::Digit0 => '0',
+        Code::Digit1 => '1',
+        Code::Digit2 => '2',
+        Code::Digit3 => '3',
+        Code::Digit4 => '4',
+        Code::Digit5 => '5',
+        Code::Digit6 => '6',
+        Code::Digit7 => '7',
+        Code::Digit8 => '8',
+        Code::Digit9 => '9',
+        Code::Backquote => '`',
+        Code::Quote => '\'',
+        Code::Insert => return Some(0xff63),
+        _ => return None,
+    };
+    Some(symbol as u32)
+}
+
+// The Linux input event code of the key, as used by the Wayland virtual keyboard.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn evdev_code(code: Code) -> Option<u32> {
+    Some(match code {
+        Code::Digit1 => 2,
+        Code::Digit2 => 3,
+        Code::Digit3 => 4,
+        Code::Digit4 => 5,
+        Code::Digit5 => 6,
+        Code::Digit6 => 7,
+        Code::Digit7 => 8,
+        Code::Digit8 => 9,
+        Code::Digit9 => 10,
+        Code::Digit0 => 11,
+        Code::Minus => 12,
+        Code::Equal => 13,
+        Code::KeyQ => 16,
+        Code::KeyW => 17,
+        Code::KeyE => 18,
+        Code::KeyR => 19,
+        Code::KeyT => 20,
+        Code::KeyY => 21,
+        Code::KeyU => 22,
+        Code::KeyI => 23,
+        Code::KeyO => 24,
+        Code::KeyP => 25,
+        Code::BracketLeft => 26,
+        Code::BracketRight => 27,
+        Code::KeyA => 30,
+        Code::KeyS => 31,
+        Code::KeyD => 32,
+        Code::KeyF => 33,
+        Code::KeyG => 34,
+        Code::KeyH => 35,
+        Code::KeyJ => 36,
+        Code::KeyK => 37,
+        Code::KeyL => 38,
+        Code::Semicolon => 39,
+        Code::Quote => 40,
+        Code::Backquote => 41,
+        Code::Backslash => 43,
+        Code::KeyZ => 44,
+        Code::KeyX => 45,
+        Code::KeyC => 46,
+        Code::KeyV => 47,
+        Code::KeyB => 48,
+        Code::KeyN => 49,
+        Code::KeyM => 50,
+        Code::Comma => 51,
+        Code::Period => 52,
+        Code::Slash => 53,
+        Code::IntlBackslash => 86,
+        Code::Insert => 110,
+        _ => return None,
+    })
+}
+
+// Keysyms of the left-hand modifier keys, held in this order around the key.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn modifier_symbols(modifiers: Modifiers) -> Vec<u32> {
+    [
+        (Modifiers::CONTROL, 0xffe3),
+        (Modifiers::SHIFT, 0xffe1),
+        (Modifiers::ALT, 0xffe9),
+        (Modifiers::SUPER, 0xffeb),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| modifiers.contains(*modifier))
+    .map(|(_, symbol)| symbol)
+    .collect()
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{FocusedApp, wayland, x11};
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    // YZZO may run through XWayland, the session decides which protocol reaches other apps
+    fn is_wayland() -> bool {
+        std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty())
+    }
+
+    pub fn focused_app() -> Result<Option<FocusedApp>, String> {
+        if is_wayland() {
+            // the compositor hands focus back by itself once the window is hidden
+            return Ok(None);
+        }
+        x11::focused_app()
+    }
+
+    pub fn paste(window: Option<u32>, shortcut: &Shortcut) -> Result<(), String> {
+        if is_wayland() {
+            return wayland::type_shortcut(shortcut);
+        }
+        x11::paste(window, shortcut)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::FocusedApp;
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    pub fn focused_app() -> Result<Option<FocusedApp>, String> {
+        Ok(None)
+    }
+
+    pub fn paste(_window: Option<u32>, _shortcut: &Shortcut) -> Result<(), String> {
+        Err("Auto-paste is not supported on this platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::{FocusedApp, key_symbol, modifier_symbols};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tauri_plugin_global_shortcut::Shortcut;
+    use x11rb::connection::{Connection, RequestConnection};
+    use x11rb::protocol::xproto::{
+        AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, InputFocus, KEY_PRESS_EVENT,
+        KEY_RELEASE_EVENT, KeyButMask, Keycode, Keysym, Window,
+    };
+    use x11rb::protocol::xtest::{self, ConnectionExt as _};
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::{CURRENT_TIME, NONE};
+
+    x11rb::atom_manager! {
+        pub Atoms: AtomsCookie {
+            _NET_ACTIVE_WINDOW,
+            _NET_SUPPORTING_WM_CHECK,
+            _NET_WM_PID,
+        }
+    }
+
+    // how long the window manager gets to move focus before the keys are typed anyway
+    const FOCUS_TIMEOUT: Duration = Duration::from_millis(500);
+    const FOCUS_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+    // how long a hotkey that pastes gets for its modifiers to be let go
+    const MODIFIER_RELEASE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+    // parent windows searched for WM_CLASS, focus often sits on a child of the frame
+    const MAX_TREE_DEPTH: usize = 8;
+
+    pub struct Display {
+        conn: RustConnection,
+        root: Window,
+        atoms: Atoms,
+    }
+
+    impl Display {
+        pub fn connect() -> Result<Self, String> {
+            let (conn, screen) = RustConnection::connect(None)
+                .map_err(|e| format!("Failed to connect to X server: {}", e))?;
+            let root = conn.setup().roots[screen].root;
+            let atoms = Atoms::new(&conn)
+                .map_err(|e| e.to_string())
+                .and_then(|cookie| cookie.reply().map_err(|e| e.to_string()))
+                .map_err(|e| format!("Failed to intern atoms: {}", e))?;
+            Ok(Self { conn, root, atoms })
+        }
+
+        fn window_property(&self, window: Window, property: u32) -> Option<u32> {
+            self.conn
+                .get_property(false, window, property, AtomEnum::ANY, 0, 1)
+                .ok()?
+                .reply()
+                .ok()?
+                .value32()?
+                .next()
+        }
+
+        // The EWMH active window, or the input focus without a window manager.
+        pub fn active_window(&self) -> Result<Window, String> {
+            if let Some(window) = self.window_property(self.root, self.atoms._NET_ACTIVE_WINDOW)
+                && window != NONE
+            {
+                return Ok(window);
+            }
+
+            self.conn
+                .get_input_focus()
+                .map_err(|e| e.to_string())?
+                .reply()
+                .map(|reply| reply.focus)
+                .map_err(|e| format!("Failed to get input focus: {}", e))
+        }
+
+        // The class half of WM_CLASS, looked up on the window and its parents.
+        pub fn window_class(&self, mut window: Window) -> Option<String> {
+            for _ in 0..MAX_TREE_DEPTH {
+                let reply = self
+                    .conn
+                    .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+                    .ok()?
+                    .reply()
+                    .ok()?;
+                if let Some(class) = parse_wm_class(&reply.value) {
+                    return Some(class);
+                }
+
+                let tree = self.conn.query_tree(window).ok()?.reply().ok()?;
+                if tree.parent == NONE || tree.parent == tree.root {
+                    return None;
+                }
+                window = tree.parent;
+            }
+            None
+        }
+
+        // Asks the window manager to activate `window`, or focuses it directly without one,
+        // then waits until it has focus.
+        pub fn activate(&self, window: Window) -> Result<(), String> {
+            let managed = self
+                .window_property(self.root, self.atoms._NET_SUPPORTING_WM_CHECK)
+                .is_some_and(|check| check != NONE);
+
+            if managed {
+                // source 2 marks a pager, which window managers do not second-guess
+                let event = ClientMessageEvent::new(
+                    32,
+                    window,
+                    self.atoms._NET_ACTIVE_WINDOW,
+                    [2, CURRENT_TIME, 0, 0, 0],
+                );
+                self.conn
+                    .send_event(
+                        false,
+                        self.root,
+                        EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                        event,
+                    )
+                    .map_err(|e| format!("Failed to activate window: {}", e))?;
+            } else {
+                self.conn
+                    .set_input_focus(InputFocus::PARENT, window, CURRENT_TIME)
+                    .map_err(|e| format!("Failed to focus window: {}", e))?;
+            }
+            self.conn.flush().map_err(|e| e.to_string())?;
+
+            let started = Instant::now();
+            while started.elapsed() < FOCUS_TIMEOUT {
+                if self.active_window()? == window {
+                    return Ok(());
+                }
+                thread::sleep(FOCUS_POLL_INTERVAL);
+            }
+            eprintln!("[X] Window {} did not take focus, pasting anyway", window);
+            Ok(())
+        }
+
+        fn keycode(&self, keysym: Keysym) -> Result<Keycode, String> {
+            let setup = self.conn.setup();
+            let (min, max) = (setup.min_keycode, setup.max_keycode);
+            let mapping = self
+                .conn
+                .get_keyboard_mapping(min, max - min + 1)
+                .map_err(|e| e.to_string())?
+                .reply()
+                .map_err(|e| format!("Failed to get keyboard mapping: {}", e))?;
+
+            let per_keycode = usize::from(mapping.keysyms_per_keycode).max(1);
+            mapping
+                .keysyms
+                .chunks(per_keycode)
+                .position(|keysyms| keysyms.contains(&keysym))
+                .map(|index| min + index as u8)
+                .ok_or_else(|| format!("No key types keysym {:#x}", keysym))
+        }
+
+        // The modifiers of a hotkey that triggered the paste may still be held, and would be
+        // added to the typed shortcut.
+        fn wait_for_modifiers_released(&self) {
+            let held =
+                KeyButMask::SHIFT | KeyButMask::CONTROL | KeyButMask::MOD1 | KeyButMask::MOD4;
+            let started = Instant::now();
+
+            while started.elapsed() < MODIFIER_RELEASE_TIMEOUT {
+                let Some(reply) = self
+                    .conn
+                    .query_pointer(self.root)
+                    .ok()
+                    .and_then(|cookie| cookie.reply().ok())
+                else {
+                    return;
+                };
+                if u16::from(reply.mask) & u16::from(held) == 0 {
+                    return;
+                }
+                thread::sleep(FOCUS_POLL_INTERVAL);
+            }
+        }
+
+        // Presses the modifiers, taps the key and releases the modifiers through XTest.
+        pub fn type_shortcut(&self, shortcut: &Shortcut) -> Result<(), String> {
+            if self
+                .conn
+                .extension_information(xtest::X11_EXTENSION_NAME)
+                .map_err(|e| e.to_string())?
+                .is_none()
+            {
+                return Err("The X server does not support XTest".to_string());
+            }
+
+            let key = key_symbol(shortcut.key)
+                .ok_or_else(|| format!("Cannot type {:?}", shortcut.key))?;
+            let modifiers = modifier_symbols(shortcut.mods)
+                .into_iter()
+                .map(|keysym| self.keycode(keysym))
+                .collect::<Result<Vec<_>, _>>()?;
+            let key = self.keycode(key)?;
+            self.wait_for_modifiers_released();
+
+            let mut events: Vec<(u8, Keycode)> = Vec::new();
+            events.extend(modifiers.iter().map(|code| (KEY_PRESS_EVENT, *code)));
+            events.push((KEY_PRESS_EVENT, key));
+            events.push((KEY_RELEASE_EVENT, key));
+            events.extend(
+                modifiers
+                    .iter()
+                    .rev()
+                    .map(|code| (KEY_RELEASE_EVENT, *code)),
+            );
+
+            for (type_, code) in events {
+                self.conn
+                    .xtest_fake_input(type_, code, CURRENT_TIME, self.root, 0, 0, 0)
+                    .map_err(|e| format!("Failed to send key: {}", e))?;
+            }
+            // a round trip makes sure the server has processed the keys
+            self.conn
+                .get_input_focus()
+                .map_err(|e| e.to_string())?
+                .reply()
+                .map_err(|e| format!("Failed to send keys: {}", e))?;
+            Ok(())
+        }
+
+        fn is_own_window(&self, window: Window) -> bool {
+            self.window_property(window, self.atoms._NET_WM_PID)
+                .is_some_and(|pid| pid == std::process::id())
+        }
+    }
+
+    // WM_CLASS holds the instance and class names, each NUL terminated.
+    pub fn parse_wm_class(value: &[u8]) -> Option<String> {
+        let mut names = value
+            .split(|b| *b == 0)
+            .map(|name| String::from_utf8_lossy(name).into_owned());
+        let instance = names.next().filter(|name| !name.is_empty());
+        names.next().filter(|name| !name.is_empty()).or(instance)
+    }
+
+    pub fn focused_app() -> Result<Option<FocusedApp>, String> {
+        let display = Display::connect()?;
+        let window = display.active_window()?;
+
+        // PointerRoot and None are not windows
+        if window <= 1 || display.is_own_window(window) {
+            return Ok(None);
+        }
+
+        Ok(Some(FocusedApp {
+            window: Some(window),
+            app: display.window_class(window),
+        }))
+    }
+
+    pub fn paste(window: Option<u32>, shortcut: &Shortcut) -> Result<(), String> {
+        let display = Display::connect()?;
+        if let Some(window) = window {
+            display.activate(window)?;
+        }
+        display.type_shortcut(shortcut)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod wayland {
+    use super::{evdev_code, key_symbol};
+    use std::fs::File;
+    use std::io::Write;
+    use std::os::fd::AsFd;
+    use std::time::Instant;
+    use tauri_plugin_global_shortcut::{Modifiers, Shortcut};
+    use wayland_client::protocol::{wl_registry, wl_seat::WlSeat};
+    use wayland_client::{Connection, Dispatch, QueueHandle, delegate_noop};
+    use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::{
+        zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1,
+        zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1,
+    };
+
+    // the compositor compiles the keymap, so the system's US layout can be included by name
+    const KEYMAP: &str = "xkb_keymap {
+    xkb_keycodes { include \"evdev+aliases(qwerty)\" };
+    xkb_types { include \"complete\" };
+    xkb_compat { include \"complete\" };
+    xkb_symbols { include \"pc+us+inet(evdev)\" };
+};
+";
+
+    // wl_keyboard keymap format
+    const XKB_V1: u32 = 1;
+
+    // modifier masks of the keymap above
+    const SHIFT_MASK: u32 = 1 << 0;
+    const CONTROL_MASK: u32 = 1 << 2;
+    const ALT_MASK: u32 = 1 << 3;
+    const SUPER_MASK: u32 = 1 << 6;
+
+    #[derive(Default)]
+    struct Globals {
+        seat: Option<WlSeat>,
+        manager: Option<ZwpVirtualKeyboardManagerV1>,
+    }
+
+    impl Dispatch<wl_registry::WlRegistry, ()> for Globals {
+        fn event(
+            state: &mut Self,
+            registry: &wl_registry::WlRegistry,
+            event: wl_registry::Event,
+            _: &(),
+            _: &Connection,
+            qh: &QueueHandle<Self>,
+        ) {
+            if let wl_registry::Event::Global {
+                name, interface, ..
+            } = event
+            {
+                match interface.as_str() {
+                    "wl_seat" if state.seat.is_none() => {
+                        state.seat = Some(registry.bind(name, 1, qh, ()));
+                    }
+                    "zwp_virtual_keyboard_manager_v1" => {
+                        state.manager = Some(registry.bind(name, 1, qh, ()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    delegate_noop!(Globals: ignore WlSeat);
+    delegate_noop!(Globals: ZwpVirtualKeyboardManagerV1);
+    delegate_noop!(Globals: ZwpVirtualKeyboardV1);
+
+    fn modifier_mask(modifiers: Modifiers) -> u32 {
+        [
+            (Modifiers::SHIFT, SHIFT_MASK),
+            (Modifiers::CONTROL, CONTROL_MASK),
+            (Modifiers::ALT, ALT_MASK),
+            (Modifiers::SUPER, SUPER_MASK),
+        ]
+        .into_iter()
+        .filter(|(modifier, _)| modifiers.contains(*modifier))
+        .fold(0, |mask, (_, bit)| mask | bit)
+    }
+
+    // The keymap is shared through a file the compositor maps, unlinked once opened.
+    fn keymap_file() -> Result<File, String> {
+        let dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir);
+        let path = dir.join(format!("yzzo-keymap-{}", std::process::id()));
+
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to create keymap: {}", e))?;
+        let _ = std::fs::remove_file(&path);
+
+        file.write_all(KEYMAP.as_bytes())
+            .and_then(|_| file.write_all(&[0]))
+            .map_err(|e| format!("Failed to write keymap: {}", e))?;
+        Ok(file)
+    }
+
+    // Types the shortcut through the virtual keyboard protocol, which wlroots compositors and
+    // KDE offer. GNOME does not.
+    pub fn type_shortcut(shortcut: &Shortcut) -> Result<(), String> {
+        let key = evdev_code(shortcut.key)
+            .filter(|_| key_symbol(shortcut.key).is_some())
+            .ok_or_else(|| format!("Cannot type {:?}", shortcut.key))?;
+
+        let conn = Connection::connect_to_env()
+            .map_err(|e| format!("Failed to connect to Wayland: {}", e))?;
+        let mut queue = conn.new_event_queue();
+        let qh = queue.handle();
+        let _registry = conn.display().get_registry(&qh, ());
+
+        let mut globals = Globals::default();
+        queue
+            .roundtrip(&mut globals)
+            .map_err(|e| format!("Failed to read Wayland globals: {}", e))?;
+        let (Some(seat), Some(manager)) = (globals.seat.take(), globals.manager.take()) else {
+            return Err("The compositor does not offer a virtual keyboard".to_string());
+        };
+
+        let keymap = keymap_file()?;
+        let keyboard = manager.create_virtual_keyboard(&seat, &qh, ());
+        keyboard.keymap(XKB_V1, keymap.as_fd(), KEYMAP.len() as u32 + 1);
+
+        let started = Instant::now();
+        let time = || started.elapsed().as_millis() as u32;
+        let mask = modifier_mask(shortcut.mods);
+
+        keyboard.modifiers(mask, 0, 0, 0);
+        keyboard.key(time(), key, 1);
+        keyboard.key(time(), key, 0);
+        keyboard.modifiers(0, 0, 0, 0);
+        keyboard.destroy();
+
+        queue
+            .roundtrip(&mut globals)
+            .map_err(|e| format!("Failed to send keys: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/auto_paste_test.rs"]
+mod auto_paste_test;
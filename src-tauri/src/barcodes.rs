@@ -0,0 +1,199 @@
+// Finds QR codes and EAN-13/UPC-A barcodes in copied images, so a screenshotted Wi-Fi code or
+// 2FA enrolment can be used without reaching for a phone.
+use crate::image_metadata;
+use crate::state::DbPool;
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+use rxing::{BarcodeFormat, DecodeHints, helpers};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use tauri::{AppHandle, Emitter, Wry};
+
+// larger images are scaled down first, codes in them are still several pixels per module
+const MAX_SCAN_DIMENSION: u32 = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeFormat {
+    QrCode,
+    Ean13,
+    UpcA,
+}
+
+// What a payload looks like, so the UI can offer the right action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadKind {
+    Text,
+    Url,
+    Wifi,
+    Otp,
+}
+
+// A decoded code, stored under `codes` in the image item metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedCode {
+    pub format: CodeFormat,
+    pub text: String,
+    pub kind: PayloadKind,
+}
+
+pub fn scan(image: &RgbaImage) -> Vec<DecodedCode> {
+    let (width, height) = image.dimensions();
+    let scaled;
+    let image = if width.max(height) > MAX_SCAN_DIMENSION {
+        let scale = MAX_SCAN_DIMENSION as f64 / width.max(height) as f64;
+        scaled = imageops::resize(
+            image,
+            ((width as f64 * scale) as u32).max(1),
+            ((height as f64 * scale) as u32).max(1),
+            FilterType::Triangle,
+        );
+        &scaled
+    } else {
+        image
+    };
+
+    let mut hints = DecodeHints {
+        PossibleFormats: Some(HashSet::from([
+            BarcodeFormat::QR_CODE,
+            BarcodeFormat::EAN_13,
+            BarcodeFormat::UPC_A,
+        ])),
+        ..DecodeHints::default()
+    };
+    // nothing found is reported as an error
+    let Ok(results) = helpers::detect_multiple_in_luma_with_hints(
+        luma(image),
+        image.width(),
+        image.height(),
+        &mut hints,
+    ) else {
+        return Vec::new();
+    };
+
+    let mut codes: Vec<DecodedCode> = Vec::new();
+    for result in results {
+        let format = match result.getBarcodeFormat() {
+            BarcodeFormat::QR_CODE => CodeFormat::QrCode,
+            BarcodeFormat::EAN_13 => CodeFormat::Ean13,
+            BarcodeFormat::UPC_A => CodeFormat::UpcA,
+            _ => continue,
+        };
+        let text = result.getText().to_string();
+        let code = DecodedCode {
+            format,
+            // product codes are just numbers
+            kind: match format {
+                CodeFormat::QrCode => classify(&text),
+                _ => PayloadKind::Text,
+            },
+            text,
+        };
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+// Luma of every pixel, with transparent pixels counted as white so codes on a transparent
+// background still read as dark on light.
+fn luma(image: &RgbaImage) -> Vec<u8> {
+    image
+        .pixels()
+        .map(|p| {
+            let [r, g, b, a] = p.0.map(u32::from);
+            let y = (299 * r + 587 * g + 114 * b) / 1000;
+            ((y * a + 255 * (255 - a)) / 255) as u8
+        })
+        .collect()
+}
+
+pub fn classify(text: &str) -> PayloadKind {
+    let lower = text.trim_start().to_ascii_lowercase();
+    if lower.starts_with("wifi:") {
+        PayloadKind::Wifi
+    } else if lower.starts_with("otpauth://") || lower.starts_with("otpauth-migration://") {
+        PayloadKind::Otp
+    } else if lower.starts_with("http://") || lower.starts_with("https://") {
+        PayloadKind::Url
+    } else {
+        PayloadKind::Text
+    }
+}
+
+pub fn scan_file(image_path: &Path) -> Result<Vec<DecodedCode>, String> {
+    let bytes = std::fs::read(image_path).map_err(|e| format!("Failed to read image: {}", e))?;
+    let image = image_metadata::decode_oriented(&bytes)?;
+    Ok(scan(&image.to_rgba8()))
+}
+
+// Stores the codes under `codes` in the item metadata, leaving the rest of it alone.
+pub async fn save_codes(db: &DbPool, id: i64, codes: &[DecodedCode]) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE items SET metadata = json_set(COALESCE(metadata, '{}'), '$.codes', json(?))
+         WHERE id = ?",
+    )
+    .bind(serde_json::json!(codes).to_string())
+    .bind(id)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to save codes: {}", e))?;
+    Ok(())
+}
+
+struct ScanJob {
+    id: i64,
+    image_path: PathBuf,
+}
+
+// Scans new image items on a worker thread, so capturing a copy does not wait on the decoder.
+#[derive(Clone)]
+pub struct ScanQueue(Sender<ScanJob>);
+
+impl ScanQueue {
+    pub fn spawn(app: AppHandle<Wry>, db: DbPool) -> Self {
+        let (sender, receiver) = mpsc::channel::<ScanJob>();
+        thread::spawn(move || {
+            for job in receiver {
+                tauri::async_runtime::block_on(process(&app, &db, job));
+            }
+        });
+
+        Self(sender)
+    }
+
+    pub fn enqueue(&self, id: i64, image_path: impl Into<PathBuf>) {
+        let _ = self.0.send(ScanJob {
+            id,
+            image_path: image_path.into(),
+        });
+    }
+}
+
+async fn process(app: &AppHandle<Wry>, db: &DbPool, job: ScanJob) {
+    let codes = match scan_file(&job.image_path) {
+        Ok(codes) => codes,
+        Err(e) => {
+            eprintln!("[X] Failed to scan item {} for codes: {}", job.id, e);
+            // an empty list keeps get_item_codes from trying again
+            Vec::new()
+        }
+    };
+
+    if let Err(e) = save_codes(db, job.id, &codes).await {
+        eprintln!("[X] {}", e);
+        return;
+    }
+    if !codes.is_empty() {
+        let _ = app.emit("item-updated", serde_json::json!({ "id": job.id }));
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/barcodes_test.rs"]
+mod barcodes_test;
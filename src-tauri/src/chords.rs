@@ -0,0 +1,199 @@
+// Two-step hotkeys like "Ctrl+Alt+V, 3". Only the leader is registered for good; pressing it
+// grabs the keys that can follow for a moment, and the first of them pressed runs its action.
+use crate::hotkey_actions::{self, HotkeyAction};
+use crate::state::AppState;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+// how long the keys after a leader stay grabbed
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Default)]
+struct Table {
+    // the keys that can follow each leader, by the leader's id
+    chords: HashMap<u32, Vec<(Shortcut, HotkeyAction)>>,
+    // the leader whose keys are grabbed, and which press of it that was
+    pending: Option<(u32, u64)>,
+    presses: u64,
+}
+
+impl Table {
+    fn end(&mut self) -> Vec<Shortcut> {
+        let Some((leader, _)) = self.pending.take() else {
+            return Vec::new();
+        };
+        self.keys(leader)
+    }
+
+    fn keys(&self, leader: u32) -> Vec<Shortcut> {
+        self.chords
+            .get(&leader)
+            .map(|keys| keys.iter().map(|(key, _)| *key).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Chords(Arc<Mutex<Table>>);
+
+impl Chords {
+    // Adds a chord, replacing the action it had. Returns whether the leader is new and has to be
+    // registered.
+    pub fn add(&self, leader: Shortcut, key: Shortcut, action: HotkeyAction) -> bool {
+        let mut table = self.0.lock().unwrap();
+        let keys = table.chords.entry(leader.id()).or_default();
+        let new_leader = keys.is_empty();
+
+        keys.retain(|(bound, _)| bound.id() != key.id());
+        keys.push((key, action));
+        new_leader
+    }
+
+    // Removes a chord. Returns whether its leader has no chords left and can be unregistered.
+    pub fn remove(&self, leader: Shortcut, key: Shortcut) -> bool {
+        let mut table = self.0.lock().unwrap();
+        let Some(keys) = table.chords.get_mut(&leader.id()) else {
+            return false;
+        };
+        keys.retain(|(bound, _)| bound.id() != key.id());
+
+        if keys.is_empty() {
+            table.chords.remove(&leader.id());
+            true
+        } else {
+            false
+        }
+    }
+
+    // Starts waiting for a key after `leader`. Returns which press this is and the keys to grab,
+    // nothing while another leader is still waiting.
+    pub fn start(&self, leader: u32) -> Option<(u64, Vec<Shortcut>)> {
+        let mut table = self.0.lock().unwrap();
+        if table.pending.is_some() {
+            return None;
+        }
+
+        let keys = table.keys(leader);
+        if keys.is_empty() {
+            return None;
+        }
+        table.presses += 1;
+        table.pending = Some((leader, table.presses));
+        Some((table.presses, keys))
+    }
+
+    // The action of a grabbed key. Ends the chord and returns the keys to release with it.
+    pub fn press(&self, key: &Shortcut) -> Option<(HotkeyAction, Vec<Shortcut>)> {
+        let mut table = self.0.lock().unwrap();
+        let (leader, _) = table.pending?;
+
+        let action = table
+            .chords
+            .get(&leader)?
+            .iter()
+            .find(|(bound, _)| bound.id() == key.id())
+            .map(|(_, action)| *action)?;
+        Some((action, table.end()))
+    }
+
+    // Ends the chord started by press `press` if no key ended it first, returning the keys to
+    // release.
+    pub fn expire(&self, press: u64) -> Vec<Shortcut> {
+        let mut table = self.0.lock().unwrap();
+        match table.pending {
+            Some((_, pending)) if pending == press => table.end(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn register(
+    app: &AppHandle,
+    leader: Shortcut,
+    key: Shortcut,
+    action: HotkeyAction,
+) -> Result<(), String> {
+    let chords = app.state::<AppState>().chords.clone();
+    println!(
+        "[I] Registering chord {:?}, {:?} for {:?}",
+        leader, key, action
+    );
+
+    if !chords.add(leader, key, action) {
+        return Ok(());
+    }
+    let result = app
+        .global_shortcut()
+        .on_shortcut(leader, move |app, leader, event| {
+            if let ShortcutState::Pressed = event.state() {
+                on_leader(app, leader.id());
+            }
+        });
+    if let Err(e) = result {
+        chords.remove(leader, key);
+        return Err(format!("Failed to register shortcut: {}", e));
+    }
+    Ok(())
+}
+
+pub fn unregister(app: &AppHandle, leader: Shortcut, key: Shortcut) {
+    let chords = app.state::<AppState>().chords.clone();
+    if chords.remove(leader, key) {
+        let _ = app.global_shortcut().unregister(leader);
+    }
+}
+
+// Shortcuts cannot be registered from a shortcut handler, the plugin holds its lock while the
+// handler runs, so the keys are grabbed and released from their own thread.
+fn on_leader(app: &AppHandle, leader: u32) {
+    let chords = app.state::<AppState>().chords.clone();
+    let Some((press, keys)) = chords.start(leader) else {
+        return;
+    };
+
+    let app = app.clone();
+    thread::spawn(move || {
+        for key in keys {
+            let result = app
+                .global_shortcut()
+                .on_shortcut(key, move |app, key, event| {
+                    if let ShortcutState::Pressed = event.state() {
+                        on_key(app, *key);
+                    }
+                });
+            if let Err(e) = result {
+                eprintln!("[X] Failed to grab {:?} after the leader: {}", key, e);
+            }
+        }
+
+        thread::sleep(CHORD_TIMEOUT);
+        release(&app, chords.expire(press));
+    });
+}
+
+fn on_key(app: &AppHandle, key: Shortcut) {
+    let chords = app.state::<AppState>().chords.clone();
+    let Some((action, keys)) = chords.press(&key) else {
+        return;
+    };
+
+    let app = app.clone();
+    thread::spawn(move || {
+        release(&app, keys);
+        hotkey_actions::run(&app, action);
+    });
+}
+
+fn release(app: &AppHandle, keys: Vec<Shortcut>) {
+    for key in keys {
+        let _ = app.global_shortcut().unregister(key);
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/chords_test.rs"]
+mod chords_test;
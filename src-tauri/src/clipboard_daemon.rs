@@ -0,0 +1,916 @@
+use crate::clipboard_formats::{ClipboardFormat, TEXT_PLAIN};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::Event;
+use x11rb::protocol::xfixes::{
+    ConnectionExt as _, SelectionEvent, SelectionEventMask, SelectionNotifyEvent as OwnerChange,
+};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ClientMessageEvent, ConnectionExt, CreateWindowAux,
+    EventMask, PropMode, Property, SELECTION_NOTIFY_EVENT, SelectionNotifyEvent,
+    SelectionRequestEvent, Timestamp, Window, WindowClass,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::{COPY_DEPTH_FROM_PARENT, COPY_FROM_PARENT, CURRENT_TIME, NONE};
+
+x11rb::atom_manager! {
+    pub Atoms: AtomsCookie {
+        CLIPBOARD,
+        CLIPBOARD_MANAGER,
+        SAVE_TARGETS,
+        TARGETS,
+        MULTIPLE,
+        TIMESTAMP,
+        DELETE,
+        INSERT_SELECTION,
+        INSERT_PROPERTY,
+        INCR,
+        MANAGER,
+        UTF8_STRING,
+        STRING,
+        TEXT,
+        YZZO_SELECTION,
+        YZZO_RESTORE,
+        YZZO_TIMESTAMP,
+    }
+}
+
+// how long to wait for the owner to answer a single conversion request
+const CONVERT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// how long to wait before setting the daemon up again after it stopped, doubled on every
+// failed attempt up to MAX_RECONNECT_DELAY
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+// upper bound for everything kept from a single copy, large payloads are dropped
+const MAX_SNAPSHOT_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum ClipboardDaemonError {
+    Connect(String),
+    Setup(String),
+    ManagerTaken,
+}
+
+impl std::fmt::Display for ClipboardDaemonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardDaemonError::Connect(e) => write!(f, "Failed to connect to X server: {}", e),
+            ClipboardDaemonError::Setup(e) => {
+                write!(f, "Failed to set up clipboard manager window: {}", e)
+            }
+            ClipboardDaemonError::ManagerTaken => {
+                write!(f, "Another clipboard manager is already running")
+            }
+        }
+    }
+}
+
+// A single representation offered by the clipboard owner.
+#[derive(Debug, Clone)]
+pub struct SnapshotEntry {
+    pub target: Atom,
+    pub type_: Atom,
+    pub format: u8,
+    pub data: Vec<u8>,
+}
+
+// Type, format and raw bytes of a converted selection.
+type Converted = (Atom, u8, Vec<u8>);
+
+// An outgoing INCR transfer to a requestor that could not take the data at once.
+struct OutgoingTransfer {
+    requestor: Window,
+    property: Atom,
+    type_: Atom,
+    format: u8,
+    data: Vec<u8>,
+    offset: usize,
+    finished: bool,
+}
+
+struct ClipboardDaemon {
+    conn: RustConnection,
+    window: Window,
+    atoms: Atoms,
+    snapshot: Vec<SnapshotEntry>,
+    pending: VecDeque<Event>,
+    transfers: Vec<OutgoingTransfer>,
+    max_chunk: usize,
+    restore: Receiver<Vec<ClipboardFormat>>,
+    // when the daemon last took the clipboard, answered to TIMESTAMP requests
+    acquired_at: Timestamp,
+}
+
+// Hands formats YZZO itself puts on the clipboard to the daemon thread. The daemon blocks on X
+// events, so every request is followed by a client message that wakes it up.
+struct Restorer {
+    sender: Sender<Vec<ClipboardFormat>>,
+    // a connection of its own, the daemon's is busy waiting for events
+    conn: RustConnection,
+    window: Window,
+    wake: Atom,
+}
+
+impl Restorer {
+    fn send(&self, formats: Vec<ClipboardFormat>) -> bool {
+        if self.sender.send(formats).is_err() {
+            return false;
+        }
+        let wake = ClientMessageEvent::new(32, self.window, self.wake, [0; 5]);
+        self.conn
+            .send_event(false, self.window, EventMask::NO_EVENT, wake)
+            .is_ok()
+            && self.conn.flush().is_ok()
+    }
+}
+
+// set while the daemon runs
+static RESTORE: Mutex<Option<Restorer>> = Mutex::new(None);
+
+fn is_x11_session() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_none() && std::env::var_os("DISPLAY").is_some()
+}
+
+// Returns whether a target only describes the selection itself and should not be stored.
+pub fn is_meta_target(atoms: &Atoms, target: Atom) -> bool {
+    target == NONE
+        || target == atoms.TARGETS
+        || target == atoms.MULTIPLE
+        || target == atoms.TIMESTAMP
+        || target == atoms.SAVE_TARGETS
+        || target == atoms.DELETE
+        || target == atoms.INSERT_SELECTION
+        || target == atoms.INSERT_PROPERTY
+}
+
+// Maps an X11 target name to the MIME type it is stored under, if it has one.
+pub fn mime_type_for_target(name: &str) -> Option<String> {
+    match name {
+        "UTF8_STRING" => Some(TEXT_PLAIN.to_string()),
+        _ if name.contains('/') => Some(name.to_string()),
+        // legacy encodings (STRING, TEXT, COMPOUND_TEXT) and private atoms
+        _ => None,
+    }
+}
+
+// Encodes UTF-8 text as Latin-1, which the `STRING` target is defined as. Returns None when
+// the text holds characters Latin-1 cannot represent.
+pub fn latin1(text: &[u8]) -> Option<Vec<u8>> {
+    std::str::from_utf8(text)
+        .ok()?
+        .chars()
+        .map(|c| u8::try_from(u32::from(c)).ok())
+        .collect()
+}
+
+// Keeps the last copied content alive on X11 after the source application exits.
+//
+// YZZO owns the `CLIPBOARD_MANAGER` selection, so well-behaved applications hand their
+// clipboard over through `SAVE_TARGETS` on exit. For everything else, every target is
+// snapshotted as soon as the clipboard changes hands and the selection is re-owned when
+// the owner disappears.
+pub fn start_clipboard_daemon() -> Result<(), ClipboardDaemonError> {
+    // the protocol only exists on X11, Wayland compositors bridge XWayland themselves
+    if !is_x11_session() {
+        println!("[I] Not running under X11, clipboard persistence disabled");
+        return Ok(());
+    }
+
+    let daemon = ClipboardDaemon::connect()?;
+    thread::spawn(move || supervise(daemon));
+
+    Ok(())
+}
+
+// Runs the daemon and sets it up again whenever it stops, e.g. when the X connection broke.
+// Until it is back, `restore_formats` refuses and writes go through arboard.
+fn supervise(mut daemon: ClipboardDaemon) {
+    println!("[V] Clipboard persistence enabled");
+    let mut delay = RECONNECT_DELAY;
+
+    loop {
+        let started = Instant::now();
+        if let Err(e) = daemon.run() {
+            eprintln!("[X] Clipboard persistence stopped: {}", e);
+        }
+        *RESTORE.lock().unwrap() = None;
+        // its connection keeps CLIPBOARD_MANAGER taken until it is closed
+        drop(daemon);
+
+        // the delay only keeps growing while restarted daemons fail right away
+        if started.elapsed() > MAX_RECONNECT_DELAY {
+            delay = RECONNECT_DELAY;
+        }
+        daemon = loop {
+            thread::sleep(delay);
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            match ClipboardDaemon::connect() {
+                Ok(daemon) => break daemon,
+                Err(e) => eprintln!("[X] Failed to restart clipboard persistence: {}", e),
+            }
+        };
+        println!("[V] Clipboard persistence restarted");
+    }
+}
+
+impl ClipboardDaemon {
+    // Connects to the X server, takes the CLIPBOARD_MANAGER selection and starts taking
+    // restore requests.
+    fn connect() -> Result<Self, ClipboardDaemonError> {
+        let (conn, screen_num) =
+            x11rb::connect(None).map_err(|e| ClipboardDaemonError::Connect(e.to_string()))?;
+
+        let setup_err = |e: &dyn std::fmt::Display| ClipboardDaemonError::Setup(e.to_string());
+
+        let atoms = Atoms::new(&conn)
+            .map_err(|e| setup_err(&e))?
+            .reply()
+            .map_err(|e| setup_err(&e))?;
+
+        let existing_manager = conn
+            .get_selection_owner(atoms.CLIPBOARD_MANAGER)
+            .map_err(|e| setup_err(&e))?
+            .reply()
+            .map_err(|e| setup_err(&e))?
+            .owner;
+        if existing_manager != NONE {
+            return Err(ClipboardDaemonError::ManagerTaken);
+        }
+
+        // XFixes reports every ownership change, even when the same window copies again
+        conn.xfixes_query_version(5, 0)
+            .map_err(|e| setup_err(&e))?
+            .reply()
+            .map_err(|e| setup_err(&e))?;
+
+        let root = conn.setup().roots[screen_num].root;
+        let window = conn.generate_id().map_err(|e| setup_err(&e))?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )
+        .map_err(|e| setup_err(&e))?;
+
+        conn.set_selection_owner(window, atoms.CLIPBOARD_MANAGER, CURRENT_TIME)
+            .map_err(|e| setup_err(&e))?;
+
+        conn.xfixes_select_selection_input(
+            window,
+            atoms.CLIPBOARD,
+            SelectionEventMask::SET_SELECTION_OWNER
+                | SelectionEventMask::SELECTION_WINDOW_DESTROY
+                | SelectionEventMask::SELECTION_CLIENT_CLOSE,
+        )
+        .map_err(|e| setup_err(&e))?;
+
+        // announce the new manager selection owner as described in ICCCM 2.8
+        let announcement = ClientMessageEvent::new(
+            32,
+            root,
+            atoms.MANAGER,
+            [CURRENT_TIME, atoms.CLIPBOARD_MANAGER, window, 0, 0],
+        );
+        conn.send_event(false, root, EventMask::STRUCTURE_NOTIFY, announcement)
+            .map_err(|e| setup_err(&e))?;
+        conn.flush().map_err(|e| setup_err(&e))?;
+
+        // leave some room for the request header
+        let max_chunk = conn.maximum_request_bytes().saturating_sub(1024).max(4096);
+
+        let (sender, restore) = mpsc::channel();
+        let (restore_conn, _) =
+            x11rb::connect(None).map_err(|e| ClipboardDaemonError::Connect(e.to_string()))?;
+        *RESTORE.lock().unwrap() = Some(Restorer {
+            sender,
+            conn: restore_conn,
+            window,
+            wake: atoms.YZZO_RESTORE,
+        });
+
+        Ok(ClipboardDaemon {
+            conn,
+            window,
+            atoms,
+            snapshot: Vec::new(),
+            pending: VecDeque::new(),
+            transfers: Vec::new(),
+            max_chunk,
+            restore,
+            acquired_at: CURRENT_TIME,
+        })
+    }
+
+    fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        // the clipboard may already hold content from before YZZO started
+        self.snapshot_clipboard()?;
+        self.conn.flush()?;
+
+        loop {
+            let event = match self.pending.pop_front() {
+                Some(event) => event,
+                None => self.conn.wait_for_event()?,
+            };
+            match event {
+                Event::SelectionRequest(request) => self.handle_selection_request(request)?,
+                Event::PropertyNotify(notify) if notify.state == Property::DELETE => {
+                    self.continue_transfer(notify.window, notify.atom)?
+                }
+                Event::XfixesSelectionNotify(change) => self.handle_owner_change(change)?,
+                // a YZZO_RESTORE wake-up, the formats come through the channel
+                _ => {}
+            }
+
+            while let Ok(formats) = self.restore.try_recv() {
+                self.serve_formats(formats)?;
+            }
+
+            self.conn.flush()?;
+        }
+    }
+
+    // Takes the clipboard over with formats restored from an item.
+    fn serve_formats(
+        &mut self,
+        formats: Vec<ClipboardFormat>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut snapshot = Vec::new();
+
+        for format in formats {
+            let target = self
+                .conn
+                .intern_atom(false, format.mime_type.as_bytes())?
+                .reply()?
+                .atom;
+
+            // terminals and older toolkits only ask for the legacy text targets, which are
+            // Latin-1 and left out for text that does not fit
+            if format.mime_type == TEXT_PLAIN {
+                snapshot.push(SnapshotEntry {
+                    target: self.atoms.UTF8_STRING,
+                    type_: self.atoms.UTF8_STRING,
+                    format: 8,
+                    data: format.data.clone(),
+                });
+                if let Some(latin1) = latin1(&format.data) {
+                    for legacy in [self.atoms.STRING, self.atoms.TEXT] {
+                        snapshot.push(SnapshotEntry {
+                            target: legacy,
+                            type_: self.atoms.STRING,
+                            format: 8,
+                            data: latin1.clone(),
+                        });
+                    }
+                }
+            }
+
+            snapshot.push(SnapshotEntry {
+                target,
+                type_: target,
+                format: 8,
+                data: format.data,
+            });
+        }
+
+        if snapshot.is_empty() {
+            return Ok(());
+        }
+        self.snapshot = snapshot;
+        self.take_ownership()
+    }
+
+    fn handle_owner_change(
+        &mut self,
+        change: OwnerChange,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if change.selection != self.atoms.CLIPBOARD {
+            return Ok(());
+        }
+
+        match change.subtype {
+            // the clipboard was cleared, nothing is left to keep alive
+            SelectionEvent::SET_SELECTION_OWNER if change.owner == NONE => {
+                self.snapshot.clear();
+                Ok(())
+            }
+            SelectionEvent::SET_SELECTION_OWNER if change.owner != self.window => {
+                self.snapshot_clipboard()
+            }
+            // the owner went away without handing its data over
+            SelectionEvent::SELECTION_WINDOW_DESTROY | SelectionEvent::SELECTION_CLIENT_CLOSE
+                if !self.snapshot.is_empty() =>
+            {
+                self.take_ownership()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn take_ownership(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let time = self.server_time()?;
+        self.conn
+            .set_selection_owner(self.window, self.atoms.CLIPBOARD, time)?;
+        self.acquired_at = time;
+        Ok(())
+    }
+
+    // Reads the server time off the PropertyNotify that appending nothing to a property of our
+    // own window produces. Falls back to CURRENT_TIME when the event does not arrive.
+    fn server_time(&mut self) -> Result<Timestamp, Box<dyn std::error::Error>> {
+        self.conn.change_property8(
+            PropMode::APPEND,
+            self.window,
+            self.atoms.YZZO_TIMESTAMP,
+            AtomEnum::STRING,
+            &[],
+        )?;
+        self.conn.flush()?;
+
+        let deadline = Instant::now() + CONVERT_TIMEOUT;
+        loop {
+            match wait_for_event(&self.conn, deadline)? {
+                Some(Event::PropertyNotify(notify))
+                    if notify.window == self.window && notify.atom == self.atoms.YZZO_TIMESTAMP =>
+                {
+                    return Ok(notify.time);
+                }
+                Some(other) => self.pending.push_back(other),
+                None => return Ok(CURRENT_TIME),
+            }
+        }
+    }
+
+    // Copies every target offered by the current clipboard owner. Whatever the previous owner
+    // offered is dropped first, so a failed snapshot never brings old content back.
+    fn snapshot_clipboard(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.snapshot.clear();
+        let Some((_, _, targets)) = self.convert(self.atoms.CLIPBOARD, self.atoms.TARGETS)? else {
+            return Ok(());
+        };
+
+        let targets: Vec<Atom> = targets
+            .chunks_exact(4)
+            .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .filter(|t| !is_meta_target(&self.atoms, *t))
+            .collect();
+
+        let mut snapshot = Vec::new();
+        let mut total = 0usize;
+
+        for target in targets {
+            if let Some((type_, format, data)) = self.convert(self.atoms.CLIPBOARD, target)? {
+                total += data.len();
+                if total > MAX_SNAPSHOT_BYTES {
+                    eprintln!(
+                        "[X] Clipboard content too large to keep, skipping remaining targets"
+                    );
+                    break;
+                }
+                snapshot.push(SnapshotEntry {
+                    target,
+                    type_,
+                    format,
+                    data,
+                });
+            }
+        }
+
+        self.snapshot = snapshot;
+        Ok(())
+    }
+
+    fn convert(
+        &mut self,
+        selection: Atom,
+        target: Atom,
+    ) -> Result<Option<Converted>, Box<dyn std::error::Error>> {
+        convert_selection(
+            &self.conn,
+            self.window,
+            &self.atoms,
+            selection,
+            target,
+            &mut self.pending,
+        )
+    }
+
+    fn handle_selection_request(
+        &mut self,
+        request: SelectionRequestEvent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // obsolete clients may leave the property empty, ICCCM says to use the target then
+        let property = if request.property == NONE {
+            request.target
+        } else {
+            request.property
+        };
+
+        let served = if request.selection == self.atoms.CLIPBOARD_MANAGER {
+            self.serve_manager(&request, property)?
+        } else if request.selection == self.atoms.CLIPBOARD && request.owner == self.window {
+            self.serve_clipboard(&request, property)?
+        } else {
+            false
+        };
+
+        let event = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: request.time,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: if served { property } else { NONE },
+        };
+        self.conn
+            .send_event(false, request.requestor, EventMask::NO_EVENT, event)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn serve_manager(
+        &mut self,
+        request: &SelectionRequestEvent,
+        property: Atom,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if request.target == self.atoms.TARGETS {
+            self.conn.change_property32(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                AtomEnum::ATOM,
+                &[self.atoms.TARGETS, self.atoms.SAVE_TARGETS],
+            )?;
+            return Ok(true);
+        }
+
+        if request.target != self.atoms.SAVE_TARGETS {
+            return Ok(false);
+        }
+
+        // the owner is about to exit and asks us to keep its clipboard
+        self.snapshot_clipboard()?;
+        if self.snapshot.is_empty() {
+            return Ok(false);
+        }
+        self.take_ownership()?;
+
+        // SAVE_TARGETS replies carry an empty property of type NULL
+        self.conn.change_property(
+            PropMode::REPLACE,
+            request.requestor,
+            property,
+            AtomEnum::NONE,
+            32,
+            0,
+            &[],
+        )?;
+        Ok(true)
+    }
+
+    fn serve_clipboard(
+        &mut self,
+        request: &SelectionRequestEvent,
+        property: Atom,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if request.target == self.atoms.TARGETS {
+            let mut targets = vec![self.atoms.TARGETS, self.atoms.TIMESTAMP];
+            targets.extend(self.snapshot.iter().map(|entry| entry.target));
+            self.conn.change_property32(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                AtomEnum::ATOM,
+                &targets,
+            )?;
+            return Ok(true);
+        }
+
+        if request.target == self.atoms.TIMESTAMP {
+            self.conn.change_property32(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                AtomEnum::INTEGER,
+                &[self.acquired_at],
+            )?;
+            return Ok(true);
+        }
+
+        let Some(entry) = self
+            .snapshot
+            .iter()
+            .find(|entry| entry.target == request.target)
+            .cloned()
+        else {
+            return Ok(false);
+        };
+
+        if entry.data.len() <= self.max_chunk {
+            let unit = usize::from(entry.format / 8).max(1);
+            self.conn.change_property(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                entry.type_,
+                entry.format,
+                (entry.data.len() / unit) as u32,
+                &entry.data,
+            )?;
+            return Ok(true);
+        }
+
+        // too large for a single request, start an INCR transfer
+        self.conn.change_window_attributes(
+            request.requestor,
+            &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )?;
+        self.conn.change_property32(
+            PropMode::REPLACE,
+            request.requestor,
+            property,
+            self.atoms.INCR,
+            &[entry.data.len() as u32],
+        )?;
+        self.transfers
+            .retain(|t| !(t.requestor == request.requestor && t.property == property));
+        self.transfers.push(OutgoingTransfer {
+            requestor: request.requestor,
+            property,
+            type_: entry.type_,
+            format: entry.format,
+            data: entry.data,
+            offset: 0,
+            finished: false,
+        });
+        Ok(true)
+    }
+
+    // Sends the next INCR chunk once the requestor deleted the previous one.
+    fn continue_transfer(
+        &mut self,
+        window: Window,
+        property: Atom,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(index) = self
+            .transfers
+            .iter()
+            .position(|t| t.requestor == window && t.property == property)
+        else {
+            return Ok(());
+        };
+
+        let transfer = &mut self.transfers[index];
+        if transfer.finished {
+            self.transfers.remove(index);
+            return Ok(());
+        }
+
+        // chunks must not split a single 16 or 32 bit element
+        let unit = usize::from(transfer.format / 8).max(1);
+        let chunk_size = self.max_chunk - self.max_chunk % unit;
+        let end = (transfer.offset + chunk_size).min(transfer.data.len());
+        let chunk = &transfer.data[transfer.offset..end];
+
+        self.conn.change_property(
+            PropMode::REPLACE,
+            window,
+            property,
+            transfer.type_,
+            transfer.format,
+            (chunk.len() / unit) as u32,
+            chunk,
+        )?;
+
+        // a zero-length chunk tells the requestor the transfer is complete
+        transfer.finished = chunk.is_empty();
+        transfer.offset = end;
+        self.conn.flush()?;
+        Ok(())
+    }
+}
+
+// Hands formats over to the daemon so it serves them as the clipboard content.
+// Returns false when the daemon is not running, e.g. outside of X11 or while it restarts.
+pub fn restore_formats(formats: Vec<ClipboardFormat>) -> bool {
+    RESTORE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|restorer| restorer.send(formats))
+}
+
+// Reads clipboard targets by MIME type, used by the watcher to capture every representation.
+pub struct SelectionReader {
+    conn: RustConnection,
+    window: Window,
+    atoms: Atoms,
+}
+
+impl SelectionReader {
+    pub fn connect() -> Option<Self> {
+        if !is_x11_session() {
+            return None;
+        }
+
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let atoms = Atoms::new(&conn).ok()?.reply().ok()?;
+        let root = conn.setup().roots[screen_num].root;
+        let window = conn.generate_id().ok()?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )
+        .ok()?;
+        conn.flush().ok()?;
+
+        Some(Self {
+            conn,
+            window,
+            atoms,
+        })
+    }
+
+    // Reads every MIME target of the clipboard accepted by `wanted`.
+    pub fn read_formats(&mut self, wanted: impl Fn(&str) -> bool) -> Vec<ClipboardFormat> {
+        self.try_read_formats(wanted).unwrap_or_else(|e| {
+            eprintln!("[X] Failed to read clipboard formats: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn try_read_formats(
+        &mut self,
+        wanted: impl Fn(&str) -> bool,
+    ) -> Result<Vec<ClipboardFormat>, Box<dyn std::error::Error>> {
+        // events meant for the daemon never reach this connection, so they can be dropped
+        let mut ignored = VecDeque::new();
+        let clipboard = self.atoms.CLIPBOARD;
+
+        let Some((_, _, targets)) = convert_selection(
+            &self.conn,
+            self.window,
+            &self.atoms,
+            clipboard,
+            self.atoms.TARGETS,
+            &mut ignored,
+        )?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut formats = Vec::new();
+        let mut total = 0usize;
+
+        for target in targets
+            .chunks_exact(4)
+            .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .filter(|t| !is_meta_target(&self.atoms, *t))
+        {
+            let name = self.conn.get_atom_name(target)?.reply()?.name;
+            let Some(mime_type) = mime_type_for_target(&String::from_utf8_lossy(&name)) else {
+                continue;
+            };
+            if !wanted(&mime_type)
+                || formats
+                    .iter()
+                    .any(|f: &ClipboardFormat| f.mime_type == mime_type)
+            {
+                continue;
+            }
+
+            if let Some((_, _, data)) = convert_selection(
+                &self.conn,
+                self.window,
+                &self.atoms,
+                clipboard,
+                target,
+                &mut ignored,
+            )? {
+                total += data.len();
+                if total > MAX_SNAPSHOT_BYTES {
+                    break;
+                }
+                formats.push(ClipboardFormat::new(mime_type, data));
+            }
+        }
+
+        Ok(formats)
+    }
+}
+
+// Requests `target` from the owner of `selection` and reads the reply, following INCR.
+// Unrelated events received while waiting are queued in `pending`.
+fn convert_selection(
+    conn: &RustConnection,
+    window: Window,
+    atoms: &Atoms,
+    selection: Atom,
+    target: Atom,
+    pending: &mut VecDeque<Event>,
+) -> Result<Option<Converted>, Box<dyn std::error::Error>> {
+    let property = atoms.YZZO_SELECTION;
+    conn.delete_property(window, property)?;
+    conn.convert_selection(window, selection, target, property, CURRENT_TIME)?;
+    conn.flush()?;
+
+    let deadline = Instant::now() + CONVERT_TIMEOUT;
+    let notify = loop {
+        match wait_for_event(conn, deadline)? {
+            Some(Event::SelectionNotify(e)) if e.requestor == window => break e,
+            Some(other) => pending.push_back(other),
+            None => return Ok(None),
+        }
+    };
+
+    if notify.property == NONE {
+        return Ok(None);
+    }
+
+    let reply = conn
+        .get_property(true, window, property, AtomEnum::ANY, 0, u32::MAX)?
+        .reply()?;
+
+    if reply.type_ != atoms.INCR {
+        return Ok(Some((reply.type_, reply.format, reply.value)));
+    }
+
+    // large data arrives in chunks, each one signalled by a new property value
+    let mut data = Vec::new();
+    let mut type_ = NONE;
+    let mut format = 8;
+    conn.flush()?;
+
+    loop {
+        let deadline = Instant::now() + CONVERT_TIMEOUT;
+        match wait_for_event(conn, deadline)? {
+            Some(Event::PropertyNotify(e))
+                if e.window == window && e.atom == property && e.state == Property::NEW_VALUE =>
+            {
+                let chunk = conn
+                    .get_property(true, window, property, AtomEnum::ANY, 0, u32::MAX)?
+                    .reply()?;
+                conn.flush()?;
+
+                if chunk.value.is_empty() {
+                    return Ok(Some((type_, format, data)));
+                }
+                type_ = chunk.type_;
+                format = chunk.format;
+                data.extend_from_slice(&chunk.value);
+
+                if data.len() > MAX_SNAPSHOT_BYTES {
+                    return Ok(None);
+                }
+            }
+            // the deletions made while reading our own property
+            Some(Event::PropertyNotify(e)) if e.window == window && e.atom == property => {}
+            // anything else, like the DELETE notifications driving our own INCR transfers
+            Some(other) => pending.push_back(other),
+            None => return Ok(None),
+        }
+    }
+}
+
+fn wait_for_event(
+    conn: &RustConnection,
+    deadline: Instant,
+) -> Result<Option<Event>, Box<dyn std::error::Error>> {
+    loop {
+        if let Some(event) = conn.poll_for_event()? {
+            return Ok(Some(event));
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/clipboard_daemon_test.rs"]
+mod clipboard_daemon_test;
@@ -0,0 +1,257 @@
+use crate::file_list;
+use crate::image_store;
+use arboard::Clipboard;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const TEXT_PLAIN: &str = "text/plain";
+pub const TEXT_HTML: &str = "text/html";
+pub const TEXT_RTF: &str = "text/rtf";
+pub const IMAGE_PNG: &str = "image/png";
+pub const IMAGE_SVG: &str = "image/svg+xml";
+pub const TEXT_URI_LIST: &str = "text/uri-list";
+pub const GNOME_COPIED_FILES: &str = "x-special/gnome-copied-files";
+
+// how many captures may wait for the frontend to store them
+const MAX_PENDING: usize = 16;
+// how long after writing the watcher treats matching content as the app's own
+const SELF_WRITE_WINDOW: Duration = Duration::from_secs(3);
+
+// A single representation of a clipboard item, e.g. its HTML or plain text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardFormat {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl ClipboardFormat {
+    pub fn new(mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            data: data.into(),
+        }
+    }
+}
+
+// Adds a format unless one with the same MIME type was already captured.
+pub fn push_format(formats: &mut Vec<ClipboardFormat>, format: ClipboardFormat) {
+    if !formats.iter().any(|f| f.mime_type == format.mime_type) {
+        formats.push(format);
+    }
+}
+
+pub fn find_format<'a>(formats: &'a [ClipboardFormat], mime_type: &str) -> Option<&'a [u8]> {
+    formats
+        .iter()
+        .find(|f| f.mime_type == mime_type)
+        .map(|f| f.data.as_slice())
+}
+
+// The representation the UI should render for an item.
+pub fn preferred_format(item_type: &str, formats: &[ClipboardFormat]) -> Option<String> {
+    if item_type == "image" {
+        return Some(IMAGE_PNG.to_string());
+    }
+    if item_type == "files" {
+        return Some(TEXT_URI_LIST.to_string());
+    }
+
+    [TEXT_PLAIN, TEXT_HTML, TEXT_RTF]
+        .iter()
+        .find(|mime| find_format(formats, mime).is_some())
+        .map(|mime| mime.to_string())
+        .or_else(|| formats.first().map(|f| f.mime_type.clone()))
+        .or_else(|| Some(TEXT_PLAIN.to_string()))
+}
+
+// Reads every representation offered alongside a copy.
+//
+// Image pixels are left out since the watcher keeps them as a file, SVG is read so the watcher
+// can keep it instead of its rendering. Text and HTML come from arboard on every platform; on
+// X11 any other MIME target (RTF, SVG, application data, ...) is read straight from the
+// selection owner.
+pub struct FormatReader {
+    #[cfg(target_os = "linux")]
+    x11: Option<crate::clipboard_daemon::SelectionReader>,
+}
+
+impl FormatReader {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            x11: crate::clipboard_daemon::SelectionReader::connect(),
+        }
+    }
+
+    pub fn read(&mut self, clipboard: &mut Clipboard, text: Option<&str>) -> Vec<ClipboardFormat> {
+        let mut formats = Vec::new();
+
+        if let Some(text) = text {
+            push_format(&mut formats, ClipboardFormat::new(TEXT_PLAIN, text));
+        }
+
+        if let Ok(html) = clipboard.get().html() {
+            push_format(&mut formats, ClipboardFormat::new(TEXT_HTML, html));
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(reader) = self.x11.as_mut() {
+            for format in
+                reader.read_formats(|mime| !mime.starts_with("image/") || mime == IMAGE_SVG)
+            {
+                push_format(&mut formats, format);
+            }
+        }
+
+        formats
+    }
+}
+
+// Puts every representation of an item back on the clipboard at once.
+//
+// On X11 the clipboard daemon serves all formats. Elsewhere arboard can only offer a file
+// list, an image, or HTML together with its plain text fallback.
+pub fn write_formats(formats: &[ClipboardFormat]) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if crate::clipboard_daemon::restore_formats(formats.to_vec()) {
+        return Ok(());
+    }
+
+    let text = |mime: &str| find_format(formats, mime).map(String::from_utf8_lossy);
+
+    let mut clipboard =
+        Clipboard::new().map_err(|e| format!("Failed to access clipboard: {}", e))?;
+
+    let files = text(TEXT_URI_LIST).and_then(|uris| crate::file_list::parse_uri_list(&uris));
+
+    if let Some(paths) = files {
+        clipboard
+            .set()
+            .file_list(&paths)
+            .map_err(|e| format!("Failed to set clipboard files: {}", e))
+    } else if let Some(png) = find_format(formats, IMAGE_PNG) {
+        let rgba = image::load_from_memory(png)
+            .map_err(|e| format!("Failed to decode image: {}", e))?
+            .to_rgba8();
+        let (width, height) = rgba.dimensions();
+
+        clipboard
+            .set_image(arboard::ImageData {
+                width: width as usize,
+                height: height as usize,
+                bytes: rgba.into_raw().into(),
+            })
+            .map_err(|e| format!("Failed to set clipboard image: {}", e))
+    } else if let Some(html) = text(TEXT_HTML) {
+        clipboard
+            .set_html(html, text(TEXT_PLAIN))
+            .map_err(|e| format!("Failed to set clipboard HTML: {}", e))
+    } else if let Some(plain) = text(TEXT_PLAIN) {
+        clipboard
+            .set_text(plain)
+            .map_err(|e| format!("Failed to set clipboard text: {}", e))
+    } else {
+        Err("Item has no format that can be restored".to_string())
+    }
+}
+
+pub fn clear_clipboard() -> Result<(), String> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.clear())
+        .map_err(|e| format!("Failed to clear clipboard: {}", e))
+}
+
+// Content of a capture and the formats offered with it.
+type PendingEntry = (String, Vec<ClipboardFormat>);
+
+// Formats captured by the watcher, waiting for `add_item` to store them with their item.
+#[derive(Clone, Default)]
+pub struct PendingFormats(Arc<Mutex<VecDeque<PendingEntry>>>);
+
+impl PendingFormats {
+    pub fn insert(&self, content: String, formats: Vec<ClipboardFormat>) {
+        let mut pending = self.0.lock().unwrap();
+        pending.retain(|(c, _)| *c != content);
+        if pending.len() >= MAX_PENDING {
+            pending.pop_front();
+        }
+        pending.push_back((content, formats));
+    }
+
+    pub fn take(&self, content: &str) -> Vec<ClipboardFormat> {
+        let mut pending = self.0.lock().unwrap();
+        match pending.iter().position(|(c, _)| c == content) {
+            Some(index) => pending.remove(index).map(|(_, f)| f).unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+}
+
+// What the app last put on the clipboard, keyed the way the watcher sees it: the plain text,
+// the display content and paths of a file list, and the pixel hash of the PNG.
+struct SelfWrite {
+    texts: Vec<String>,
+    image_hash: Option<u64>,
+    at: Instant,
+}
+
+// Lets the watcher recognise content the app wrote itself, such as an item copied back from
+// the history, so it is not captured again as a new copy.
+#[derive(Clone, Default)]
+pub struct SelfWrites(Arc<Mutex<Option<SelfWrite>>>);
+
+impl SelfWrites {
+    // Puts the formats on the clipboard as the app's own write.
+    pub fn write(&self, formats: &[ClipboardFormat]) -> Result<(), String> {
+        self.expect(formats);
+        write_formats(formats)
+    }
+
+    pub fn expect(&self, formats: &[ClipboardFormat]) {
+        let text = |mime: &str| find_format(formats, mime).map(String::from_utf8_lossy);
+
+        let mut texts: Vec<String> = text(TEXT_PLAIN)
+            .into_iter()
+            .map(|t| t.into_owned())
+            .collect();
+        if let Some(paths) = text(TEXT_URI_LIST).and_then(|uris| file_list::parse_uri_list(&uris)) {
+            texts.push(file_list::display_content(&paths));
+            // a single copied image file is keyed by its path
+            texts.extend(paths.iter().map(|p| p.to_string_lossy().into_owned()));
+        }
+
+        let image_hash = find_format(formats, IMAGE_PNG)
+            .and_then(|png| image::load_from_memory(png).ok())
+            .map(|image| {
+                let rgba = image.to_rgba8();
+                image_store::pixel_hash(rgba.width(), rgba.height(), rgba.as_raw())
+            });
+
+        *self.0.lock().unwrap() = Some(SelfWrite {
+            texts,
+            image_hash,
+            at: Instant::now(),
+        });
+    }
+
+    pub fn is_own_text(&self, text: &str) -> bool {
+        self.matches(|write| write.texts.iter().any(|t| t == text))
+    }
+
+    pub fn is_own_image(&self, hash: u64) -> bool {
+        self.matches(|write| write.image_hash == Some(hash))
+    }
+
+    fn matches(&self, predicate: impl Fn(&SelfWrite) -> bool) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|write| write.at.elapsed() < SELF_WRITE_WINDOW && predicate(write))
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/clipboard_formats_test.rs"]
+mod clipboard_formats_test;
@@ -1,11 +1,35 @@
+use crate::clipboard_formats::{
+    ClipboardFormat, FormatReader, IMAGE_SVG, PendingFormats, TEXT_PLAIN, find_format, push_format,
+};
+use crate::color;
+use crate::file_list;
+use crate::image_store::{self, StoredImage};
+use crate::state::AppState;
+use crate::svg_image;
 use arboard::Clipboard;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, Wry};
 
+// While paused copies are still tracked, so they are not captured once capture resumes.
+static CAPTURE_PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_capture_paused() -> bool {
+    CAPTURE_PAUSED.load(Ordering::Relaxed)
+}
+
+pub fn set_capture_paused(app: &AppHandle, paused: bool) {
+    CAPTURE_PAUSED.store(paused, Ordering::Relaxed);
+    println!(
+        "[I] Clipboard capture {}",
+        if paused { "paused" } else { "resumed" }
+    );
+    let _ = app.emit("capture-paused", serde_json::json!({ "paused": paused }));
+}
+
 #[derive(Debug)]
 pub enum ClipboardWatcherError {
     ClipboardInit(String),
@@ -56,6 +80,9 @@ pub fn start_clipboard_watcher(app_handle: AppHandle<Wry>) -> Result<(), Clipboa
     let last_text_clone = last_text.clone();
     let last_image_hash = Arc::new(Mutex::new(0u64));
     let last_image_hash_clone = last_image_hash.clone();
+    let pending_formats = app_handle.state::<AppState>().pending_formats.clone();
+    let image_storage = app_handle.state::<AppState>().image_storage.clone();
+    let self_writes = app_handle.state::<AppState>().self_writes.clone();
 
     thread::spawn(move || {
         let mut clipboard = match Clipboard::new() {
@@ -65,6 +92,7 @@ pub fn start_clipboard_watcher(app_handle: AppHandle<Wry>) -> Result<(), Clipboa
                 return;
             }
         };
+        let mut format_reader = FormatReader::new();
 
         // Initialize with current clipboard content to avoid treating
         // pre-existing content as a new copy on first iteration
@@ -72,13 +100,17 @@ pub fn start_clipboard_watcher(app_handle: AppHandle<Wry>) -> Result<(), Clipboa
             let mut last = last_text_clone.lock().unwrap();
             *last = current_text;
         }
+        if let Ok(current_files) = clipboard.get().file_list() {
+            let mut last = last_text_clone.lock().unwrap();
+            *last = file_list::display_content(&current_files);
+        }
         if let Ok(current_image) = clipboard.get_image() {
-            let mut hasher = DefaultHasher::new();
-            current_image.bytes.hash(&mut hasher);
-            current_image.width.hash(&mut hasher);
-            current_image.height.hash(&mut hasher);
             let mut last_hash = last_image_hash_clone.lock().unwrap();
-            *last_hash = hasher.finish();
+            *last_hash = image_store::pixel_hash(
+                current_image.width as u32,
+                current_image.height as u32,
+                &current_image.bytes,
+            );
         }
 
         // Wait for frontend to mount and set up event listeners
@@ -89,182 +121,157 @@ pub fn start_clipboard_watcher(app_handle: AppHandle<Wry>) -> Result<(), Clipboa
             let image_result = clipboard.get_image();
             let text_result = clipboard.get_text();
 
-            // prioritize image data over HTML text representing images
-            if let Ok(image) = image_result {
-                // calculate hash of image data to detect duplicates
-                let mut hasher = DefaultHasher::new();
-                image.bytes.hash(&mut hasher);
-                image.width.hash(&mut hasher);
-                image.height.hash(&mut hasher);
-                let current_hash = hasher.finish();
-
-                let mut last_hash = last_image_hash_clone.lock().unwrap();
+            // copied files and folders, either offered as a file list or as file:// text
+            let copied_paths = clipboard
+                .get()
+                .file_list()
+                .ok()
+                .filter(|paths| !paths.is_empty())
+                .or_else(|| {
+                    text_result
+                        .as_ref()
+                        .ok()
+                        .and_then(|text| file_list::parse_uri_list(text))
+                });
+
+            // a single image file is captured as an image item rather than a file list
+            let (image_file, copied_files) = match copied_paths {
+                Some(paths) if file_list::is_single_image(&paths) && paths[0].exists() => {
+                    (paths.into_iter().next(), None)
+                }
+                paths => (None, paths),
+            };
 
-                // only process if this is a new/different image
-                if *last_hash != current_hash {
-                    *last_hash = current_hash;
-                    let app_data_dir = match app_handle.path().app_data_dir() {
-                        Ok(dir) => dir,
-                        Err(e) => {
-                            eprintln!("[X] Failed to get app data directory: {}", e);
-                            continue;
-                        }
-                    };
+            if let Some(paths) = copied_files {
+                let content = file_list::display_content(&paths);
+                let mut last = last_text_clone.lock().unwrap();
 
-                    let images_dir = app_data_dir.join("images");
-                    if let Err(e) = std::fs::create_dir_all(&images_dir) {
-                        eprintln!("[X] Failed to create images directory: {}", e);
-                        continue;
-                    }
+                if *last != content {
+                    *last = content.clone();
+                    let mut last_hash = last_image_hash_clone.lock().unwrap();
+                    *last_hash = 0;
 
-                    // generate unique filename using hash for consistent naming
-                    let filename = format!("{}.png", current_hash);
-                    let file_path = images_dir.join(&filename);
-
-                    // convert ImageData to image crate format and save
-                    let img_buffer = image::RgbaImage::from_raw(
-                        image.width as u32,
-                        image.height as u32,
-                        image.bytes.into_owned(),
-                    );
-
-                    if let Some(img) = img_buffer {
-                        // only save if file doesn't already exist (deduplication at file level)
-                        if !file_path.exists() {
-                            let _ = img.save(&file_path);
+                    // items copied back from the history are not new copies
+                    if !self_writes.is_own_text(&content) && !is_capture_paused() {
+                        // generated formats come first so a "cut" is always restored as a copy
+                        let mut formats = file_list::clipboard_formats(&paths);
+                        for format in format_reader.read(&mut clipboard, None) {
+                            push_format(&mut formats, format);
                         }
+                        pending_formats.insert(content.clone(), formats);
 
-                        let metadata = serde_json::json!({
-                            "width": image.width,
-                            "height": image.height,
-                            "format": "png",
-                            "hash": current_hash.to_string(),
-                            "size": std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0)
-                        });
+                        let metadata =
+                            serde_json::to_string(&file_list::describe(&paths)).unwrap_or_default();
 
                         let _ = app_handle.emit(
                             "clipboard-changed",
                             serde_json::json!({
-                                "type": "image",
-                                "content": filename,
-                                "file_path": file_path.to_str().unwrap(),
-                                "metadata": metadata.to_string()
+                                "type": "files",
+                                "content": content,
+                                "metadata": metadata
                             }),
                         );
-
-                        // clear last text so we don't emit duplicate on next text
-                        let mut last = last_text_clone.lock().unwrap();
-                        *last = String::new();
                     }
                 }
-            } else if let Ok(text) = text_result {
-                // check if it's a file URL pointing to an image
-                if text.starts_with("file://") {
-                    let file_path = text.trim_start_matches("file://");
-                    // URL decode the path (spaces are %20, etc.)
-                    let decoded_path = urlencoding::decode(file_path).unwrap_or_default();
-                    let path = std::path::Path::new(decoded_path.as_ref());
-
-                    // check if it's an image file
-                    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                        let ext_lower = ext.to_lowercase();
-                        if matches!(
-                            ext_lower.as_str(),
-                            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "tiff" | "tif"
-                        ) {
-                            if path.exists() {
-                                // load the image and process it
-                                if let Ok(img) = image::open(path) {
-                                    let rgba = img.to_rgba8();
-                                    let (width, height) = rgba.dimensions();
-
-                                    // calculate hash for deduplication
-                                    let mut hasher = DefaultHasher::new();
-                                    rgba.as_raw().hash(&mut hasher);
-                                    width.hash(&mut hasher);
-                                    height.hash(&mut hasher);
-                                    let current_hash = hasher.finish();
-
-                                    let mut last_hash = last_image_hash_clone.lock().unwrap();
-
-                                    if *last_hash != current_hash {
-                                        *last_hash = current_hash;
-
-                                        let app_data_dir = match app_handle.path().app_data_dir() {
-                                            Ok(dir) => dir,
-                                            Err(e) => {
-                                                eprintln!(
-                                                    "[X] Failed to get app data directory: {}",
-                                                    e
-                                                );
-                                                continue;
-                                            }
-                                        };
-
-                                        let images_dir = app_data_dir.join("images");
-                                        if let Err(e) = std::fs::create_dir_all(&images_dir) {
-                                            eprintln!(
-                                                "[X] Failed to create images directory: {}",
-                                                e
-                                            );
-                                            continue;
-                                        }
-
-                                        let filename = format!("{}.png", current_hash);
-                                        let dest_path = images_dir.join(&filename);
-
-                                        if !dest_path.exists() {
-                                            let _ = rgba.save(&dest_path);
-                                        }
-
-                                        let metadata = serde_json::json!({
-                                            "width": width,
-                                            "height": height,
-                                            "format": "png",
-                                            "hash": current_hash.to_string(),
-                                            "size": std::fs::metadata(&dest_path).map(|m| m.len()).unwrap_or(0)
-                                        });
-
-                                        let _ = app_handle.emit(
-                                            "clipboard-changed",
-                                            serde_json::json!({
-                                                "type": "image",
-                                                "content": filename,
-                                                "file_path": dest_path.to_str().unwrap(),
-                                                "metadata": metadata.to_string()
-                                            }),
-                                        );
-
-                                        let mut last = last_text_clone.lock().unwrap();
-                                        *last = String::new();
-                                    }
-                                }
-                                continue;
+            // a copied image file keeps its original encoding instead of being re-encoded
+            } else if let Some(source) = image_file {
+                let source_key = source.to_string_lossy().into_owned();
+                let mut last = last_text_clone.lock().unwrap();
+
+                if *last != source_key {
+                    *last = source_key.clone();
+
+                    if !self_writes.is_own_text(&source_key) && !is_capture_paused() {
+                        match image_store::save_original(&images_dir, &source, &image_storage.get())
+                        {
+                            Ok(stored) => {
+                                let mut last_hash = last_image_hash_clone.lock().unwrap();
+                                *last_hash = stored.hash;
+                                emit_image(
+                                    &app_handle,
+                                    &pending_formats,
+                                    format_reader.read(&mut clipboard, None),
+                                    stored,
+                                );
                             }
+                            Err(e) => eprintln!("[X] Failed to store copied image: {}", e),
                         }
                     }
                 }
+            // prioritize image data over HTML text representing images
+            } else if let Ok(image) = image_result {
+                let current_hash =
+                    image_store::pixel_hash(image.width as u32, image.height as u32, &image.bytes);
 
-                // no image data, process text
-                // but skip HTML content that represents images
-                let is_html_image = text.trim().starts_with("<meta")
-                    || text.trim().starts_with("<!DOCTYPE")
-                    || (text.contains("<img") && text.contains("src="));
+                let mut last_hash = last_image_hash_clone.lock().unwrap();
 
-                if !is_html_image {
-                    // clear image hash when text is detected
-                    let mut last_hash = last_image_hash_clone.lock().unwrap();
-                    *last_hash = 0;
-                    let mut last = last_text_clone.lock().unwrap();
-                    if *last != text {
-                        *last = text.clone();
-                        let _ = app_handle.emit(
-                            "clipboard-changed",
-                            serde_json::json!({
-                                "type": "text",
-                                "content": text
-                            }),
-                        );
+                // only process if this is a new/different image
+                if *last_hash != current_hash {
+                    *last_hash = current_hash;
+
+                    if self_writes.is_own_image(current_hash) || is_capture_paused() {
+                        last_text_clone.lock().unwrap().clear();
+                    } else {
+                        let formats = format_reader.read(&mut clipboard, None);
+
+                        // vector editors offer a rendering next to the SVG, which is kept
+                        // instead; raw pixels have no original encoding, so they are stored as PNG
+                        let stored = match store_copied_svg(&images_dir, &formats) {
+                            Some(stored) => Ok(stored),
+                            None => image_store::save_pixels(
+                                &images_dir,
+                                image.width as u32,
+                                image.height as u32,
+                                image.bytes.into_owned(),
+                                &image_storage.get(),
+                            ),
+                        };
+
+                        match stored {
+                            Ok(stored) => {
+                                emit_image(&app_handle, &pending_formats, formats, stored);
+
+                                // clear last text so we don't emit duplicate on next text
+                                let mut last = last_text_clone.lock().unwrap();
+                                *last = String::new();
+                            }
+                            Err(e) => eprintln!("[X] Failed to store clipboard image: {}", e),
+                        }
+                    }
+                }
+            } else if let Ok(text) = text_result {
+                // no image data, process text
+                // clear image hash when text is detected
+                let mut last_hash = last_image_hash_clone.lock().unwrap();
+                *last_hash = 0;
+                let mut last = last_text_clone.lock().unwrap();
+                if *last != text {
+                    *last = text.clone();
+
+                    if !self_writes.is_own_text(&text) && !is_capture_paused() {
+                        // keep HTML, RTF and any other representation next to the plain text
+                        let formats = format_reader.read(&mut clipboard, Some(&text));
+
+                        // SVG markup, e.g. from Figma's "Copy as SVG", becomes an image item
+                        if let Some(stored) = store_copied_svg(&images_dir, &formats) {
+                            emit_image(&app_handle, &pending_formats, formats, stored);
+                        } else {
+                            pending_formats.insert(text.clone(), formats);
+
+                            // a copied colour value becomes a colour item with its conversions
+                            let event = match color::detect(&text) {
+                                Some(value) => serde_json::json!({
+                                    "type": "color",
+                                    "content": text,
+                                    "metadata": serde_json::json!(value).to_string()
+                                }),
+                                None => serde_json::json!({
+                                    "type": "text",
+                                    "content": text
+                                }),
+                            };
+                            let _ = app_handle.emit("clipboard-changed", event);
+                        }
                     }
                 }
             }
@@ -274,3 +281,35 @@ pub fn start_clipboard_watcher(app_handle: AppHandle<Wry>) -> Result<(), Clipboa
 
     Ok(())
 }
+
+// An SVG offered as `image/svg+xml`, or as markup in the plain text, stored as an image.
+// Markup that does not parse stays text.
+fn store_copied_svg(images_dir: &Path, formats: &[ClipboardFormat]) -> Option<StoredImage> {
+    let svg = find_format(formats, IMAGE_SVG)
+        .or_else(|| find_format(formats, TEXT_PLAIN).filter(|text| svg_image::is_svg(text)))?;
+
+    image_store::save_svg(images_dir, svg)
+        .map_err(|e| eprintln!("[X] Failed to store copied SVG: {}", e))
+        .ok()
+}
+
+fn emit_image(
+    app_handle: &AppHandle<Wry>,
+    pending_formats: &PendingFormats,
+    mut formats: Vec<ClipboardFormat>,
+    stored: StoredImage,
+) {
+    // an SVG is restored from the image file
+    formats.retain(|f| f.mime_type != IMAGE_SVG);
+    pending_formats.insert(stored.file_name.clone(), formats);
+
+    let _ = app_handle.emit(
+        "clipboard-changed",
+        serde_json::json!({
+            "type": "image",
+            "content": stored.file_name,
+            "file_path": stored.path.to_string_lossy(),
+            "metadata": stored.metadata.to_string()
+        }),
+    );
+}
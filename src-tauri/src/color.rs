@@ -0,0 +1,474 @@
+// Recognises CSS colour values copied as text and finds the dominant colours of images.
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+use serde::{Deserialize, Serialize};
+
+pub const PALETTE_SIZE: usize = 5;
+// images are sampled down to this size, plenty to find their main colours
+const PALETTE_SAMPLE_SIZE: u32 = 64;
+// OKLab distance under which two colours are treated as the same swatch
+const PALETTE_MIN_DISTANCE: f64 = 0.08;
+// how close a colour has to be to match a search, in OKLab distance
+pub const MATCH_DISTANCE: f64 = 0.08;
+// longer text is never a single colour value
+const MAX_COLOR_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorNotation {
+    Hex,
+    Rgb,
+    Hsl,
+    Oklch,
+}
+
+// A copied colour in every notation, stored as the metadata of `color` items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorValue {
+    // the notation it was copied in
+    pub notation: ColorNotation,
+    pub hex: String,
+    pub rgb: String,
+    pub hsl: String,
+    pub oklch: String,
+    pub alpha: f64,
+    // false when an oklch() colour lies outside sRGB and was clipped for the other notations
+    pub in_gamut: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaletteColor {
+    pub hex: String,
+    // share of the opaque pixels closest to this colour
+    pub proportion: f64,
+}
+
+// sRGB channels and alpha, all within 0..=1
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rgba {
+    r: f64,
+    g: f64,
+    b: f64,
+    alpha: f64,
+}
+
+// Whether copied text is a colour value worth a colour item. Short hex forms made of digits
+// only, like "#123" or "#1234", are far more often issue references than colours.
+pub fn detect(text: &str) -> Option<ColorValue> {
+    if let Some(hex) = text.trim().strip_prefix('#')
+        && matches!(hex.len(), 3 | 4)
+        && hex.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    parse(text)
+}
+
+// Parses text that is exactly one colour value: `#rgb`, `#rrggbb` (with optional alpha),
+// `rgb()`/`rgba()`, `hsl()`/`hsla()` or `oklch()`, in comma or space separated syntax.
+pub fn parse(text: &str) -> Option<ColorValue> {
+    let text = text.trim().to_ascii_lowercase();
+    if text.is_empty() || text.len() > MAX_COLOR_LENGTH {
+        return None;
+    }
+
+    if let Some(hex) = text.strip_prefix('#') {
+        return Some(describe(parse_hex(hex)?, ColorNotation::Hex, true));
+    }
+
+    let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+    let args: Vec<&str> = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|a| !a.is_empty())
+        .collect();
+    if !(3..=4).contains(&args.len()) {
+        return None;
+    }
+    let alpha = match args.get(3) {
+        Some(alpha) => parse_alpha(alpha)?,
+        None => 1.0,
+    };
+
+    match name.trim() {
+        "rgb" | "rgba" => {
+            let color = Rgba {
+                r: parse_channel(args[0])?,
+                g: parse_channel(args[1])?,
+                b: parse_channel(args[2])?,
+                alpha,
+            };
+            Some(describe(color, ColorNotation::Rgb, true))
+        }
+        "hsl" | "hsla" => {
+            let (r, g, b) = hsl_to_rgb(
+                parse_hue(args[0])?,
+                parse_percent(args[1])?,
+                parse_percent(args[2])?,
+            );
+            Some(describe(Rgba { r, g, b, alpha }, ColorNotation::Hsl, true))
+        }
+        "oklch" => {
+            let lightness = match args[0].strip_suffix('%') {
+                Some(percent) => parse_number(percent)? / 100.0,
+                None => parse_number(args[0])?,
+            }
+            .clamp(0.0, 1.0);
+            // 100% chroma is 0.4 in CSS
+            let chroma = match args[1].strip_suffix('%') {
+                Some(percent) => parse_number(percent)? / 100.0 * 0.4,
+                None => parse_number(args[1])?,
+            }
+            .max(0.0);
+            let hue = parse_hue(args[2])?;
+
+            let (r, g, b) = oklab_to_srgb(oklch_to_oklab(lightness, chroma, hue));
+            let in_gamut = [r, g, b].iter().all(|c| (-1e-4..=1.0 + 1e-4).contains(c));
+            let color = Rgba {
+                r: r.clamp(0.0, 1.0),
+                g: g.clamp(0.0, 1.0),
+                b: b.clamp(0.0, 1.0),
+                alpha,
+            };
+
+            let mut value = describe(color, ColorNotation::Oklch, in_gamut);
+            // keep the exact value rather than the round trip through clipped sRGB
+            value.oklch = format_oklch(lightness, chroma, hue, alpha);
+            Some(value)
+        }
+        _ => None,
+    }
+}
+
+// Perceptual distance between two colour values, `None` if either does not parse.
+pub fn distance(a: &str, b: &str) -> Option<f64> {
+    let a = srgb_to_oklab(parse_rgba(a)?);
+    let b = srgb_to_oklab(parse_rgba(b)?);
+    Some(oklab_distance(a, b))
+}
+
+fn parse_rgba(text: &str) -> Option<(f64, f64, f64)> {
+    // the hex notation is lossless for 8-bit colours, which is all a palette holds
+    let value = parse(text)?;
+    let color = parse_hex(value.hex.strip_prefix('#')?)?;
+    Some((color.r, color.g, color.b))
+}
+
+// The dominant colours of an image, most common first. Transparent pixels are ignored.
+pub fn palette(image: &RgbaImage) -> Vec<PaletteColor> {
+    let (width, height) = image.dimensions();
+    let scale = (PALETTE_SAMPLE_SIZE as f64 / width.max(height) as f64).min(1.0);
+    let sample_width = ((width as f64 * scale).round() as u32).max(1);
+    let sample_height = ((height as f64 * scale).round() as u32).max(1);
+    // nearest neighbour keeps real pixel colours instead of blending edges into new ones
+    let sample = imageops::resize(image, sample_width, sample_height, FilterType::Nearest);
+
+    // 4 bits per channel: sums of r, g, b and the pixel count
+    let mut buckets = vec![[0u64; 4]; 4096];
+    for pixel in sample.pixels().filter(|p| p[3] >= 128) {
+        let [r, g, b, _] = pixel.0;
+        let index = ((r as usize >> 4) << 8) | ((g as usize >> 4) << 4) | (b as usize >> 4);
+        let bucket = &mut buckets[index];
+        bucket[0] += r as u64;
+        bucket[1] += g as u64;
+        bucket[2] += b as u64;
+        bucket[3] += 1;
+    }
+
+    let mut buckets: Vec<[u64; 4]> = buckets.into_iter().filter(|b| b[3] > 0).collect();
+    buckets.sort_by(|a, b| b[3].cmp(&a[3]));
+    let total: u64 = buckets.iter().map(|b| b[3]).sum();
+
+    // greedily keep the most common colours, folding similar ones into the closest swatch
+    let mut swatches: Vec<([f64; 3], [u64; 4])> = Vec::new();
+    for bucket in buckets {
+        let count = bucket[3] as f64;
+        let lab = srgb_to_oklab((
+            bucket[0] as f64 / count / 255.0,
+            bucket[1] as f64 / count / 255.0,
+            bucket[2] as f64 / count / 255.0,
+        ));
+
+        let nearest = swatches
+            .iter()
+            .enumerate()
+            .map(|(i, (swatch, _))| (i, oklab_distance(*swatch, lab)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        match nearest {
+            Some((i, distance))
+                if distance < PALETTE_MIN_DISTANCE || swatches.len() == PALETTE_SIZE =>
+            {
+                let sums = &mut swatches[i].1;
+                for (sum, value) in sums.iter_mut().zip(bucket) {
+                    *sum += value;
+                }
+            }
+            _ => swatches.push((lab, bucket)),
+        }
+    }
+
+    swatches.sort_by(|a, b| b.1[3].cmp(&a.1[3]));
+    swatches
+        .into_iter()
+        .map(|(_, [r, g, b, count])| PaletteColor {
+            hex: format!(
+                "#{:02x}{:02x}{:02x}",
+                (r as f64 / count as f64).round() as u8,
+                (g as f64 / count as f64).round() as u8,
+                (b as f64 / count as f64).round() as u8
+            ),
+            proportion: (count as f64 / total as f64 * 1000.0).round() / 1000.0,
+        })
+        .collect()
+}
+
+fn describe(color: Rgba, notation: ColorNotation, in_gamut: bool) -> ColorValue {
+    let [r, g, b, a] = [color.r, color.g, color.b, color.alpha].map(to_byte);
+    let opaque = color.alpha >= 1.0;
+
+    let hex = if opaque {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    };
+    let alpha = format_number(color.alpha, 3);
+    let rgb = if opaque {
+        format!("rgb({}, {}, {})", r, g, b)
+    } else {
+        format!("rgba({}, {}, {}, {})", r, g, b, alpha)
+    };
+
+    let (h, s, l) = rgb_to_hsl(color.r, color.g, color.b);
+    let (h, s, l) = (
+        format_number(h, 1),
+        format_number(s * 100.0, 1),
+        format_number(l * 100.0, 1),
+    );
+    let hsl = if opaque {
+        format!("hsl({}, {}%, {}%)", h, s, l)
+    } else {
+        format!("hsla({}, {}%, {}%, {})", h, s, l, alpha)
+    };
+
+    let (lightness, chroma, hue) = oklab_to_oklch(srgb_to_oklab((color.r, color.g, color.b)));
+
+    ColorValue {
+        notation,
+        hex,
+        rgb,
+        hsl,
+        oklch: format_oklch(lightness, chroma, hue, color.alpha),
+        alpha: (color.alpha * 1000.0).round() / 1000.0,
+        in_gamut,
+    }
+}
+
+fn format_oklch(lightness: f64, chroma: f64, hue: f64, alpha: f64) -> String {
+    let base = format!(
+        "oklch({}% {} {}",
+        format_number(lightness * 100.0, 2),
+        format_number(chroma, 4),
+        format_number(hue, 2)
+    );
+    if alpha >= 1.0 {
+        format!("{})", base)
+    } else {
+        format!("{} / {})", base, format_number(alpha, 3))
+    }
+}
+
+// Rounds to at most `decimals` places without trailing zeros.
+fn format_number(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value);
+    let trimmed = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn to_byte(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn parse_hex(hex: &str) -> Option<Rgba> {
+    if !matches!(hex.len(), 3 | 4 | 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    // #rgb and #rgba repeat each digit
+    let digits: Vec<u8> = if hex.len() <= 4 {
+        hex.chars()
+            .map(|c| {
+                let d = c.to_digit(16).unwrap() as u8;
+                d * 16 + d
+            })
+            .collect()
+    } else {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    };
+
+    Some(Rgba {
+        r: digits[0] as f64 / 255.0,
+        g: digits[1] as f64 / 255.0,
+        b: digits[2] as f64 / 255.0,
+        alpha: digits.get(3).map_or(1.0, |a| *a as f64 / 255.0),
+    })
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    // rejects "nan" and "inf", which f64 parsing accepts
+    text.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+// An rgb() channel, either 0-255 or a percentage.
+fn parse_channel(text: &str) -> Option<f64> {
+    let value = match text.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(text)? / 255.0,
+    };
+    Some(value.clamp(0.0, 1.0))
+}
+
+// hsl() saturation and lightness; the space separated syntax allows a bare number.
+fn parse_percent(text: &str) -> Option<f64> {
+    let value = parse_number(text.strip_suffix('%').unwrap_or(text))?;
+    Some((value / 100.0).clamp(0.0, 1.0))
+}
+
+fn parse_alpha(text: &str) -> Option<f64> {
+    let value = match text.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(text)?,
+    };
+    Some(value.clamp(0.0, 1.0))
+}
+
+// A hue in degrees, accepting the CSS angle units.
+fn parse_hue(text: &str) -> Option<f64> {
+    let degrees = if let Some(deg) = text.strip_suffix("deg") {
+        parse_number(deg)?
+    } else if let Some(grad) = text.strip_suffix("grad") {
+        parse_number(grad)? * 0.9
+    } else if let Some(rad) = text.strip_suffix("rad") {
+        parse_number(rad)?.to_degrees()
+    } else if let Some(turn) = text.strip_suffix("turn") {
+        parse_number(turn)? * 360.0
+    } else {
+        parse_number(text)?
+    };
+    Some(degrees.rem_euclid(360.0))
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (f64, f64, f64) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    (r + m, g + m, b + m)
+}
+
+fn rgb_to_hsl(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, saturation, lightness)
+}
+
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// https://bottosson.github.io/posts/oklab/
+fn srgb_to_oklab((r, g, b): (f64, f64, f64)) -> [f64; 3] {
+    let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+// Returns unclipped sRGB, which lies outside 0..=1 for colours out of gamut.
+fn oklab_to_srgb([lightness, a, b]: [f64; 3]) -> (f64, f64, f64) {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    (
+        from_linear(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+        from_linear(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+        from_linear(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+    )
+}
+
+fn oklch_to_oklab(lightness: f64, chroma: f64, hue: f64) -> [f64; 3] {
+    let hue = hue.to_radians();
+    [lightness, chroma * hue.cos(), chroma * hue.sin()]
+}
+
+fn oklab_to_oklch([lightness, a, b]: [f64; 3]) -> (f64, f64, f64) {
+    let chroma = (a * a + b * b).sqrt();
+    // greys have no meaningful hue
+    let hue = if chroma < 1e-4 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+    (lightness, chroma, hue)
+}
+
+fn oklab_distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[cfg(test)]
+#[path = "./tests/color_test.rs"]
+mod color_test;
@@ -0,0 +1,119 @@
+use crate::barcodes::{self, DecodedCode};
+use crate::clipboard_formats::{ClipboardFormat, TEXT_PLAIN};
+use crate::commands::items;
+use crate::image_access;
+use crate::models::Item;
+use crate::qr_encoder::{self, QrLevel, RenderedQr};
+use crate::state::AppState;
+use tauri::State;
+
+// The QR codes and barcodes found in an image item. Images captured before codes were scanned,
+// or still waiting in the scan queue, get scanned now and the result saved.
+#[tauri::command]
+pub async fn get_item_codes(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<Vec<DecodedCode>, String> {
+    let item = sqlx::query_as::<_, Item>("SELECT * FROM items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| format!("Failed to get item: {}", e))?
+        .ok_or_else(|| format!("Item {} not found", id))?;
+
+    if item.item_type != "image" {
+        return Ok(Vec::new());
+    }
+
+    let metadata: serde_json::Value = item
+        .metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str(m).ok())
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(codes) = metadata.get("codes") {
+        return serde_json::from_value(codes.clone())
+            .map_err(|e| format!("Failed to read codes: {}", e));
+    }
+
+    let images_dir = image_access::images_dir(&app).map_err(|e| e.to_string())?;
+    let path = image_access::resolve_image_path(&state.db, &images_dir, id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let codes = tauri::async_runtime::spawn_blocking(move || barcodes::scan_file(&path))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    barcodes::save_codes(&state.db, id, &codes).await?;
+
+    Ok(codes)
+}
+
+// Saves the text of a decoded code as a text item and puts it on the clipboard. `index` picks
+// the code when the image holds several, the first one otherwise. Returns the copied text.
+#[tauri::command]
+pub async fn copy_item_code(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+    index: Option<usize>,
+) -> Result<String, String> {
+    let codes = get_item_codes(app.clone(), state.clone(), id).await?;
+    let index = index.unwrap_or(0);
+    let code = codes
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| format!("Item {} has no code at index {}", id, index))?;
+
+    // the watcher ignores the app's own writes, so the item is added here
+    items::add_item(
+        app,
+        state.clone(),
+        code.text.clone(),
+        Some("text".to_string()),
+        None,
+        None,
+    )
+    .await?;
+    state
+        .self_writes
+        .write(&[ClipboardFormat::new(TEXT_PLAIN, code.text.clone())])?;
+
+    Ok(code.text)
+}
+
+// Renders a text item as a QR code, to be scanned from the screen. `size` is the PNG width in
+// pixels, DEFAULT_QR_SIZE by default.
+#[tauri::command]
+pub async fn get_item_qr(
+    state: State<'_, AppState>,
+    id: i64,
+    level: Option<QrLevel>,
+    size: Option<u32>,
+) -> Result<RenderedQr, String> {
+    let item = sqlx::query_as::<_, Item>("SELECT * FROM items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| format!("Failed to get item: {}", e))?
+        .ok_or_else(|| format!("Item {} not found", id))?;
+
+    if !matches!(item.item_type.as_str(), "text" | "color") {
+        return Err(format!(
+            "Only text items can be shown as a QR code, item {} is {}",
+            id, item.item_type
+        ));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        qr_encoder::render(
+            &item.content,
+            level,
+            size.unwrap_or(qr_encoder::DEFAULT_QR_SIZE),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
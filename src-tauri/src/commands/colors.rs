@@ -0,0 +1,111 @@
+use crate::color::{self, ColorValue, PaletteColor};
+use crate::image_access;
+use crate::image_metadata;
+use crate::models::Item;
+use crate::state::AppState;
+use tauri::State;
+
+// Converts a colour value between hex, rgb(), hsl() and oklch().
+#[tauri::command]
+pub fn convert_color(value: String) -> Result<ColorValue, String> {
+    color::parse(&value).ok_or_else(|| format!("Not a colour value: {}", value))
+}
+
+// The colours of an item: the colour itself for `color` items, the dominant colours for
+// images. Images captured before palettes were stored get one computed and saved.
+#[tauri::command]
+pub async fn get_item_palette(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<Vec<PaletteColor>, String> {
+    let item = sqlx::query_as::<_, Item>("SELECT * FROM items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| format!("Failed to get item: {}", e))?
+        .ok_or_else(|| format!("Item {} not found", id))?;
+
+    match item.item_type.as_str() {
+        "color" => Ok(color::parse(&item.content)
+            .map(|value| {
+                vec![PaletteColor {
+                    hex: value.hex,
+                    proportion: 1.0,
+                }]
+            })
+            .unwrap_or_default()),
+        "image" => {
+            let mut metadata: serde_json::Value = item
+                .metadata
+                .as_deref()
+                .and_then(|m| serde_json::from_str(m).ok())
+                .unwrap_or_else(|| serde_json::json!({}));
+
+            if let Some(palette) = metadata.get("palette") {
+                return serde_json::from_value(palette.clone())
+                    .map_err(|e| format!("Failed to read palette: {}", e));
+            }
+
+            let images_dir = image_access::images_dir(&app).map_err(|e| e.to_string())?;
+            let path = image_access::resolve_image_path(&state.db, &images_dir, id)
+                .await
+                .map_err(|e| e.to_string())?;
+            let palette = tauri::async_runtime::spawn_blocking(move || {
+                let bytes =
+                    std::fs::read(&path).map_err(|e| format!("Failed to read image: {}", e))?;
+                let image = image_metadata::decode_oriented(&bytes)?;
+                Ok::<_, String>(color::palette(&image.to_rgba8()))
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+
+            metadata["palette"] = serde_json::json!(palette);
+            sqlx::query("UPDATE items SET metadata = ? WHERE id = ?")
+                .bind(metadata.to_string())
+                .bind(id)
+                .execute(&state.db)
+                .await
+                .map_err(|e| format!("Failed to save palette: {}", e))?;
+
+            Ok(palette)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+// Colour items and images whose palette contains a colour close to `value`, most recent first.
+#[tauri::command]
+pub async fn find_items_by_color(
+    state: State<'_, AppState>,
+    value: String,
+    max_distance: Option<f64>,
+) -> Result<Vec<Item>, String> {
+    let target = color::parse(&value).ok_or_else(|| format!("Not a colour value: {}", value))?;
+    let max_distance = max_distance.unwrap_or(color::MATCH_DISTANCE);
+    let matches = |other: &str| {
+        color::distance(&target.hex, other).is_some_and(|distance| distance <= max_distance)
+    };
+
+    let items: Vec<Item> = sqlx::query_as::<_, Item>(
+        "SELECT * FROM items WHERE item_type IN ('color', 'image') ORDER BY bumped_at DESC",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| format!("Failed to get items: {}", e))?;
+
+    Ok(items
+        .into_iter()
+        .filter(|item| match item.item_type.as_str() {
+            "color" => matches(&item.content),
+            _ => item
+                .metadata
+                .as_deref()
+                .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+                .and_then(|m| {
+                    serde_json::from_value::<Vec<PaletteColor>>(m["palette"].clone()).ok()
+                })
+                .is_some_and(|palette| palette.iter().any(|c| matches(&c.hex))),
+        })
+        .collect())
+}
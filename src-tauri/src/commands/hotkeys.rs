@@ -1,7 +1,15 @@
+use crate::chords;
+use crate::commands::settings;
+use crate::history_cycle;
+use crate::hotkey_actions::{self, HotkeyAction};
+use crate::keyboard_layout::KeyboardLayout;
 use crate::state::AppState;
-use crate::{HOLD_BEHAVIOR, state::DbPool};
+use crate::{HOLD_THRESHOLD_MS, HOTKEY_BEHAVIOR, state::DbPool};
+use serde::{Deserialize, Serialize};
 use std::panic;
+use std::sync::Mutex;
 use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 use tauri_plugin_positioner::{Position, WindowExt};
@@ -15,14 +23,193 @@ pub fn move_to_tray_or_center<W: WindowExt>(window: &W) {
     }
 }
 
+pub const HOTKEY_BEHAVIOR_KEY: &str = "hotkey_behavior";
+pub const HOLD_THRESHOLD_KEY: &str = "hold_threshold_ms";
+
+// in hybrid mode, how long the hotkey has to be held before it counts as holding
+pub const DEFAULT_HOLD_THRESHOLD_MS: u64 = 300;
+pub const MIN_HOLD_THRESHOLD_MS: u64 = 100;
+pub const MAX_HOLD_THRESHOLD_MS: u64 = 2000;
+pub const HOTKEY_BINDINGS: &str = "hotkey_bindings";
+
+// The physical key of the main hotkey, next to the symbol saved under `hotkey`.
+pub const HOTKEY_CODE: &str = "hotkey_code";
+
+// A hotkey bound to an action next to the main one. `hotkey` is what the user recorded, `code`
+// the physical key it was on, which is what gets registered. Either may be a chord, with the
+// steps separated by commas: "Ctrl+Alt+V, 3" and "KeyV, Digit3".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub hotkey: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub action: HotkeyAction,
+}
+
+impl HotkeyBinding {
+    pub fn chord(&self) -> Result<Chord, String> {
+        resolve_chord(&self.hotkey, self.code.as_deref(), None)
+    }
+}
+
+// A hotkey of one step, or a leader and the key pressed after it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chord {
+    pub leader: Shortcut,
+    pub key: Option<Shortcut>,
+}
+
+impl Chord {
+    pub fn single(shortcut: Shortcut) -> Self {
+        Self {
+            leader: shortcut,
+            key: None,
+        }
+    }
+
+    fn steps(&self) -> impl Iterator<Item = &Shortcut> {
+        std::iter::once(&self.leader).chain(self.key.as_ref())
+    }
+
+    // The physical keys, saved as the binding's `code`.
+    pub fn code(&self) -> String {
+        self.steps()
+            .map(|step| step.key.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Whether both cannot be bound at once: the same keys, a leader that is also bound on its
+    // own, or a key grabbed after a leader that is bound elsewhere.
+    pub fn conflicts_with(&self, other: &Chord) -> bool {
+        let same_leader = self.leader.id() == other.leader.id();
+        let same_key = match (self.key, other.key) {
+            (Some(key), Some(other_key)) => key.id() == other_key.id(),
+            _ => true,
+        };
+        let grabbed =
+            |chord: &Chord, leader: &Shortcut| chord.key.is_some_and(|key| key.id() == leader.id());
+
+        (same_leader && same_key) || grabbed(self, &other.leader) || grabbed(other, &self.leader)
+    }
+}
+
+// Why a hotkey could not be set. Commands return it as `{ "kind": ..., "message": ... }` so the
+// settings can tell a mistyped hotkey from one that is already taken.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum HotkeyError {
+    Parse(String),
+    InvalidAction(String),
+    // bound to something else in YZZO, or grabbed by another application
+    Conflict(String),
+    Database(String),
+}
+
+impl std::fmt::Display for HotkeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyError::Parse(e) => write!(f, "Invalid hotkey: {}", e),
+            HotkeyError::InvalidAction(e) => write!(f, "Invalid hotkey action: {}", e),
+            HotkeyError::Conflict(e) => write!(f, "Hotkey is taken: {}", e),
+            HotkeyError::Database(e) => write!(f, "Failed to save hotkey: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HotkeyError {}
+
 #[cfg(target_os = "macos")]
 pub const DEFAULT_HOTKEY: &str = "Cmd+`";
 
 #[cfg(not(target_os = "macos"))]
 pub const DEFAULT_HOTKEY: &str = "Alt+Q";
 
+// What pressing the main hotkey does. Saved under its lowercase name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum HotkeyBehavior {
+    // each press opens or closes the window
+    #[default]
+    Toggle,
+    // the window shows while the hotkey is held
+    Hold,
+    // a tap toggles, holding past the threshold works like hold
+    Hybrid,
+    // pressing again while the modifiers are held steps through recent items
+    Cycle,
+}
+
+impl HotkeyBehavior {
+    const ALL: [HotkeyBehavior; 4] = [
+        HotkeyBehavior::Toggle,
+        HotkeyBehavior::Hold,
+        HotkeyBehavior::Hybrid,
+        HotkeyBehavior::Cycle,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HotkeyBehavior::Toggle => "toggle",
+            HotkeyBehavior::Hold => "hold",
+            HotkeyBehavior::Hybrid => "hybrid",
+            HotkeyBehavior::Cycle => "cycle",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|behavior| behavior.as_str() == value)
+    }
+
+    pub fn current() -> Self {
+        Self::ALL
+            .get(usize::from(HOTKEY_BEHAVIOR.load(Ordering::Relaxed)))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn store(self) {
+        HOTKEY_BEHAVIOR.store(self as u8, Ordering::Relaxed);
+    }
+}
+
+// A press of the hotkey in hybrid mode.
+#[derive(Debug, Clone, Copy)]
+struct HybridPress {
+    at: Instant,
+    // whether the window was open before the press
+    was_visible: bool,
+}
+
+// What releasing the hotkey does in hybrid mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HybridRelease {
+    // a tap that showed the window leaves it open
+    KeepOpen,
+    // closing an open window picks nothing
+    Hide,
+    // a hold copies the selected item and hides, like hold mode
+    CopyAndHide,
+}
+
+// A tap toggles: it shows a hidden window and leaves it open, and hides an open one. Holding
+// past the threshold shows the window only while held.
+pub fn hybrid_release(held: Duration, threshold: Duration, was_visible: bool) -> HybridRelease {
+    if was_visible {
+        HybridRelease::Hide
+    } else if held >= threshold {
+        HybridRelease::CopyAndHide
+    } else {
+        HybridRelease::KeepOpen
+    }
+}
+
 pub fn register_hotkey_handler(app: &AppHandle, shortcut: Shortcut) -> Result<(), String> {
     let app_clone = app.clone();
+    let hybrid_press: Mutex<Option<HybridPress>> = Mutex::new(None);
 
     println!("[I] Registering shortcut: {:?}", shortcut);
 
@@ -33,105 +220,333 @@ pub fn register_hotkey_handler(app: &AppHandle, shortcut: Shortcut) -> Result<()
                 shortcut,
                 event.state()
             );
-            let hold_mode = crate::HOLD_BEHAVIOR.load(Ordering::Relaxed);
-
-            if let Some(window) = app_clone.get_webview_window("main") {
-                if hold_mode {
-                    match event.state() {
-                        ShortcutState::Pressed => {
-                            let _ = window.unminimize();
-                            move_to_tray_or_center(&window);
-                            let _ = window.show();
-                            let _ = window.set_focus();
+            match (HotkeyBehavior::current(), event.state()) {
+                // cycling shows and hides the window itself
+                (HotkeyBehavior::Cycle, ShortcutState::Pressed) => {
+                    history_cycle::on_press(&app_clone, shortcut.mods)
+                }
+                (HotkeyBehavior::Cycle, ShortcutState::Released) => {}
+                (HotkeyBehavior::Hybrid, state) => {
+                    let mut press = hybrid_press.lock().unwrap();
+                    match state {
+                        // key repeat sends more presses while held, the first one counts
+                        ShortcutState::Pressed if press.is_none() => {
+                            let was_visible = app_clone
+                                .get_webview_window("main")
+                                .and_then(|window| window.is_visible().ok())
+                                .unwrap_or(false);
+                            *press = Some(HybridPress {
+                                at: Instant::now(),
+                                was_visible,
+                            });
+                            if !was_visible {
+                                hotkey_actions::show_window(&app_clone);
+                            }
                         }
+                        ShortcutState::Pressed => {}
                         ShortcutState::Released => {
-                            let _ = window.emit("hotkey-released", ());
-                            let _ = window.hide();
-                        }
-                    }
-                } else {
-                    if let ShortcutState::Pressed = event.state() {
-                        if let Ok(visible) = window.is_visible() {
-                            if visible {
-                                let _ = window.hide();
-                            } else {
-                                let _ = window.unminimize();
-                                move_to_tray_or_center(&window);
-                                let _ = window.show();
-                                let _ = window.set_focus();
+                            let Some(HybridPress { at, was_visible }) = press.take() else {
+                                return;
+                            };
+                            let threshold =
+                                Duration::from_millis(HOLD_THRESHOLD_MS.load(Ordering::Relaxed));
+                            let Some(window) = app_clone.get_webview_window("main") else {
+                                return;
+                            };
+                            match hybrid_release(at.elapsed(), threshold, was_visible) {
+                                HybridRelease::KeepOpen => {}
+                                HybridRelease::Hide => {
+                                    let _ = window.hide();
+                                }
+                                HybridRelease::CopyAndHide => {
+                                    let _ = window.emit("hotkey-released", ());
+                                    let _ = window.hide();
+                                }
                             }
                         }
                     }
                 }
+                (HotkeyBehavior::Hold, ShortcutState::Pressed) => {
+                    hotkey_actions::show_window(&app_clone)
+                }
+                (HotkeyBehavior::Hold, ShortcutState::Released) => {
+                    if let Some(window) = app_clone.get_webview_window("main") {
+                        let _ = window.emit("hotkey-released", ());
+                        let _ = window.hide();
+                    }
+                }
+                (HotkeyBehavior::Toggle, ShortcutState::Pressed) => {
+                    hotkey_actions::toggle_window(&app_clone)
+                }
+                (HotkeyBehavior::Toggle, ShortcutState::Released) => {}
             }
         })
         .map_err(|e| format!("Failed to register shortcut: {}", e))
 }
 
-// Parse a hotkey string (like "Cmd+`") into a `Shortcut` instance.
-pub fn parse_hotkey(hotkey: &str) -> Result<Shortcut, String> {
-    let mut modifiers = Modifiers::empty();
-    let mut key = Code::KeyA;
-
-    let parts: Vec<&str> = hotkey.split('+').collect();
-
-    for part in parts.iter() {
-        let part_lower = part.trim().to_lowercase();
-
-        // check if it's a modifier
-        match part_lower.as_str() {
-            "cmd" | "super" => modifiers.insert(Modifiers::SUPER),
-            "ctrl" => modifiers.insert(Modifiers::CONTROL),
-            "alt" => modifiers.insert(Modifiers::ALT),
-            "shift" => modifiers.insert(Modifiers::SHIFT),
-            _ => {
-                // it's the actual key (last part)
-                key = match part.trim() {
-                    "`" => Code::Backquote,
-                    "'" => Code::Quote,
-                    "A" => Code::KeyA,
-                    "B" => Code::KeyB,
-                    "C" => Code::KeyC,
-                    "D" => Code::KeyD,
-                    "E" => Code::KeyE,
-                    "F" => Code::KeyF,
-                    "G" => Code::KeyG,
-                    "H" => Code::KeyH,
-                    "I" => Code::KeyI,
-                    "J" => Code::KeyJ,
-                    "K" => Code::KeyK,
-                    "L" => Code::KeyL,
-                    "M" => Code::KeyM,
-                    "N" => Code::KeyN,
-                    "O" => Code::KeyO,
-                    "P" => Code::KeyP,
-                    "Q" => Code::KeyQ,
-                    "R" => Code::KeyR,
-                    "S" => Code::KeyS,
-                    "T" => Code::KeyT,
-                    "U" => Code::KeyU,
-                    "V" => Code::KeyV,
-                    "W" => Code::KeyW,
-                    "X" => Code::KeyX,
-                    "Y" => Code::KeyY,
-                    "Z" => Code::KeyZ,
-                    "0" => Code::Digit0,
-                    "1" => Code::Digit1,
-                    "2" => Code::Digit2,
-                    "3" => Code::Digit3,
-                    "4" => Code::Digit4,
-                    "5" => Code::Digit5,
-                    "6" => Code::Digit6,
-                    "7" => Code::Digit7,
-                    "8" => Code::Digit8,
-                    "9" => Code::Digit9,
-                    _ => return Err(format!("Unsupported key: {}", part)),
-                };
+pub fn register_binding(
+    app: &AppHandle,
+    shortcut: Shortcut,
+    action: HotkeyAction,
+) -> Result<(), String> {
+    println!("[I] Registering shortcut {:?} for {:?}", shortcut, action);
+
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if let ShortcutState::Pressed = event.state() {
+                hotkey_actions::run(app, action);
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut: {}", e))
+}
+
+// Keys written as the character they type rather than their code name.
+const KEY_SYMBOLS: [(Code, &str); 11] = [
+    (Code::Backquote, "`"),
+    (Code::Quote, "'"),
+    (Code::Minus, "-"),
+    (Code::Equal, "="),
+    (Code::BracketLeft, "["),
+    (Code::BracketRight, "]"),
+    (Code::Backslash, "\\"),
+    (Code::Semicolon, ";"),
+    (Code::Comma, ","),
+    (Code::Period, "."),
+    (Code::Slash, "/"),
+];
+
+#[cfg(target_os = "macos")]
+const SUPER_LABEL: &str = "Cmd";
+
+#[cfg(not(target_os = "macos"))]
+const SUPER_LABEL: &str = "Super";
+
+fn parse_modifier(part: &str) -> Option<Modifiers> {
+    match part.to_lowercase().as_str() {
+        "cmd" | "command" | "super" | "meta" | "win" => Some(Modifiers::SUPER),
+        "ctrl" | "control" => Some(Modifiers::CONTROL),
+        "alt" | "option" => Some(Modifiers::ALT),
+        "shift" => Some(Modifiers::SHIFT),
+        _ => None,
+    }
+}
+
+// Accepts the W3C code name of any key, plus letters, digits and punctuation as typed.
+fn parse_key(part: &str) -> Result<Code, String> {
+    if let Some((code, _)) = KEY_SYMBOLS.iter().find(|(_, symbol)| *symbol == part) {
+        return Ok(*code);
+    }
+
+    let mut chars = part.chars();
+    let name = match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => format!("Key{}", c.to_ascii_uppercase()),
+        (Some(c), None) if c.is_ascii_digit() => format!("Digit{}", c),
+        // f1, f12
+        (Some('f'), Some(c)) if c.is_ascii_digit() => part.to_uppercase(),
+        _ => match part.to_lowercase().as_str() {
+            "esc" => "Escape".to_string(),
+            "return" => "Enter".to_string(),
+            "del" => "Delete".to_string(),
+            "up" | "down" | "left" | "right" => {
+                format!(
+                    "Arrow{}{}",
+                    part[..1].to_uppercase(),
+                    &part[1..].to_lowercase()
+                )
             }
+            _ => part.to_string(),
+        },
+    };
+
+    match name.parse::<Code>() {
+        Ok(
+            Code::ControlLeft
+            | Code::ControlRight
+            | Code::AltLeft
+            | Code::AltRight
+            | Code::ShiftLeft
+            | Code::ShiftRight
+            | Code::MetaLeft
+            | Code::MetaRight
+            | Code::Fn
+            | Code::FnLock,
+        ) => Err(format!("{} is a modifier, not a key", part)),
+        Ok(Code::Unidentified) | Err(_) => Err(format!("Unsupported key: {}", part)),
+        Ok(code) => Ok(code),
+    }
+}
+
+// Splits a hotkey like "Ctrl+Shift+V" into its modifiers and its one key.
+fn split_hotkey(hotkey: &str) -> Result<(Modifiers, &str), String> {
+    let mut modifiers = Modifiers::empty();
+    let mut key = None;
+
+    for part in hotkey.split('+').map(str::trim) {
+        if part.is_empty() {
+            return Err(format!("Hotkey has an empty part: {}", hotkey));
+        }
+
+        if let Some(modifier) = parse_modifier(part) {
+            modifiers.insert(modifier);
+        } else if key.replace(part).is_some() {
+            return Err(format!("Hotkey can only have one key: {}", hotkey));
+        }
+    }
+
+    let key = key.ok_or_else(|| format!("Hotkey needs a key besides the modifiers: {}", hotkey))?;
+    Ok((modifiers, key))
+}
+
+// The shortcut to register for `hotkey`. `code` is the physical key saved with the hotkey and
+// wins when given. Otherwise a single character is looked up in `layout`, so "Ctrl+A" on AZERTY
+// registers the key labelled A; names like "Space" or "F5" are the same key on every layout.
+pub fn resolve_hotkey(
+    hotkey: &str,
+    code: Option<&str>,
+    layout: Option<&KeyboardLayout>,
+) -> Result<Shortcut, String> {
+    let (modifiers, key) = split_hotkey(hotkey)?;
+
+    let code = match (code, layout.and_then(|layout| layout.code_for(key))) {
+        (Some(code), _) => parse_key(code)?,
+        (None, Some(code)) => code,
+        (None, None) => parse_key(key)?,
+    };
+    Ok(Shortcut::new(Some(modifiers), code))
+}
+
+// Splits "Ctrl+Alt+V, 3" into its steps. A comma right after a `+` is the comma key.
+fn split_chord(hotkey: &str) -> Vec<&str> {
+    let mut steps = Vec::new();
+    let mut start = 0;
+
+    for (index, c) in hotkey.char_indices() {
+        if c == ',' && !hotkey[start..index].trim_end().ends_with('+') {
+            steps.push(hotkey[start..index].trim());
+            start = index + 1;
         }
     }
+    steps.push(hotkey[start..].trim());
+    steps
+}
+
+// Resolves each step of a chord like `resolve_hotkey`, `code` holding a physical key per step.
+pub fn resolve_chord(
+    hotkey: &str,
+    code: Option<&str>,
+    layout: Option<&KeyboardLayout>,
+) -> Result<Chord, String> {
+    let steps = split_chord(hotkey);
+    if steps.len() > 2 {
+        return Err(format!(
+            "Chords are a leader and one key after it: {}",
+            hotkey
+        ));
+    }
+    let codes: Vec<Option<&str>> = match code {
+        Some(code) => code.split(',').map(|code| Some(code.trim())).collect(),
+        None => vec![None; steps.len()],
+    };
+    if codes.len() != steps.len() {
+        return Err(format!("Saved keys {:?} do not match {}", code, hotkey));
+    }
+
+    let leader = resolve_hotkey(steps[0], codes[0], layout)?;
+    let key = match steps.get(1) {
+        Some(step) => Some(resolve_hotkey(step, codes[1], layout)?),
+        None => None,
+    };
+    Ok(Chord { leader, key })
+}
+
+// Parses a hotkey with keys in their US QWERTY position: any number of modifiers and exactly
+// one key.
+pub fn parse_hotkey(hotkey: &str) -> Result<Shortcut, String> {
+    resolve_hotkey(hotkey, None, None)
+}
+
+fn key_label(key: Code) -> String {
+    if let Some((_, symbol)) = KEY_SYMBOLS.iter().find(|(code, _)| *code == key) {
+        return symbol.to_string();
+    }
+
+    let name = key.to_string();
+    match name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+    {
+        Some(rest) if rest.len() == 1 => rest.to_string(),
+        _ => name,
+    }
+}
+
+fn join_hotkey(modifiers: Modifiers, key: String) -> String {
+    let mut parts: Vec<String> = [
+        (Modifiers::CONTROL, "Ctrl"),
+        (Modifiers::ALT, "Alt"),
+        (Modifiers::SHIFT, "Shift"),
+        (Modifiers::SUPER, SUPER_LABEL),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| modifiers.contains(*modifier))
+    .map(|(_, label)| label.to_string())
+    .collect();
+
+    parts.push(key);
+    parts.join("+")
+}
+
+// The canonical spelling of a hotkey, modifiers in a fixed order. Matches `formatHotkey` in the
+// frontend so a saved hotkey reads the same wherever it is shown. With a layout the key is
+// written as the symbol printed on it there.
+pub fn format_hotkey(shortcut: &Shortcut, layout: Option<&KeyboardLayout>) -> String {
+    let key = layout
+        .and_then(|layout| layout.symbol_for(shortcut.key))
+        .unwrap_or_else(|| key_label(shortcut.key));
+    join_hotkey(shortcut.mods, key)
+}
+
+// How a newly set hotkey is saved: named after its keys in the active layout, or as the user
+// wrote it where the layout is unknown.
+fn hotkey_label(hotkey: &str, chord: &Chord, layout: Option<&KeyboardLayout>) -> String {
+    match layout {
+        Some(layout) => chord
+            .steps()
+            .map(|step| format_hotkey(step, Some(layout)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => canonical_hotkey(hotkey),
+    }
+}
+
+// `hotkey` spelled canonically, or as it was when it does not parse. Symbols only some layouts
+// have, like "é", are kept and uppercased.
+pub fn canonical_hotkey(hotkey: &str) -> String {
+    split_chord(hotkey)
+        .into_iter()
+        .map(canonical_step)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    Ok(Shortcut::new(Some(modifiers), key))
+fn canonical_step(hotkey: &str) -> String {
+    let Ok((modifiers, key)) = split_hotkey(hotkey) else {
+        return hotkey.to_string();
+    };
+
+    let label = match parse_key(key) {
+        Ok(code) => key_label(code),
+        Err(_) if key.chars().count() == 1 => {
+            let upper = key.to_uppercase();
+            if upper.chars().count() == 1 {
+                upper
+            } else {
+                key.to_string()
+            }
+        }
+        Err(_) => return hotkey.to_string(),
+    };
+    join_hotkey(modifiers, label)
 }
 
 #[tauri::command]
@@ -143,79 +558,377 @@ pub async fn get_hotkey(state: State<'_, AppState>) -> Result<String, String> {
             .map_err(|e| e.to_string())?;
 
     match result {
-        Some((value,)) => Ok(value),
-        None => Ok(DEFAULT_HOTKEY.to_string()),
+        Some((value,)) => Ok(canonical_hotkey(&value)),
+        None => Ok(canonical_hotkey(DEFAULT_HOTKEY)),
     }
 }
 
+// The main hotkey's shortcut, on the physical key saved with it.
+async fn main_shortcut(db: &DbPool) -> Result<Shortcut, String> {
+    let hotkey = settings::get_setting(db, "hotkey")
+        .await?
+        .unwrap_or_else(|| DEFAULT_HOTKEY.to_string());
+    let code = settings::get_setting(db, HOTKEY_CODE).await?;
+    resolve_hotkey(&hotkey, code.as_deref(), None)
+}
+
+// Saves the main hotkey together with its physical key.
+async fn save_main_hotkey(db: &DbPool, hotkey: &str, code: &str) -> Result<(), String> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to save hotkey: {}", e))?;
+    for (key, value) in [("hotkey", hotkey), (HOTKEY_CODE, code)] {
+        sqlx::query("INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)")
+            .bind(key)
+            .bind(value)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to save hotkey: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to save hotkey: {}", e))
+}
+
+// Registers the main hotkey at startup. A hotkey saved without its physical key is looked up in
+// the active layout, and the key saved so a later layout switch does not move the hotkey.
+pub async fn register_main_hotkey(app: &AppHandle, db: &DbPool) {
+    let (hotkey, shortcut) = match load_main_hotkey(db, KeyboardLayout::active().as_ref()).await {
+        Ok(main) => main,
+        Err(e) => return eprintln!("[X] Failed to load hotkey: {}", e),
+    };
+
+    match register_hotkey_handler(app, shortcut) {
+        Ok(_) => println!("[V] Global shortcut registered: {}", hotkey),
+        Err(e) => eprintln!("[X] Failed to register hotkey {}: {}", hotkey, e),
+    }
+}
+
+// The main hotkey and its physical key, saving the key when it is missing. Hotkeys saved before
+// the key was kept always meant its US position, only the default one goes through `layout`.
+pub(crate) async fn load_main_hotkey(
+    db: &DbPool,
+    layout: Option<&KeyboardLayout>,
+) -> Result<(String, Shortcut), String> {
+    let saved = settings::get_setting(db, "hotkey").await?;
+    let code = settings::get_setting(db, HOTKEY_CODE).await?;
+
+    let is_default = saved
+        .as_deref()
+        .is_none_or(|hotkey| canonical_hotkey(hotkey) == canonical_hotkey(DEFAULT_HOTKEY));
+    let hotkey = saved.unwrap_or_else(|| DEFAULT_HOTKEY.to_string());
+    let shortcut = match (&code, is_default) {
+        (None, false) => parse_hotkey(&hotkey),
+        _ => resolve_hotkey(&hotkey, code.as_deref(), layout),
+    }
+    .map_err(|e| format!("{} does not parse: {}", hotkey, e))?;
+
+    if code.is_none() {
+        settings::set_setting(db, HOTKEY_CODE, &shortcut.key.to_string()).await?;
+    }
+    Ok((hotkey, shortcut))
+}
+
+// Swaps the main hotkey without ever leaving it broken: the new hotkey is registered while the
+// old one still works, saved only once it is registered, and the old one released last. `code`
+// is the physical key the hotkey was recorded on; without it the active layout decides. Returns
+// the hotkey as saved.
 #[tauri::command]
 pub async fn set_hotkey(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     hotkey: String,
-) -> Result<(), String> {
-    let old_hotkey = get_hotkey(state.clone())
-        .await
-        .unwrap_or_else(|_| DEFAULT_HOTKEY.to_string());
+    code: Option<String>,
+) -> Result<String, HotkeyError> {
+    let layout = KeyboardLayout::active();
+    let shortcut =
+        resolve_hotkey(&hotkey, code.as_deref(), layout.as_ref()).map_err(HotkeyError::Parse)?;
+    let hotkey = hotkey_label(&hotkey, &Chord::single(shortcut), layout.as_ref());
+    let code = shortcut.key.to_string();
 
-    if let Ok(old_shortcut) = crate::parse_hotkey(&old_hotkey) {
+    let old_shortcut = main_shortcut(&state.db).await.ok();
+    if old_shortcut.is_some_and(|old| old.id() == shortcut.id()) {
+        // only the spelling changed, the registration stays
+        save_main_hotkey(&state.db, &hotkey, &code)
+            .await
+            .map_err(HotkeyError::Database)?;
+        return Ok(hotkey);
+    }
+
+    let bindings = load_bindings(&state.db).await;
+    if let Some(binding) = find_conflict(&bindings, &Chord::single(shortcut)) {
+        return Err(HotkeyError::Conflict(format!(
+            "{} is already bound to {:?} by {}",
+            hotkey, binding.action, binding.hotkey
+        )));
+    }
+
+    register_hotkey_handler(&app, shortcut).map_err(HotkeyError::Conflict)?;
+
+    if let Err(e) = save_main_hotkey(&state.db, &hotkey, &code).await {
+        let _ = app.global_shortcut().unregister(shortcut);
+        return Err(HotkeyError::Database(e));
+    }
+
+    if let Some(old_shortcut) = old_shortcut {
         let _ = app.global_shortcut().unregister(old_shortcut);
     }
+    println!("[V] Global shortcut changed to {} on {}", hotkey, code);
+
+    Ok(hotkey)
+}
 
-    let result = sqlx::query("INSERT OR REPLACE INTO settings (key, value) VALUES ('hotkey', ?)")
-        .bind(&hotkey)
-        .execute(&state.db)
+pub async fn load_bindings(db: &DbPool) -> Vec<HotkeyBinding> {
+    settings::get_setting(db, HOTKEY_BINDINGS)
         .await
-        .map_err(|e| e.to_string())?;
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+async fn save_bindings(db: &DbPool, bindings: &[HotkeyBinding]) -> Result<(), String> {
+    let value = serde_json::to_string(bindings)
+        .map_err(|e| format!("Failed to save hotkey bindings: {}", e))?;
+    settings::set_setting(db, HOTKEY_BINDINGS, &value).await
+}
+
+// The binding in the way of `chord`, however it is spelled.
+pub fn find_conflict<'a>(
+    bindings: &'a [HotkeyBinding],
+    chord: &Chord,
+) -> Option<&'a HotkeyBinding> {
+    bindings.iter().find(|binding| {
+        binding
+            .chord()
+            .is_ok_and(|bound| bound.conflicts_with(chord))
+    })
+}
 
-    if result.rows_affected() == 0 {
-        return Err("Failed to update hotkey".to_string());
+fn register_chord(app: &AppHandle, chord: Chord, action: HotkeyAction) -> Result<(), String> {
+    match chord.key {
+        Some(key) => chords::register(app, chord.leader, key, action),
+        None => register_binding(app, chord.leader, action),
     }
+}
+
+fn unregister_chord(app: &AppHandle, chord: Chord) {
+    match chord.key {
+        Some(key) => chords::unregister(app, chord.leader, key),
+        None => {
+            let _ = app.global_shortcut().unregister(chord.leader);
+        }
+    }
+}
+
+// Registers the saved bindings at startup. Bindings that fail to register are logged and kept,
+// another application may hold the shortcut for now. Like the main hotkey, bindings saved
+// without their physical keys get the ones the active layout gives them.
+pub async fn register_bindings(app: &AppHandle, db: &DbPool) {
+    let mut bindings = load_bindings(db).await;
+    let layout = KeyboardLayout::active();
+    let mut resolved = false;
 
-    let new_shortcut =
-        crate::parse_hotkey(&hotkey).map_err(|e| format!("Invalid hotkey: {}", e))?;
+    for binding in &mut bindings {
+        let chord = match resolve_chord(&binding.hotkey, binding.code.as_deref(), layout.as_ref()) {
+            Ok(chord) => chord,
+            Err(e) => {
+                eprintln!("[X] Failed to parse hotkey {}: {}", binding.hotkey, e);
+                continue;
+            }
+        };
+        if binding.code.is_none() {
+            binding.code = Some(chord.code());
+            resolved = true;
+        }
 
-    let _ = app.global_shortcut().unregister_all();
-    register_hotkey_handler(&app, new_shortcut)?;
+        match register_chord(app, chord, binding.action) {
+            Ok(_) => println!(
+                "[V] Hotkey {} bound to {:?}",
+                binding.hotkey, binding.action
+            ),
+            Err(e) => eprintln!("[X] Failed to bind hotkey {}: {}", binding.hotkey, e),
+        }
+    }
+
+    if resolved && let Err(e) = save_bindings(db, &bindings).await {
+        eprintln!("[X] {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_hotkey_bindings(state: State<'_, AppState>) -> Result<Vec<HotkeyBinding>, String> {
+    let mut bindings = load_bindings(&state.db).await;
+    for binding in &mut bindings {
+        binding.hotkey = canonical_hotkey(&binding.hotkey);
+    }
+    Ok(bindings)
+}
+
+// Binds `hotkey` to `action`, replacing the action it had. `hotkey` may be a chord like
+// "Ctrl+Alt+V, 3", several chords can share a leader. The main hotkey cannot be rebound here, it
+// is changed with `set_hotkey`. On failure the previous action stays bound.
+#[tauri::command]
+pub async fn bind_hotkey(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    hotkey: String,
+    code: Option<String>,
+    action: HotkeyAction,
+) -> Result<(), HotkeyError> {
+    action.validate().map_err(HotkeyError::InvalidAction)?;
+    let layout = KeyboardLayout::active();
+    let chord =
+        resolve_chord(&hotkey, code.as_deref(), layout.as_ref()).map_err(HotkeyError::Parse)?;
+    let hotkey = hotkey_label(&hotkey, &chord, layout.as_ref());
+
+    let main = main_shortcut(&state.db)
+        .await
+        .map_err(HotkeyError::Database)?;
+    if chord.conflicts_with(&Chord::single(main)) {
+        return Err(HotkeyError::Conflict(format!(
+            "{} is in the way of the hotkey that opens YZZO",
+            hotkey
+        )));
+    }
+
+    let mut bindings = load_bindings(&state.db).await;
+    let previous = match find_conflict(&bindings, &chord) {
+        Some(existing) if existing.chord() == Ok(chord) => Some(existing.clone()),
+        Some(existing) => {
+            return Err(HotkeyError::Conflict(format!(
+                "{} is in the way of {}, bound to {:?}",
+                hotkey, existing.hotkey, existing.action
+            )));
+        }
+        None => None,
+    };
+    if let Some(previous) = &previous {
+        unregister_chord(&app, chord);
+        bindings.retain(|binding| binding.hotkey != previous.hotkey);
+    }
+
+    if let Err(e) = register_chord(&app, chord, action) {
+        restore_binding(&app, chord, previous.as_ref());
+        return Err(HotkeyError::Conflict(e));
+    }
+
+    bindings.push(HotkeyBinding {
+        hotkey,
+        code: Some(chord.code()),
+        action,
+    });
+    if let Err(e) = save_bindings(&state.db, &bindings).await {
+        restore_binding(&app, chord, previous.as_ref());
+        return Err(HotkeyError::Database(e));
+    }
 
     Ok(())
 }
 
-pub async fn load_hold_behavior_from_db(db: &DbPool) -> bool {
-    sqlx::query_as::<_, (String,)>("SELECT value FROM settings WHERE key = 'hold_behavior'")
-        .fetch_optional(db)
+// Puts back the binding `bind_hotkey` was replacing.
+fn restore_binding(app: &AppHandle, chord: Chord, previous: Option<&HotkeyBinding>) {
+    unregister_chord(app, chord);
+
+    if let Some(previous) = previous
+        && let Err(e) = register_chord(app, chord, previous.action)
+    {
+        eprintln!("[X] Failed to restore hotkey {}: {}", previous.hotkey, e);
+    }
+}
+
+// Unbinds `hotkey` as `get_hotkey_bindings` spells it.
+#[tauri::command]
+pub async fn unbind_hotkey(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    hotkey: String,
+) -> Result<(), String> {
+    let hotkey = canonical_hotkey(&hotkey);
+
+    let mut bindings = load_bindings(&state.db).await;
+    let Some(index) = bindings
+        .iter()
+        .position(|binding| canonical_hotkey(&binding.hotkey) == hotkey)
+    else {
+        return Err(format!("{} is not bound", hotkey));
+    };
+
+    let binding = bindings.remove(index);
+    if let Ok(chord) = binding.chord() {
+        unregister_chord(&app, chord);
+    }
+    save_bindings(&state.db, &bindings).await
+}
+
+pub async fn load_hotkey_behavior_from_db(db: &DbPool) -> HotkeyBehavior {
+    settings::get_setting(db, HOTKEY_BEHAVIOR_KEY)
         .await
         .ok()
         .flatten()
-        .map(|r| r.0 == "true")
-        .unwrap_or(false)
+        .and_then(|value| HotkeyBehavior::parse(&value))
+        .unwrap_or_default()
 }
 
 #[tauri::command]
-pub async fn get_hold_behavior(state: State<'_, AppState>) -> Result<bool, String> {
-    let result =
-        sqlx::query_as::<_, (String,)>("SELECT value FROM settings WHERE key = 'hold_behavior'")
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| e.to_string())?;
-
-    Ok(result.map(|r| r.0 == "true").unwrap_or(false))
+pub async fn get_hotkey_behavior(state: State<'_, AppState>) -> Result<HotkeyBehavior, String> {
+    Ok(load_hotkey_behavior_from_db(&state.db).await)
 }
 
 #[tauri::command]
-pub async fn set_hold_behavior(
+pub async fn set_hotkey_behavior(
     state: State<'_, AppState>,
-    hold_behavior: bool,
+    behavior: HotkeyBehavior,
 ) -> Result<(), String> {
-    let value = if hold_behavior { "true" } else { "false" };
+    settings::set_setting(&state.db, HOTKEY_BEHAVIOR_KEY, behavior.as_str()).await?;
+
+    behavior.store();
+
+    Ok(())
+}
+
+// Lets the settings page warn that cycle mode falls back to a timeout, e.g. on Wayland.
+#[tauri::command]
+pub fn get_cycle_release_detected() -> bool {
+    history_cycle::detects_release()
+}
+
+// Reads a saved threshold, falling back to the default when it is missing or out of range.
+pub fn parse_hold_threshold(value: Option<&str>) -> u64 {
+    value
+        .and_then(|value| value.parse().ok())
+        .filter(|ms| (MIN_HOLD_THRESHOLD_MS..=MAX_HOLD_THRESHOLD_MS).contains(ms))
+        .unwrap_or(DEFAULT_HOLD_THRESHOLD_MS)
+}
 
-    sqlx::query("INSERT OR REPLACE INTO settings (key, value) VALUES ('hold_behavior', ?)")
-        .bind(value)
-        .execute(&state.db)
+pub async fn load_hold_threshold_from_db(db: &DbPool) -> u64 {
+    let value = settings::get_setting(db, HOLD_THRESHOLD_KEY)
         .await
-        .map_err(|e| e.to_string())?;
+        .ok()
+        .flatten();
+    parse_hold_threshold(value.as_deref())
+}
+
+#[tauri::command]
+pub async fn get_hold_threshold(state: State<'_, AppState>) -> Result<u64, String> {
+    Ok(load_hold_threshold_from_db(&state.db).await)
+}
+
+#[tauri::command]
+pub async fn set_hold_threshold(
+    state: State<'_, AppState>,
+    threshold_ms: u64,
+) -> Result<(), String> {
+    if !(MIN_HOLD_THRESHOLD_MS..=MAX_HOLD_THRESHOLD_MS).contains(&threshold_ms) {
+        return Err(format!(
+            "Hold threshold must be between {} and {} ms",
+            MIN_HOLD_THRESHOLD_MS, MAX_HOLD_THRESHOLD_MS
+        ));
+    }
+    settings::set_setting(&state.db, HOLD_THRESHOLD_KEY, &threshold_ms.to_string()).await?;
 
-    HOLD_BEHAVIOR.store(hold_behavior, Ordering::Relaxed);
+    HOLD_THRESHOLD_MS.store(threshold_ms, Ordering::Relaxed);
 
     Ok(())
 }
@@ -1,10 +1,23 @@
-use crate::state::AppState;
-use tauri::{Manager, State};
-
-use crate::models::Item;
+use crate::auto_paste;
+use crate::clipboard_formats::{
+    self, ClipboardFormat, TEXT_HTML, TEXT_PLAIN, TEXT_RTF, find_format,
+};
+use crate::commands::settings;
+use crate::file_list;
+use crate::image_access::{self, ImageAccessError};
+use crate::image_store;
+use crate::rich_text;
+use crate::state::{AppState, DbPool};
+use crate::thumbnails;
+use serde::Deserialize;
+use std::path::Path;
+use tauri::{Emitter, Manager, State};
+
+use crate::models::{Item, ItemFormat};
 
 #[tauri::command]
 pub async fn add_item(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     content: String,
     item_type: Option<String>,
@@ -12,6 +25,12 @@ pub async fn add_item(
     metadata: Option<String>,
 ) -> Result<(), String> {
     let item_type = item_type.unwrap_or_else(|| "text".to_string());
+    let images_dir = image_access::images_dir(&app).map_err(|e| e.to_string())?;
+    let file_path =
+        stored_file_path(&images_dir, &item_type, file_path).map_err(|e| e.to_string())?;
+
+    // every representation the watcher captured alongside this content
+    let formats = state.pending_formats.take(&content);
 
     // check if an item with the same content already exists
     let existing: Option<Item> = if item_type == "image" {
@@ -50,15 +69,32 @@ pub async fn add_item(
             None
         }
     } else {
-        sqlx::query_as::<_, Item>("SELECT * FROM items WHERE content = ? AND item_type = 'text'")
+        sqlx::query_as::<_, Item>("SELECT * FROM items WHERE content = ? AND item_type = ?")
             .bind(&content)
+            .bind(&item_type)
             .fetch_optional(&state.db)
             .await
             .map_err(|e| e.to_string())?
     };
 
+    let perceptual_hash = metadata
+        .as_deref()
+        .filter(|_| item_type == "image")
+        .and_then(image_store::perceptual_hash_from_metadata);
+
+    // a screenshot taken twice or a re-encoded copy has different pixels but looks the same
+    let existing = match (existing, perceptual_hash) {
+        (None, Some(phash)) => find_similar_image(&state.db, phash).await?,
+        (existing, _) => existing,
+    };
+
     // if it exists, just bump it to the top
     if let Some(item) = existing {
+        // the watcher already stored the new image, which the existing item makes redundant
+        if let Some(ref new_path) = file_path {
+            remove_redundant_image(Path::new(new_path), item.file_path.as_deref());
+        }
+
         sqlx::query(
             "UPDATE items
              SET bumped_at = CURRENT_TIMESTAMP,
@@ -69,6 +105,10 @@ pub async fn add_item(
         .execute(&state.db)
         .await
         .map_err(|e| format!("Failed to bump existing item: {}", e))?;
+
+        if !formats.is_empty() {
+            store_formats(&state.db, i64::from(item.id), &formats).await?;
+        }
         return Ok(());
     }
 
@@ -91,6 +131,7 @@ pub async fn add_item(
             if old_item.item_type == "image" {
                 if let Some(ref path) = old_item.file_path {
                     let _ = std::fs::remove_file(path);
+                    thumbnails::remove(Path::new(path));
                 }
             }
 
@@ -102,27 +143,329 @@ pub async fn add_item(
         }
     }
 
-    sqlx::query(
-        "INSERT INTO items (content, item_type, file_path, metadata, created_at, updated_at, bumped_at)
-         VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+    // images render in the encoding they were stored with
+    let preferred_format = metadata
+        .as_deref()
+        .filter(|_| item_type == "image")
+        .and_then(|meta| serde_json::from_str::<serde_json::Value>(meta).ok())
+        .and_then(|meta| meta.get("mime_type")?.as_str().map(String::from))
+        .or_else(|| clipboard_formats::preferred_format(&item_type, &formats));
+
+    let result = sqlx::query(
+        "INSERT INTO items (content, item_type, file_path, metadata, preferred_format, perceptual_hash, created_at, updated_at, bumped_at)
+         VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
     )
     .bind(content)
-    .bind(item_type)
-    .bind(file_path)
+    .bind(&item_type)
+    .bind(&file_path)
     .bind(metadata)
+    .bind(preferred_format)
+    .bind(perceptual_hash.map(|phash| phash as i64))
     .execute(&state.db)
     .await
     .map_err(|e| e.to_string())?;
 
+    let id = result.last_insert_rowid();
+    store_formats(&state.db, id, &formats).await?;
+
+    if item_type == "image"
+        && let Some(file_path) = file_path
+    {
+        state.thumbnails.enqueue(&file_path);
+        state.codes.enqueue(id, &file_path);
+        state.ocr.enqueue(id, file_path);
+    }
+
+    Ok(())
+}
+
+// The file of a new item, as stored. Paths come from the webview, so an image is only accepted
+// from inside the images directory, and nothing later thumbnails, scans or deletes a file
+// elsewhere on disk. Other items have no file.
+pub(crate) fn stored_file_path(
+    images_dir: &Path,
+    item_type: &str,
+    file_path: Option<String>,
+) -> Result<Option<String>, ImageAccessError> {
+    match (item_type, file_path) {
+        ("image", Some(path)) => image_access::ensure_within(images_dir, Path::new(&path))
+            .map(|path| Some(path.to_string_lossy().into_owned())),
+        _ => Ok(None),
+    }
+}
+
+// Deletes the file the watcher stored for a copy that turned out to be a duplicate, unless the
+// existing item still uses it.
+fn remove_redundant_image(new_path: &Path, existing_path: Option<&str>) {
+    let existing_path = existing_path.and_then(|path| Path::new(path).canonicalize().ok());
+    if existing_path.as_deref() != Some(new_path) {
+        let _ = std::fs::remove_file(new_path);
+    }
+}
+
+// Finds the image item whose perceptual hash is closest to `phash`, within the configured
+// Hamming distance.
+async fn find_similar_image(db: &DbPool, phash: u64) -> Result<Option<Item>, String> {
+    // identical perceptual hashes are found through the index
+    let identical: Option<Item> = sqlx::query_as::<_, Item>(
+        "SELECT * FROM items WHERE item_type = 'image' AND perceptual_hash = ? LIMIT 1",
+    )
+    .bind(phash as i64)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let threshold = settings::load_image_similarity_threshold(db).await;
+    if identical.is_some() || threshold == 0 {
+        return Ok(identical);
+    }
+
+    let candidates: Vec<Item> = sqlx::query_as::<_, Item>(
+        "SELECT * FROM items WHERE item_type = 'image' AND perceptual_hash IS NOT NULL",
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(candidates
+        .into_iter()
+        .filter_map(|item| {
+            let distance = image_store::hamming_distance(item.perceptual_hash? as u64, phash);
+            (distance <= threshold).then_some((distance, item))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, item)| item))
+}
+
+// Replaces the stored representations of an item.
+async fn store_formats(
+    db: &DbPool,
+    item_id: i64,
+    formats: &[ClipboardFormat],
+) -> Result<(), String> {
+    if formats.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query("DELETE FROM item_formats WHERE item_id = ?")
+        .bind(item_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to replace item formats: {}", e))?;
+
+    for format in formats {
+        sqlx::query("INSERT INTO item_formats (item_id, mime_type, data) VALUES (?, ?, ?)")
+            .bind(item_id)
+            .bind(&format.mime_type)
+            .bind(&format.data)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to store item format: {}", e))?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_item_formats(id: i64, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let formats: Vec<(String,)> =
+        sqlx::query_as("SELECT mime_type FROM item_formats WHERE item_id = ? ORDER BY id")
+            .bind(id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| format!("Failed to get item formats: {}", e))?;
+
+    Ok(formats.into_iter().map(|(mime_type,)| mime_type).collect())
+}
+
+// Loads every stored representation of an item, including its image file.
+async fn load_formats(db: &DbPool, id: i64) -> Result<Vec<ClipboardFormat>, String> {
+    let item: Item = sqlx::query_as::<_, Item>("SELECT * FROM items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Item {} not found", id))?;
+
+    let mut formats: Vec<ClipboardFormat> = sqlx::query_as::<_, ItemFormat>(
+        "SELECT mime_type, data FROM item_formats WHERE item_id = ? ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|f| ClipboardFormat::new(f.mime_type, f.data))
+    .collect();
+
+    // images live on disk rather than in item_formats
+    if item.item_type == "image"
+        && let Some(ref file_path) = item.file_path
+    {
+        let strip_metadata = settings::load_strip_image_metadata(db).await;
+        let image_formats = image_store::clipboard_formats(Path::new(file_path), strip_metadata)?;
+        formats.splice(0..0, image_formats);
+    }
+
+    // items captured before formats were stored only have their content
+    if find_format(&formats, TEXT_PLAIN).is_none()
+        && matches!(item.item_type.as_str(), "text" | "color")
+    {
+        formats.push(ClipboardFormat::new(TEXT_PLAIN, item.content));
+    }
+
+    Ok(formats)
+}
+
+// Restores every stored representation of an item to the clipboard at once, so rich editors
+// get the formatting while terminals still get plain text. The item moves to the top and its
+// use is counted; the watcher recognises the write and does not capture it again.
+#[tauri::command]
+pub async fn copy_item(
+    app: tauri::AppHandle,
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    restore_item(&state, id).await?;
+    let _ = app.emit("item-updated", serde_json::json!({ "id": id }));
     Ok(())
 }
 
+// Restores an item like `copy_item`, then hides the window and pastes the item into the
+// application that was focused before it was shown. Only copies where pasting is not supported.
 #[tauri::command]
-pub async fn get_items(state: State<'_, AppState>) -> Result<Vec<Item>, String> {
-    let items: Vec<Item> = sqlx::query_as::<_, Item>("SELECT * FROM items ORDER BY bumped_at DESC")
-        .fetch_all(&state.db)
+pub async fn paste_item(
+    app: tauri::AppHandle,
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    copy_item(app.clone(), id, state.clone()).await?;
+    if !auto_paste::SUPPORTED {
+        return Ok(());
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+
+    let options = settings::load_auto_paste(&state.db).await;
+    let target = state.previous_focus.take();
+    tauri::async_runtime::spawn_blocking(move || auto_paste::paste(target.as_ref(), &options))
         .await
-        .map_err(|e| format!("Failed to get items {}", e))?;
+        .map_err(|e| e.to_string())?
+}
+
+// Writes an item to the clipboard as the app's own write and records the use.
+pub async fn restore_item(state: &AppState, id: i64) -> Result<(), String> {
+    let formats = load_formats(&state.db, id).await?;
+    state.self_writes.write(&formats)?;
+    record_use(&state.db, id).await
+}
+
+// Moves the item to the top and counts a use, after it was put back on the clipboard.
+async fn record_use(db: &DbPool, id: i64) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE items
+         SET bumped_at = CURRENT_TIMESTAMP,
+             updated_at = CURRENT_TIMESTAMP,
+             last_used_at = CURRENT_TIMESTAMP,
+             use_count = use_count + 1
+         WHERE id = ?",
+    )
+    .bind(id)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to record item use: {}", e))?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyMode {
+    // every captured representation, as copied originally
+    Rich,
+    // text only, with HTML or RTF formatting stripped
+    Plain,
+    // HTML converted to Markdown
+    Markdown,
+}
+
+// Derives the text for a plain or Markdown copy from the richest captured format.
+pub fn text_for_mode(formats: &[ClipboardFormat], mode: CopyMode) -> Option<String> {
+    let text = |mime: &str| find_format(formats, mime).map(|d| String::from_utf8_lossy(d));
+
+    if let (CopyMode::Markdown, Some(html)) = (mode, text(TEXT_HTML)) {
+        return Some(rich_text::html_to_markdown(&html));
+    }
+
+    text(TEXT_PLAIN)
+        .map(|t| t.into_owned())
+        .or_else(|| text(TEXT_HTML).map(|html| rich_text::html_to_plain_text(&html)))
+        .or_else(|| text(TEXT_RTF).map(|rtf| rich_text::rtf_to_plain_text(&rtf)))
+}
+
+#[tauri::command]
+pub async fn copy_item_as(
+    app: tauri::AppHandle,
+    id: i64,
+    mode: CopyMode,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let formats = load_formats(&state.db, id).await?;
+
+    if let CopyMode::Rich = mode {
+        state.self_writes.write(&formats)?;
+    } else {
+        let text =
+            text_for_mode(&formats, mode).ok_or_else(|| "Item has no text to copy".to_string())?;
+        state
+            .self_writes
+            .write(&[ClipboardFormat::new(TEXT_PLAIN, text)])?;
+    }
+    record_use(&state.db, id).await?;
+    let _ = app.emit("item-updated", serde_json::json!({ "id": id }));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_items(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<Item>, String> {
+    let mut items: Vec<Item> =
+        sqlx::query_as::<_, Item>("SELECT * FROM items ORDER BY bumped_at DESC")
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| format!("Failed to get items {}", e))?;
+
+    let images_dir = image_access::images_dir(&app).ok();
+    for item in items.iter_mut() {
+        match item.item_type.as_str() {
+            "files" => item.stale = item.metadata.as_deref().is_some_and(file_list::is_stale),
+            "image" => {
+                // rows saved before paths were checked may point anywhere
+                let file_path = item.file_path.as_deref().and_then(|path| {
+                    image_access::ensure_within(images_dir.as_deref()?, Path::new(path)).ok()
+                });
+                if let Some(ref file_path) = file_path {
+                    item.thumbnails = thumbnails::existing(file_path);
+
+                    // thumbnails deleted or never generated are rebuilt in the background
+                    if item.thumbnails.len() < thumbnails::THUMBNAIL_SIZES.len() {
+                        state.thumbnails.enqueue(file_path);
+                    }
+                    // images captured before OCR was available
+                    if item.ocr_text.is_none() {
+                        state.ocr.enqueue(i64::from(item.id), file_path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
 
     Ok(items)
 }
@@ -156,7 +499,8 @@ pub async fn delete_item(id: i64, state: State<'_, AppState>) -> Result<(), Stri
         // if it's an image, delete
         if item.item_type == "image" {
             if let Some(file_path) = item.file_path {
-                let _ = std::fs::remove_file(file_path);
+                let _ = std::fs::remove_file(&file_path);
+                thumbnails::remove(Path::new(&file_path));
             }
         }
 
@@ -170,38 +514,34 @@ pub async fn delete_item(id: i64, state: State<'_, AppState>) -> Result<(), Stri
     Ok(())
 }
 
+// Image commands take an item id rather than a path, so the webview can only reach files the
+// app stored in its images directory.
+// Offers the image in its original encoding together with a PNG fallback. `strip_metadata`
+// overrides the setting for this copy.
 #[tauri::command]
-pub async fn get_image_base64(file_path: String) -> Result<String, String> {
-    let bytes =
-        std::fs::read(&file_path).map_err(|e| format!("Failed to read image file: {}", e))?;
-
-    let base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
-    Ok(format!("data:image/png;base64,{}", base64))
-}
-
-#[tauri::command]
-pub async fn write_image_to_clipboard(file_path: String) -> Result<(), String> {
-    use arboard::Clipboard;
-    use image::GenericImageView;
-
-    let img = image::open(&file_path).map_err(|e| format!("Failed to open image: {}", e))?;
-
-    let (width, height) = img.dimensions();
-    let rgba = img.to_rgba8();
-
-    let img_data = arboard::ImageData {
-        width: width as usize,
-        height: height as usize,
-        bytes: rgba.into_raw().into(),
+pub async fn write_image_to_clipboard(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+    strip_metadata: Option<bool>,
+) -> Result<(), ImageAccessError> {
+    let images_dir = image_access::images_dir(&app)?;
+    let path = image_access::resolve_image_path(&state.db, &images_dir, id).await?;
+
+    let strip_metadata = match strip_metadata {
+        Some(strip) => strip,
+        None => settings::load_strip_image_metadata(&state.db).await,
     };
-
-    let mut clipboard =
-        Clipboard::new().map_err(|e| format!("Failed to access clipboard: {}", e))?;
-
-    clipboard
-        .set_image(img_data)
-        .map_err(|e| format!("Failed to set clipboard image: {}", e))?;
-
+    let formats =
+        image_store::clipboard_formats(&path, strip_metadata).map_err(ImageAccessError::Io)?;
+    state
+        .self_writes
+        .write(&formats)
+        .map_err(ImageAccessError::Clipboard)?;
+    record_use(&state.db, id)
+        .await
+        .map_err(ImageAccessError::Database)?;
+    let _ = app.emit("item-updated", serde_json::json!({ "id": id }));
     Ok(())
 }
 
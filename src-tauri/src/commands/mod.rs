@@ -1,2 +1,5 @@
+pub mod codes;
+pub mod colors;
 pub mod hotkeys;
 pub mod items;
+pub mod settings;
@@ -0,0 +1,184 @@
+use crate::auto_paste::{self, AutoPasteOptions};
+use crate::clipboard_watcher;
+use crate::image_store::StorageOptions;
+use crate::state::{AppState, DbPool};
+use tauri::{Emitter, State};
+
+pub const STRIP_IMAGE_METADATA: &str = "strip_image_metadata";
+pub const IMAGE_STORAGE: &str = "image_storage";
+pub const AUTO_PASTE: &str = "auto_paste";
+pub const IMAGE_SIMILARITY_THRESHOLD: &str = "image_similarity_threshold";
+pub const DEFAULT_IMAGE_SIMILARITY_THRESHOLD: u32 = 5;
+// a 64-bit hash differs in at most 64 bits, anything near half of that is unrelated
+pub const MAX_IMAGE_SIMILARITY_THRESHOLD: u32 = 16;
+
+pub async fn get_setting(db: &DbPool, key: &str) -> Result<Option<String>, String> {
+    let result: Option<(String,)> =
+        sqlx::query_as("SELECT value FROM settings WHERE key = ? LIMIT 1")
+            .bind(key)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| format!("Failed to read setting {}: {}", key, e))?;
+
+    Ok(result.map(|(value,)| value))
+}
+
+pub async fn set_setting(db: &DbPool, key: &str, value: &str) -> Result<(), String> {
+    sqlx::query("INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)")
+        .bind(key)
+        .bind(value)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Failed to save setting {}: {}", key, e))?;
+
+    Ok(())
+}
+
+pub async fn load_image_similarity_threshold(db: &DbPool) -> u32 {
+    get_setting(db, IMAGE_SIMILARITY_THRESHOLD)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_IMAGE_SIMILARITY_THRESHOLD)
+}
+
+#[tauri::command]
+pub async fn get_image_similarity_threshold(state: State<'_, AppState>) -> Result<u32, String> {
+    Ok(load_image_similarity_threshold(&state.db).await)
+}
+
+// 0 only merges images whose perceptual hashes are identical.
+#[tauri::command]
+pub async fn set_image_similarity_threshold(
+    state: State<'_, AppState>,
+    threshold: u32,
+) -> Result<(), String> {
+    if threshold > MAX_IMAGE_SIMILARITY_THRESHOLD {
+        return Err(format!(
+            "Similarity threshold must be at most {}",
+            MAX_IMAGE_SIMILARITY_THRESHOLD
+        ));
+    }
+
+    set_setting(
+        &state.db,
+        IMAGE_SIMILARITY_THRESHOLD,
+        &threshold.to_string(),
+    )
+    .await
+}
+
+// Storage options are kept as a single JSON value; fields missing from older values take
+// their defaults.
+pub async fn load_image_storage(db: &DbPool) -> StorageOptions {
+    get_setting(db, IMAGE_STORAGE)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_image_storage(state: State<'_, AppState>) -> Result<StorageOptions, String> {
+    Ok(state.image_storage.get())
+}
+
+// Applies to images captured from now on; stored images are left as they are.
+#[tauri::command]
+pub async fn set_image_storage(
+    state: State<'_, AppState>,
+    options: StorageOptions,
+) -> Result<(), String> {
+    options.validate()?;
+
+    let value = serde_json::to_string(&options)
+        .map_err(|e| format!("Failed to save image storage settings: {}", e))?;
+    set_setting(&state.db, IMAGE_STORAGE, &value).await?;
+
+    state.image_storage.set(options);
+    Ok(())
+}
+
+pub async fn load_strip_image_metadata(db: &DbPool) -> bool {
+    get_setting(db, STRIP_IMAGE_METADATA)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|value| value == "true")
+}
+
+#[tauri::command]
+pub async fn get_strip_image_metadata(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(load_strip_image_metadata(&state.db).await)
+}
+
+#[tauri::command]
+pub async fn set_strip_image_metadata(
+    state: State<'_, AppState>,
+    strip_metadata: bool,
+) -> Result<(), String> {
+    let value = if strip_metadata { "true" } else { "false" };
+    set_setting(&state.db, STRIP_IMAGE_METADATA, value).await
+}
+
+// Whether image items get their text recognised, which needs the `ocr` feature and Tesseract.
+#[tauri::command]
+pub fn get_ocr_available(state: State<'_, AppState>) -> bool {
+    state.ocr.is_enabled()
+}
+
+// Whether picked items can be pasted into other applications on this platform.
+#[tauri::command]
+pub fn get_auto_paste_available() -> bool {
+    auto_paste::SUPPORTED
+}
+
+// Kept as a single JSON value like the storage options. Reads as disabled where pasting is not
+// supported, so a settings file carried over from Linux falls back to copying.
+pub async fn load_auto_paste(db: &DbPool) -> AutoPasteOptions {
+    let options: AutoPasteOptions = get_setting(db, AUTO_PASTE)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+    AutoPasteOptions {
+        enabled: options.enabled && auto_paste::SUPPORTED,
+        ..options
+    }
+}
+
+#[tauri::command]
+pub async fn get_auto_paste(state: State<'_, AppState>) -> Result<AutoPasteOptions, String> {
+    Ok(load_auto_paste(&state.db).await)
+}
+
+#[tauri::command]
+pub async fn set_auto_paste(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    options: AutoPasteOptions,
+) -> Result<(), String> {
+    options.validate()?;
+
+    let value = serde_json::to_string(&options)
+        .map_err(|e| format!("Failed to save auto-paste settings: {}", e))?;
+    set_setting(&state.db, AUTO_PASTE, &value).await?;
+
+    // the main window keeps its own copy of the options
+    let _ = app.emit("auto-paste-changed", &options);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_paused() -> bool {
+    clipboard_watcher::is_capture_paused()
+}
+
+// Not saved, capture always starts again with the app.
+#[tauri::command]
+pub fn set_capture_paused(app: tauri::AppHandle, paused: bool) {
+    clipboard_watcher::set_capture_paused(&app, paused);
+}
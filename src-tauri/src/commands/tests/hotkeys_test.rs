@@ -1,4 +1,4 @@
-use super::parse_hotkey;
+use super::{Chord, canonical_hotkey, format_hotkey, parse_hotkey, resolve_chord};
 use tauri_plugin_global_shortcut::{Code, Modifiers};
 
 #[test]
@@ -89,3 +89,416 @@ fn test_parse_whitespace_handling() {
     assert!(result.mods.contains(Modifiers::CONTROL));
     assert_eq!(result.key, Code::KeyA);
 }
+
+#[test]
+fn test_parse_named_keys() {
+    assert_eq!(parse_hotkey("Ctrl+F5").unwrap().key, Code::F5);
+    assert_eq!(parse_hotkey("Ctrl+f12").unwrap().key, Code::F12);
+    assert_eq!(parse_hotkey("Alt+Space").unwrap().key, Code::Space);
+    assert_eq!(parse_hotkey("Alt+Enter").unwrap().key, Code::Enter);
+    assert_eq!(parse_hotkey("Alt+ArrowUp").unwrap().key, Code::ArrowUp);
+    assert_eq!(parse_hotkey("Alt+left").unwrap().key, Code::ArrowLeft);
+    assert_eq!(parse_hotkey("Shift+Insert").unwrap().key, Code::Insert);
+    assert_eq!(parse_hotkey("Ctrl+Numpad7").unwrap().key, Code::Numpad7);
+    assert_eq!(parse_hotkey("Ctrl+NumpadAdd").unwrap().key, Code::NumpadAdd);
+    assert_eq!(parse_hotkey("Ctrl+KeyV").unwrap().key, Code::KeyV);
+}
+
+#[test]
+fn test_parse_punctuation() {
+    assert_eq!(parse_hotkey("Ctrl+-").unwrap().key, Code::Minus);
+    assert_eq!(parse_hotkey("Ctrl+Minus").unwrap().key, Code::Minus);
+    assert_eq!(parse_hotkey("Ctrl+/").unwrap().key, Code::Slash);
+    assert_eq!(parse_hotkey("Ctrl+\\").unwrap().key, Code::Backslash);
+    assert_eq!(parse_hotkey("Ctrl+[").unwrap().key, Code::BracketLeft);
+    assert_eq!(parse_hotkey("Ctrl+'").unwrap().key, Code::Quote);
+}
+
+#[test]
+fn test_parse_lowercase_letters() {
+    assert_eq!(parse_hotkey("ctrl+v").unwrap().key, Code::KeyV);
+}
+
+#[test]
+fn test_parse_rejects_modifier_only() {
+    let result = parse_hotkey("Ctrl+Shift");
+    assert!(result.unwrap_err().contains("needs a key"));
+
+    assert!(parse_hotkey("Ctrl+ShiftLeft").is_err());
+    assert!(parse_hotkey("Ctrl+").is_err());
+}
+
+#[test]
+fn test_parse_rejects_multiple_keys() {
+    let result = parse_hotkey("Ctrl+A+B");
+    assert!(result.unwrap_err().contains("one key"));
+}
+
+#[test]
+fn test_format_orders_modifiers() {
+    let shortcut = parse_hotkey("shift+alt+ctrl+v").unwrap();
+    assert_eq!(format_hotkey(&shortcut, None), "Ctrl+Alt+Shift+V");
+
+    assert_eq!(
+        format_hotkey(&parse_hotkey("Alt+9").unwrap(), None),
+        "Alt+9"
+    );
+    assert_eq!(
+        format_hotkey(&parse_hotkey("Alt+Backquote").unwrap(), None),
+        "Alt+`"
+    );
+    assert_eq!(
+        format_hotkey(&parse_hotkey("Alt+up").unwrap(), None),
+        "Alt+ArrowUp"
+    );
+    assert_eq!(
+        format_hotkey(&parse_hotkey("Alt+f3").unwrap(), None),
+        "Alt+F3"
+    );
+}
+
+#[test]
+fn test_format_round_trips() {
+    for hotkey in [
+        "Ctrl+A",
+        "Alt+Q",
+        "Cmd+`",
+        "Super+Space",
+        "Ctrl+Shift+Numpad0",
+        "Ctrl+Alt+F12",
+        "Shift+Insert",
+        "Ctrl+\\",
+        "Ctrl+.",
+        "Alt+PageDown",
+    ] {
+        let shortcut = parse_hotkey(hotkey).unwrap();
+        let formatted = format_hotkey(&shortcut, None);
+        assert_eq!(parse_hotkey(&formatted).unwrap(), shortcut, "{}", hotkey);
+        assert_eq!(
+            format_hotkey(&parse_hotkey(&formatted).unwrap(), None),
+            formatted
+        );
+    }
+}
+
+#[test]
+fn test_find_conflict_compares_key_combinations() {
+    use super::{HotkeyBinding, find_conflict};
+    use crate::hotkey_actions::HotkeyAction;
+
+    let bindings = vec![
+        HotkeyBinding {
+            hotkey: "Ctrl+Alt+1".to_string(),
+            code: None,
+            action: HotkeyAction::PasteRecent { index: 1 },
+        },
+        HotkeyBinding {
+            hotkey: "Ctrl+Alt+P".to_string(),
+            code: Some("KeyP".to_string()),
+            action: HotkeyAction::PauseCapture,
+        },
+    ];
+
+    let single = |hotkey| Chord::single(parse_hotkey(hotkey).unwrap());
+    let conflict = find_conflict(&bindings, &single("alt+ctrl+P"));
+    assert_eq!(conflict.map(|b| b.action), Some(HotkeyAction::PauseCapture));
+    assert!(find_conflict(&bindings, &single("Ctrl+Alt+2")).is_none());
+
+    let chord = resolve_chord("Ctrl+Alt+P, 1", None, None).unwrap();
+    assert!(find_conflict(&bindings, &chord).is_some());
+}
+
+#[test]
+fn test_split_chord_keeps_the_comma_key() {
+    use super::split_chord;
+
+    assert_eq!(split_chord("Ctrl+Alt+V, 3"), vec!["Ctrl+Alt+V", "3"]);
+    assert_eq!(split_chord("Ctrl+,"), vec!["Ctrl+,"]);
+    assert_eq!(split_chord("Ctrl+, ,"), vec!["Ctrl+,", ""]);
+    assert_eq!(split_chord("Ctrl+V"), vec!["Ctrl+V"]);
+}
+
+#[test]
+fn test_resolve_chord() {
+    let chord = resolve_chord("Ctrl+Alt+V, 3", None, None).unwrap();
+    assert_eq!(chord.leader, parse_hotkey("Ctrl+Alt+V").unwrap());
+    assert_eq!(chord.key, Some(parse_hotkey("3").unwrap()));
+    assert_eq!(chord.code(), "KeyV, Digit3");
+
+    let chord = resolve_chord("Ctrl+Alt+A, Q", Some("KeyQ, KeyA"), None).unwrap();
+    assert_eq!(chord.leader.key, Code::KeyQ);
+    assert_eq!(chord.key.unwrap().key, Code::KeyA);
+
+    assert!(resolve_chord("Ctrl+V, 1, 2", None, None).is_err());
+    assert!(resolve_chord("Ctrl+V, 1", Some("KeyV"), None).is_err());
+    assert!(resolve_chord("Ctrl+V, ", None, None).is_err());
+}
+
+#[test]
+fn test_chord_conflicts() {
+    let chord = |hotkey| resolve_chord(hotkey, None, None).unwrap();
+
+    assert!(chord("Ctrl+Alt+V, 1").conflicts_with(&chord("alt+ctrl+v, 1")));
+    assert!(!chord("Ctrl+Alt+V, 1").conflicts_with(&chord("Ctrl+Alt+V, 2")));
+    // a leader cannot also be bound on its own
+    assert!(chord("Ctrl+Alt+V, 1").conflicts_with(&chord("Ctrl+Alt+V")));
+    assert!(chord("Ctrl+Alt+V").conflicts_with(&chord("Ctrl+Alt+V, 1")));
+    // nor be the key after another leader
+    assert!(chord("Ctrl+Alt+V, Ctrl+B").conflicts_with(&chord("Ctrl+B, 1")));
+    assert!(!chord("Ctrl+Alt+V, 1").conflicts_with(&chord("Ctrl+Alt+B, 1")));
+}
+
+#[test]
+fn test_canonical_chord() {
+    assert_eq!(canonical_hotkey("alt+ctrl+v,3"), "Ctrl+Alt+V, 3");
+    assert_eq!(canonical_hotkey("ctrl+,"), "Ctrl+,");
+}
+
+#[test]
+fn test_error_serializes_with_kind() {
+    use super::HotkeyError;
+
+    let error = HotkeyError::Parse("Unsupported key: Nope".to_string());
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        serde_json::json!({ "kind": "parse", "message": "Unsupported key: Nope" })
+    );
+
+    let error = HotkeyError::Conflict("Ctrl+Alt+1 is already bound".to_string());
+    assert_eq!(serde_json::to_value(&error).unwrap()["kind"], "conflict");
+    assert_eq!(
+        error.to_string(),
+        "Hotkey is taken: Ctrl+Alt+1 is already bound"
+    );
+}
+
+#[test]
+fn test_resolve_looks_symbols_up_in_the_layout() {
+    use super::resolve_hotkey;
+    use crate::keyboard_layout::KeyboardLayout;
+
+    let azerty = KeyboardLayout::new(vec![
+        (Code::KeyQ, [Some('a'), Some('A')]),
+        (Code::KeyA, [Some('q'), Some('Q')]),
+        (Code::Digit2, [Some('é'), Some('2')]),
+    ]);
+
+    let shortcut = resolve_hotkey("Ctrl+A", None, Some(&azerty)).unwrap();
+    assert_eq!(shortcut.key, Code::KeyQ);
+    assert!(shortcut.mods.contains(Modifiers::CONTROL));
+    assert_eq!(
+        resolve_hotkey("Ctrl+é", None, Some(&azerty)).unwrap().key,
+        Code::Digit2
+    );
+
+    // names are physical keys on every layout
+    assert_eq!(
+        resolve_hotkey("Ctrl+Space", None, Some(&azerty))
+            .unwrap()
+            .key,
+        Code::Space
+    );
+    // symbols the layout lacks keep their US position
+    assert_eq!(
+        resolve_hotkey("Ctrl+Z", None, Some(&azerty)).unwrap().key,
+        Code::KeyZ
+    );
+    assert!(resolve_hotkey("Ctrl+é", None, None).is_err());
+}
+
+#[test]
+fn test_resolve_prefers_the_saved_code() {
+    use super::resolve_hotkey;
+    use crate::keyboard_layout::KeyboardLayout;
+
+    let azerty = KeyboardLayout::new(vec![(Code::KeyQ, [Some('a'), Some('A')])]);
+
+    // recorded on QWERTY, still the same key after switching to AZERTY
+    let shortcut = resolve_hotkey("Ctrl+A", Some("KeyA"), Some(&azerty)).unwrap();
+    assert_eq!(shortcut.key, Code::KeyA);
+    assert!(resolve_hotkey("Ctrl+A", Some("NoSuchKey"), None).is_err());
+}
+
+#[test]
+fn test_canonical_keeps_layout_symbols() {
+    use super::canonical_hotkey;
+
+    assert_eq!(canonical_hotkey("shift+ctrl+a"), "Ctrl+Shift+A");
+    assert_eq!(canonical_hotkey("Ctrl+é"), "Ctrl+É");
+    assert_eq!(canonical_hotkey("Alt+Backquote"), "Alt+`");
+    // no single uppercase letter
+    assert_eq!(canonical_hotkey("Ctrl+ß"), "Ctrl+ß");
+    assert_eq!(canonical_hotkey("Ctrl+Nope"), "Ctrl+Nope");
+}
+
+#[test]
+fn test_format_names_keys_after_the_layout() {
+    use crate::keyboard_layout::KeyboardLayout;
+
+    let azerty = KeyboardLayout::new(vec![
+        (Code::KeyQ, [Some('a'), Some('A')]),
+        (Code::Digit1, [Some('&'), Some('1')]),
+    ]);
+
+    let shortcut = parse_hotkey("Ctrl+Q").unwrap();
+    assert_eq!(format_hotkey(&shortcut, Some(&azerty)), "Ctrl+A");
+    assert_eq!(
+        format_hotkey(&parse_hotkey("Ctrl+Shift+1").unwrap(), Some(&azerty)),
+        "Ctrl+Shift+&"
+    );
+    assert_eq!(
+        format_hotkey(&parse_hotkey("Ctrl+F1").unwrap(), Some(&azerty)),
+        "Ctrl+F1"
+    );
+}
+
+#[test]
+fn test_hybrid_tap_toggles() {
+    use super::{HybridRelease, hybrid_release};
+    use std::time::Duration;
+
+    let threshold = Duration::from_millis(300);
+    // a tap opens a hidden window and leaves it open
+    assert_eq!(
+        hybrid_release(Duration::from_millis(80), threshold, false),
+        HybridRelease::KeepOpen
+    );
+    // and closes an open one without copying the selection
+    assert_eq!(
+        hybrid_release(Duration::from_millis(80), threshold, true),
+        HybridRelease::Hide
+    );
+}
+
+#[test]
+fn test_hybrid_hold_shows_while_held() {
+    use super::{HybridRelease, hybrid_release};
+    use std::time::Duration;
+
+    let threshold = Duration::from_millis(300);
+    assert_eq!(
+        hybrid_release(Duration::from_millis(300), threshold, false),
+        HybridRelease::CopyAndHide
+    );
+    // holding while it was already open only closes it
+    assert_eq!(
+        hybrid_release(Duration::from_secs(2), threshold, true),
+        HybridRelease::Hide
+    );
+}
+
+#[test]
+fn test_parse_hold_threshold() {
+    use super::{DEFAULT_HOLD_THRESHOLD_MS, parse_hold_threshold};
+
+    assert_eq!(parse_hold_threshold(Some("500")), 500);
+    assert_eq!(parse_hold_threshold(None), DEFAULT_HOLD_THRESHOLD_MS);
+    assert_eq!(
+        parse_hold_threshold(Some("fast")),
+        DEFAULT_HOLD_THRESHOLD_MS
+    );
+    assert_eq!(parse_hold_threshold(Some("10")), DEFAULT_HOLD_THRESHOLD_MS);
+    assert_eq!(
+        parse_hold_threshold(Some("60000")),
+        DEFAULT_HOLD_THRESHOLD_MS
+    );
+}
+
+#[test]
+fn test_saved_hotkeys_keep_their_us_position() {
+    use super::{DEFAULT_HOTKEY, HOTKEY_CODE, load_main_hotkey, resolve_hotkey};
+    use crate::commands::settings::{get_setting, set_setting};
+    use crate::keyboard_layout::KeyboardLayout;
+
+    let azerty = KeyboardLayout::new(vec![
+        (Code::KeyQ, [Some('a'), Some('A')]),
+        (Code::KeyA, [Some('q'), Some('Q')]),
+        (Code::Backquote, [Some('²'), None]),
+    ]);
+
+    tauri::async_runtime::block_on(async {
+        let db = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // saved before the physical key was kept, it was always the key labelled A on QWERTY
+        set_setting(&db, "hotkey", "Ctrl+A").await.unwrap();
+        let (hotkey, shortcut) = load_main_hotkey(&db, Some(&azerty)).await.unwrap();
+        assert_eq!(hotkey, "Ctrl+A");
+        assert_eq!(shortcut.key, Code::KeyA);
+        assert_eq!(
+            get_setting(&db, HOTKEY_CODE).await.unwrap().as_deref(),
+            Some("KeyA")
+        );
+
+        // the default follows the layout
+        sqlx::query("DELETE FROM settings")
+            .execute(&db)
+            .await
+            .unwrap();
+        let (_, shortcut) = load_main_hotkey(&db, Some(&azerty)).await.unwrap();
+        assert_eq!(
+            shortcut,
+            resolve_hotkey(DEFAULT_HOTKEY, None, Some(&azerty)).unwrap()
+        );
+    });
+}
+
+#[test]
+fn test_hotkey_behavior_names() {
+    use super::HotkeyBehavior;
+
+    for behavior in HotkeyBehavior::ALL {
+        assert_eq!(HotkeyBehavior::parse(behavior.as_str()), Some(behavior));
+        assert_eq!(
+            serde_json::to_string(&behavior).unwrap(),
+            format!("\"{}\"", behavior.as_str())
+        );
+    }
+    assert_eq!(HotkeyBehavior::parse("true"), None);
+}
+
+#[test]
+fn test_hotkey_behavior_migration_keeps_the_active_mode() {
+    use super::{HotkeyBehavior, load_hotkey_behavior_from_db};
+
+    let migration = include_str!("../../../migrations/20260205000000_add_hotkey_behavior.sql");
+    let cases: [(&[(&str, &str)], HotkeyBehavior); 4] = [
+        (&[("hold_behavior", "false")], HotkeyBehavior::Toggle),
+        (&[("hold_behavior", "true")], HotkeyBehavior::Hold),
+        (
+            &[("hold_behavior", "true"), ("hybrid_behavior", "true")],
+            HotkeyBehavior::Hybrid,
+        ),
+        (
+            &[("hybrid_behavior", "true"), ("cycle_behavior", "true")],
+            HotkeyBehavior::Cycle,
+        ),
+    ];
+
+    tauri::async_runtime::block_on(async {
+        for (rows, expected) in cases {
+            let db = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+            sqlx::query("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+                .execute(&db)
+                .await
+                .unwrap();
+            for (key, value) in rows {
+                crate::commands::settings::set_setting(&db, key, value)
+                    .await
+                    .unwrap();
+            }
+
+            sqlx::raw_sql(migration).execute(&db).await.unwrap();
+
+            assert_eq!(load_hotkey_behavior_from_db(&db).await, expected);
+            let (left,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM settings")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+            assert_eq!(left, 1);
+        }
+    });
+}
@@ -1,3 +1,5 @@
+use super::{CopyMode, text_for_mode};
+use crate::clipboard_formats::{ClipboardFormat, TEXT_HTML, TEXT_PLAIN, TEXT_RTF};
 use std::fs;
 use tempfile::TempDir;
 
@@ -101,3 +103,101 @@ fn test_clear_images_directory_not_exists() {
     // Should still not exist (we didn't create it)
     assert!(!images_dir.exists());
 }
+
+#[test]
+fn test_plain_copy_prefers_captured_plain_text() {
+    let formats = vec![
+        ClipboardFormat::new(TEXT_HTML, "<p><b>Hello</b> world</p>"),
+        ClipboardFormat::new(TEXT_PLAIN, "Hello world (source)"),
+    ];
+
+    assert_eq!(
+        text_for_mode(&formats, CopyMode::Plain),
+        Some("Hello world (source)".to_string())
+    );
+}
+
+#[test]
+fn test_plain_copy_strips_html_without_plain_text() {
+    let formats = vec![ClipboardFormat::new(TEXT_HTML, "<p><b>Hello</b> world</p>")];
+
+    assert_eq!(
+        text_for_mode(&formats, CopyMode::Plain),
+        Some("Hello world".to_string())
+    );
+}
+
+#[test]
+fn test_plain_copy_from_rtf() {
+    let formats = vec![ClipboardFormat::new(
+        TEXT_RTF,
+        r"{\rtf1\ansi Hello \b world\b0}",
+    )];
+
+    assert_eq!(
+        text_for_mode(&formats, CopyMode::Plain),
+        Some("Hello world".to_string())
+    );
+}
+
+#[test]
+fn test_markdown_copy_converts_html() {
+    let formats = vec![
+        ClipboardFormat::new(TEXT_PLAIN, "Hello world"),
+        ClipboardFormat::new(TEXT_HTML, "<p><b>Hello</b> world</p>"),
+    ];
+
+    assert_eq!(
+        text_for_mode(&formats, CopyMode::Markdown),
+        Some("**Hello** world".to_string())
+    );
+}
+
+#[test]
+fn test_markdown_copy_falls_back_to_plain_text() {
+    let formats = vec![ClipboardFormat::new(TEXT_PLAIN, "just text")];
+
+    assert_eq!(
+        text_for_mode(&formats, CopyMode::Markdown),
+        Some("just text".to_string())
+    );
+}
+
+#[test]
+fn test_copy_without_text_formats() {
+    let formats = vec![ClipboardFormat::new("image/png", vec![0u8, 1, 2])];
+    assert_eq!(text_for_mode(&formats, CopyMode::Plain), None);
+}
+
+#[test]
+fn test_images_outside_the_images_dir_are_rejected_at_insert() {
+    use super::stored_file_path;
+    use crate::image_access::ImageAccessError;
+
+    let temp_dir = TempDir::new().unwrap();
+    let images_dir = temp_dir.path().join("images");
+    fs::create_dir_all(&images_dir).unwrap();
+    let image = images_dir.join("123.png");
+    fs::write(&image, b"png").unwrap();
+    let secret = temp_dir.path().join("secret.txt");
+    fs::write(&secret, b"secret").unwrap();
+
+    assert_eq!(
+        stored_file_path(&images_dir, "image", Some(image.display().to_string())).unwrap(),
+        Some(image.canonicalize().unwrap().display().to_string())
+    );
+    assert!(matches!(
+        stored_file_path(&images_dir, "image", Some(secret.display().to_string())),
+        Err(ImageAccessError::OutsideImagesDir(_))
+    ));
+    let traversal = images_dir.join("..").join("secret.txt");
+    assert!(matches!(
+        stored_file_path(&images_dir, "image", Some(traversal.display().to_string())),
+        Err(ImageAccessError::OutsideImagesDir(_))
+    ));
+    // only images keep a file
+    assert_eq!(
+        stored_file_path(&images_dir, "text", Some(secret.display().to_string())).unwrap(),
+        None
+    );
+}
@@ -0,0 +1,132 @@
+use crate::clipboard_formats::{ClipboardFormat, GNOME_COPIED_FILES, TEXT_PLAIN, TEXT_URI_LIST};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+pub const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "tiff", "tif", "svg",
+];
+
+// A copied file or folder as stored in the item metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    pub size: Option<u64>,
+    // seconds since the Unix epoch
+    pub modified: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FilesMetadata {
+    pub files: Vec<FileEntry>,
+}
+
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+// A single copied image file is captured as an image item instead of a file list.
+pub fn is_single_image(paths: &[PathBuf]) -> bool {
+    paths.len() == 1 && is_image_path(&paths[0])
+}
+
+// Parses a `text/uri-list` or `x-special/gnome-copied-files` payload.
+// Returns `None` unless every entry is a local `file://` URI.
+pub fn parse_uri_list(text: &str) -> Option<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for (index, line) in text.lines().map(str::trim).enumerate() {
+        // comments are allowed by RFC 2483, GNOME prefixes the list with the operation
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if index == 0 && (line == "copy" || line == "cut") {
+            continue;
+        }
+
+        let path = line.strip_prefix("file://")?;
+        // file://localhost/path is the same as file:///path
+        let path = path.strip_prefix("localhost").unwrap_or(path);
+        if !path.starts_with('/') {
+            return None;
+        }
+
+        let decoded = urlencoding::decode(path).ok()?;
+        paths.push(PathBuf::from(decoded.as_ref()));
+    }
+
+    (!paths.is_empty()).then_some(paths)
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let encoded: Vec<String> = path
+        .to_string_lossy()
+        .split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect();
+    format!("file://{}", encoded.join("/"))
+}
+
+// The item content shown in the list and used for deduplication.
+pub fn display_content(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn describe(paths: &[PathBuf]) -> FilesMetadata {
+    let files = paths
+        .iter()
+        .map(|path| {
+            let meta = std::fs::metadata(path).ok();
+            let is_dir = meta.as_ref().is_some_and(|m| m.is_dir());
+
+            FileEntry {
+                path: path.to_string_lossy().into_owned(),
+                name: path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                is_dir,
+                // folder sizes would need a full walk, so only files report one
+                size: meta.as_ref().filter(|_| !is_dir).map(|m| m.len()),
+                modified: meta
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs()),
+            }
+        })
+        .collect();
+
+    FilesMetadata { files }
+}
+
+// Whether any file referenced by a files item no longer exists.
+pub fn is_stale(metadata: &str) -> bool {
+    serde_json::from_str::<FilesMetadata>(metadata)
+        .map(|meta| meta.files.iter().any(|f| !Path::new(&f.path).exists()))
+        .unwrap_or(false)
+}
+
+// The representations file managers expect when pasting a file list.
+pub fn clipboard_formats(paths: &[PathBuf]) -> Vec<ClipboardFormat> {
+    let uris: Vec<String> = paths.iter().map(|p| path_to_uri(p)).collect();
+
+    vec![
+        // RFC 2483 lines end with CRLF
+        ClipboardFormat::new(TEXT_URI_LIST, format!("{}\r\n", uris.join("\r\n"))),
+        // always restored as a copy, restoring a cut would move the files again
+        ClipboardFormat::new(GNOME_COPIED_FILES, format!("copy\n{}", uris.join("\n"))),
+        ClipboardFormat::new(TEXT_PLAIN, display_content(paths)),
+    ]
+}
+
+#[cfg(test)]
+#[path = "./tests/file_list_test.rs"]
+mod file_list_test;
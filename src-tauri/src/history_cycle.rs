@@ -0,0 +1,278 @@
+// Alt-Tab style history: while the hotkey modifiers are held each press of the key moves the
+// selection one item further back, releasing them copies the selected item. The selection lives
+// here rather than in the webview so it keeps up with fast presses.
+use crate::auto_paste;
+use crate::commands::hotkeys::move_to_tray_or_center;
+use crate::commands::{items, settings};
+use crate::state::{AppState, DbPool};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::Modifiers;
+
+// how far back a cycle reaches
+pub const CYCLE_LENGTH: i64 = 10;
+
+const MODIFIER_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// where the modifiers cannot be read, the cycle ends once the key has not been pressed for this
+// long
+const IDLE_TIMEOUT: Duration = Duration::from_millis(900);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Press {
+    // the first press, the items still have to be loaded
+    Started,
+    // a later press, with the newly selected item
+    Moved(Option<i64>),
+}
+
+#[derive(Debug, Default)]
+struct Cycle {
+    active: bool,
+    items: Vec<i64>,
+    presses: usize,
+    last_press: Option<Instant>,
+}
+
+impl Cycle {
+    // the first press selects the item before the current one, like Alt-Tab
+    fn selected(&self) -> Option<i64> {
+        if self.items.is_empty() {
+            return None;
+        }
+        Some(self.items[self.presses % self.items.len()])
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct HistoryCycle(Arc<Mutex<Cycle>>);
+
+impl HistoryCycle {
+    pub fn press(&self) -> Press {
+        let mut cycle = self.0.lock().unwrap();
+        cycle.last_press = Some(Instant::now());
+        cycle.presses += 1;
+
+        if cycle.active {
+            Press::Moved(cycle.selected())
+        } else {
+            cycle.active = true;
+            cycle.items.clear();
+            cycle.presses = 1;
+            Press::Started
+        }
+    }
+
+    // Sets the items to cycle through, newest first, and returns the selection. Presses made
+    // while they were loading are counted.
+    pub fn load(&self, items: Vec<i64>) -> Option<i64> {
+        let mut cycle = self.0.lock().unwrap();
+        cycle.items = items;
+        cycle.selected()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.0.lock().unwrap().active
+    }
+
+    pub fn idle_for(&self) -> Duration {
+        self.0
+            .lock()
+            .unwrap()
+            .last_press
+            .map_or(Duration::ZERO, |at| at.elapsed())
+    }
+
+    // Ends the cycle and returns the item to copy.
+    pub fn finish(&self) -> Option<i64> {
+        let mut cycle = self.0.lock().unwrap();
+        let selected = cycle.selected();
+        *cycle = Cycle::default();
+        selected
+    }
+}
+
+pub async fn recent_item_ids(db: &DbPool, limit: i64) -> Result<Vec<i64>, String> {
+    let rows: Vec<(i64,)> = sqlx::query_as("SELECT id FROM items ORDER BY bumped_at DESC LIMIT ?")
+        .bind(limit)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to get items: {}", e))?;
+
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+// Called from the hotkey handler on every press of the hotkey in cycle mode. `modifiers` are
+// the hotkey's, the cycle ends when they are released.
+pub fn on_press(app: &AppHandle, modifiers: Modifiers) {
+    let state = app.state::<AppState>();
+
+    match state.history_cycle.press() {
+        Press::Moved(selected) => emit_selected(app, selected),
+        Press::Started => {
+            auto_paste::remember_focus(app);
+
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                run(&app, modifiers).await;
+            });
+        }
+    }
+}
+
+async fn run(app: &AppHandle, modifiers: Modifiers) {
+    let state = app.state::<AppState>();
+
+    let ids = recent_item_ids(&state.db, CYCLE_LENGTH)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("[X] {}", e);
+            Vec::new()
+        });
+    let selected = state.history_cycle.load(ids);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        move_to_tray_or_center(&window);
+        let _ = window.show();
+    }
+    emit_selected(app, selected);
+
+    let cycle = state.history_cycle.clone();
+    let _ = tauri::async_runtime::spawn_blocking(move || wait_for_release(&cycle, modifiers)).await;
+
+    finish(app).await;
+}
+
+fn emit_selected(app: &AppHandle, selected: Option<i64>) {
+    if let Some(id) = selected {
+        let _ = app.emit("cycle-selected", serde_json::json!({ "id": id }));
+    }
+}
+
+// Whether releasing the hotkey modifiers can be seen. Elsewhere a cycle ends after
+// `IDLE_TIMEOUT` without a press, which the settings page points out.
+pub fn detects_release() -> bool {
+    ModifierReader::connect().is_some()
+}
+
+fn wait_for_release(cycle: &HistoryCycle, modifiers: Modifiers) {
+    let reader = ModifierReader::connect();
+
+    while cycle.is_active() {
+        thread::sleep(MODIFIER_POLL_INTERVAL);
+
+        let held = if modifiers.is_empty() {
+            None
+        } else {
+            reader
+                .as_ref()
+                .and_then(|reader| reader.any_held(modifiers))
+        };
+        match held {
+            Some(true) => {}
+            Some(false) => return,
+            None if cycle.idle_for() >= IDLE_TIMEOUT => return,
+            None => {}
+        }
+    }
+}
+
+async fn finish(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let selected = state.history_cycle.finish();
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    let _ = app.emit("cycle-finished", ());
+
+    let Some(id) = selected else {
+        return;
+    };
+    if let Err(e) = items::restore_item(&state, id).await {
+        eprintln!("[X] Failed to copy item {}: {}", id, e);
+        return;
+    }
+    let _ = app.emit("item-updated", serde_json::json!({ "id": id }));
+
+    let options = settings::load_auto_paste(&state.db).await;
+    let target = state.previous_focus.take();
+    if options.enabled {
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            auto_paste::paste(target.as_ref(), &options)
+        })
+        .await;
+        if let Ok(Err(e)) = result {
+            eprintln!("[X] Failed to paste item {}: {}", id, e);
+        }
+    }
+}
+
+// Reads which modifiers are held anywhere on the desktop, which only X11 allows.
+#[cfg(target_os = "linux")]
+struct ModifierReader {
+    conn: x11rb::rust_connection::RustConnection,
+    root: x11rb::protocol::xproto::Window,
+}
+
+#[cfg(target_os = "linux")]
+impl ModifierReader {
+    fn connect() -> Option<Self> {
+        use x11rb::connection::Connection;
+
+        if std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty()) {
+            return None;
+        }
+        let (conn, screen) = x11rb::rust_connection::RustConnection::connect(None).ok()?;
+        let root = conn.setup().roots[screen].root;
+        Some(Self { conn, root })
+    }
+
+    fn any_held(&self, modifiers: Modifiers) -> Option<bool> {
+        use x11rb::protocol::xproto::ConnectionExt;
+
+        let mask = self.conn.query_pointer(self.root).ok()?.reply().ok()?.mask;
+        Some(
+            modifier_masks(modifiers)
+                .into_iter()
+                .any(|m| mask.contains(m)),
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn modifier_masks(modifiers: Modifiers) -> Vec<x11rb::protocol::xproto::KeyButMask> {
+    use x11rb::protocol::xproto::KeyButMask;
+
+    [
+        (Modifiers::CONTROL, KeyButMask::CONTROL),
+        (Modifiers::SHIFT, KeyButMask::SHIFT),
+        (Modifiers::ALT, KeyButMask::MOD1),
+        (Modifiers::SUPER, KeyButMask::MOD4),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| modifiers.contains(*modifier))
+    .map(|(_, mask)| mask)
+    .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+struct ModifierReader;
+
+#[cfg(not(target_os = "linux"))]
+impl ModifierReader {
+    fn connect() -> Option<Self> {
+        None
+    }
+
+    fn any_held(&self, _modifiers: Modifiers) -> Option<bool> {
+        None
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/history_cycle_test.rs"]
+mod history_cycle_test;
@@ -0,0 +1,131 @@
+// What a global hotkey does. The main hotkey toggles the window; further hotkeys can be bound
+// to any of these actions from the settings.
+use crate::auto_paste;
+use crate::clipboard_formats;
+use crate::clipboard_watcher;
+use crate::commands::hotkeys::move_to_tray_or_center;
+use crate::commands::{items, settings};
+use crate::history_cycle::recent_item_ids;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+// how far back a paste-recent hotkey may reach
+pub const MAX_PASTE_INDEX: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HotkeyAction {
+    ToggleWindow,
+    // the `index`th most recent item, 1 being the newest
+    PasteRecent { index: usize },
+    // swaps the two newest items and pastes the one that was second
+    PastePrevious,
+    // toggles capturing of new copies
+    PauseCapture,
+    ClearClipboard,
+    // shows the window with the search field focused
+    OpenSearch,
+}
+
+impl HotkeyAction {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            HotkeyAction::PasteRecent { index } if !(1..=MAX_PASTE_INDEX).contains(index) => Err(
+                format!("Item to paste must be between 1 and {}", MAX_PASTE_INDEX),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn run(app: &AppHandle, action: HotkeyAction) {
+    println!("[I] Running hotkey action: {:?}", action);
+
+    match action {
+        HotkeyAction::ToggleWindow => toggle_window(app),
+        HotkeyAction::PasteRecent { index } => paste_recent(app, index),
+        HotkeyAction::PastePrevious => paste_recent(app, 2),
+        HotkeyAction::PauseCapture => {
+            clipboard_watcher::set_capture_paused(app, !clipboard_watcher::is_capture_paused())
+        }
+        HotkeyAction::ClearClipboard => {
+            if let Err(e) = clipboard_formats::clear_clipboard() {
+                eprintln!("[X] {}", e);
+            }
+        }
+        HotkeyAction::OpenSearch => open_search(app),
+    }
+}
+
+pub fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        auto_paste::remember_focus(app);
+        let _ = window.unminimize();
+        move_to_tray_or_center(&window);
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+pub fn toggle_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main")
+        && let Ok(visible) = window.is_visible()
+    {
+        if visible {
+            let _ = window.hide();
+        } else {
+            show_window(app);
+        }
+    }
+}
+
+fn open_search(app: &AppHandle) {
+    show_window(app);
+    let _ = app.emit("open-search", ());
+}
+
+// Restores the item and, when auto-paste is on, pastes it into the focused application like an
+// item picked from the window.
+fn paste_recent(app: &AppHandle, index: usize) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    // YZZO is not shown, so the focused window is the one to paste into
+    auto_paste::remember_focus(app);
+    let target = state.previous_focus.take();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+
+        let ids = match recent_item_ids(&state.db, index as i64).await {
+            Ok(ids) => ids,
+            Err(e) => return eprintln!("[X] {}", e),
+        };
+        let Some(id) = ids.get(index - 1).copied() else {
+            return println!("[I] No item {} to paste", index);
+        };
+
+        if let Err(e) = items::restore_item(&state, id).await {
+            return eprintln!("[X] Failed to copy item {}: {}", id, e);
+        }
+        let _ = app.emit("item-updated", serde_json::json!({ "id": id }));
+
+        let options = settings::load_auto_paste(&state.db).await;
+        if !options.enabled {
+            return;
+        }
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            auto_paste::paste(target.as_ref(), &options)
+        })
+        .await;
+        if let Ok(Err(e)) = result {
+            eprintln!("[X] Failed to paste item {}: {}", id, e);
+        }
+    });
+}
+
+#[cfg(test)]
+#[path = "./tests/hotkey_actions_test.rs"]
+mod hotkey_actions_test;
@@ -0,0 +1,93 @@
+use crate::models::Item;
+use crate::state::DbPool;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+// Why an image could not be served. Commands return it as `{ "kind": ..., "message": ... }`
+// so the frontend can tell a missing item from a refused path.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum ImageAccessError {
+    NotFound(String),
+    NotAnImage(String),
+    OutsideImagesDir(String),
+    Io(String),
+    Database(String),
+    Clipboard(String),
+}
+
+impl std::fmt::Display for ImageAccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageAccessError::NotFound(e) => write!(f, "Image not found: {}", e),
+            ImageAccessError::NotAnImage(e) => write!(f, "Item is not an image: {}", e),
+            ImageAccessError::OutsideImagesDir(e) => {
+                write!(
+                    f,
+                    "Refusing to access a file outside the images directory: {}",
+                    e
+                )
+            }
+            ImageAccessError::Io(e) => write!(f, "Failed to access image: {}", e),
+            ImageAccessError::Database(e) => write!(f, "Failed to look up image: {}", e),
+            ImageAccessError::Clipboard(e) => write!(f, "Failed to copy image: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImageAccessError {}
+
+pub fn images_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, ImageAccessError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("images"))
+        .map_err(|e| ImageAccessError::Io(e.to_string()))
+}
+
+// Canonicalises `path`, resolving symlinks and `..`, and only returns it if it still points
+// inside `images_dir`.
+pub fn ensure_within(images_dir: &Path, path: &Path) -> Result<PathBuf, ImageAccessError> {
+    let root = images_dir
+        .canonicalize()
+        .map_err(|e| ImageAccessError::Io(format!("{}: {}", images_dir.display(), e)))?;
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| ImageAccessError::NotFound(format!("{}: {}", path.display(), e)))?;
+
+    if resolved.starts_with(&root) {
+        Ok(resolved)
+    } else {
+        Err(ImageAccessError::OutsideImagesDir(
+            path.display().to_string(),
+        ))
+    }
+}
+
+// Looks up the file of an image item and checks it is one the app stored itself.
+pub async fn resolve_image_path(
+    db: &DbPool,
+    images_dir: &Path,
+    id: i64,
+) -> Result<PathBuf, ImageAccessError> {
+    let item: Item = sqlx::query_as::<_, Item>("SELECT * FROM items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| ImageAccessError::Database(e.to_string()))?
+        .ok_or_else(|| ImageAccessError::NotFound(format!("item {}", id)))?;
+
+    if item.item_type != "image" {
+        return Err(ImageAccessError::NotAnImage(format!("item {}", id)));
+    }
+
+    let file_path = item
+        .file_path
+        .ok_or_else(|| ImageAccessError::NotFound(format!("item {} has no file", id)))?;
+
+    ensure_within(images_dir, Path::new(&file_path))
+}
+
+#[cfg(test)]
+#[path = "./tests/image_access_test.rs"]
+mod image_access_test;
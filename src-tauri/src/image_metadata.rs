@@ -0,0 +1,320 @@
+// Reads and strips the EXIF/XMP metadata embedded in image files.
+use crate::svg_image;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+
+// TIFF field types
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+
+// What an image file says about itself, stored under `exif` in the item metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddedMetadata {
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    // as written by the camera, `YYYY:MM:DD HH:MM:SS`
+    pub taken_at: Option<String>,
+    // EXIF orientation, 1 when the pixels are stored upright
+    pub orientation: u16,
+    pub has_gps: bool,
+    pub has_xmp: bool,
+}
+
+impl Default for EmbeddedMetadata {
+    fn default() -> Self {
+        Self {
+            camera_make: None,
+            camera_model: None,
+            taken_at: None,
+            orientation: 1,
+            has_gps: false,
+            has_xmp: false,
+        }
+    }
+}
+
+impl EmbeddedMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// Reads the embedded metadata of an encoded image, if it has any.
+pub fn read(bytes: &[u8]) -> Option<EmbeddedMetadata> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+
+    let mut metadata = decoder
+        .exif_metadata()
+        .ok()
+        .flatten()
+        .map(|exif| parse_exif(&exif))
+        .unwrap_or_default();
+
+    if let Some(xmp) = decoder.xmp_metadata().ok().flatten() {
+        let xmp = String::from_utf8_lossy(&xmp);
+        metadata.has_xmp = true;
+        // XMP mirrors the EXIF GPS fields as exif:GPSLatitude / exif:GPSLongitude
+        metadata.has_gps |= xmp.contains("GPSLatitude") || xmp.contains("GPSLongitude");
+        metadata.camera_make = metadata
+            .camera_make
+            .or_else(|| xmp_value(&xmp, "tiff:Make"));
+        metadata.camera_model = metadata
+            .camera_model
+            .or_else(|| xmp_value(&xmp, "tiff:Model"));
+        metadata.taken_at = metadata
+            .taken_at
+            .or_else(|| xmp_value(&xmp, "exif:DateTimeOriginal"))
+            .or_else(|| xmp_value(&xmp, "xmp:CreateDate"));
+    }
+
+    (!metadata.is_empty()).then_some(metadata)
+}
+
+// Parses the TIFF structure of a raw EXIF chunk.
+pub fn parse_exif(chunk: &[u8]) -> EmbeddedMetadata {
+    // some writers keep the APP1 identifier in front of the TIFF header
+    let chunk = chunk.strip_prefix(b"Exif\0\0").unwrap_or(chunk);
+    let mut metadata = EmbeddedMetadata::default();
+
+    let Some(tiff) = Tiff::new(chunk) else {
+        return metadata;
+    };
+    let Some(ifd0) = tiff.u32_at(4) else {
+        return metadata;
+    };
+
+    for entry in tiff.entries(ifd0 as usize) {
+        match entry.tag {
+            TAG_MAKE => metadata.camera_make = tiff.ascii(&entry),
+            TAG_MODEL => metadata.camera_model = tiff.ascii(&entry),
+            TAG_DATE_TIME => metadata.taken_at = metadata.taken_at.or(tiff.ascii(&entry)),
+            TAG_ORIENTATION => {
+                metadata.orientation = tiff
+                    .short(&entry)
+                    .filter(|o| (1..=8).contains(o))
+                    .unwrap_or(1)
+            }
+            TAG_GPS_IFD => {
+                // an empty GPS directory carries no location
+                metadata.has_gps = tiff
+                    .long(&entry)
+                    .is_some_and(|offset| !tiff.entries(offset as usize).is_empty())
+            }
+            TAG_EXIF_IFD => {
+                let exif_ifd = tiff.long(&entry).unwrap_or(0) as usize;
+                if let Some(original) = tiff
+                    .entries(exif_ifd)
+                    .iter()
+                    .find(|e| e.tag == TAG_DATE_TIME_ORIGINAL)
+                {
+                    // the capture time wins over the last modification time
+                    metadata.taken_at = tiff.ascii(original);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    metadata
+}
+
+// Returns the text of an XMP property written either as an attribute or as an element.
+fn xmp_value(xmp: &str, name: &str) -> Option<String> {
+    let attribute = format!("{}=\"", name);
+    if let Some(start) = xmp.find(&attribute).map(|i| i + attribute.len()) {
+        return xmp[start..].split('"').next().map(str::to_string);
+    }
+
+    let element = format!("<{}>", name);
+    let start = xmp.find(&element)? + element.len();
+    let end = xmp[start..].find('<')?;
+    Some(xmp[start..start + end].trim().to_string()).filter(|v| !v.is_empty())
+}
+
+struct Entry {
+    tag: u16,
+    type_: u16,
+    count: u32,
+    // offset of the 4-byte value/offset field
+    value_pos: usize,
+}
+
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..4)? {
+            [0x49, 0x49, 42, 0] => true,
+            [0x4D, 0x4D, 0, 42] => false,
+            _ => return None,
+        };
+        Some(Self {
+            data,
+            little_endian,
+        })
+    }
+
+    fn u16_at(&self, pos: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32_at(&self, pos: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn entries(&self, ifd: usize) -> Vec<Entry> {
+        let Some(count) = self.u16_at(ifd).filter(|_| ifd > 0) else {
+            return Vec::new();
+        };
+
+        (0..count as usize)
+            .map_while(|i| {
+                let pos = ifd + 2 + i * 12;
+                Some(Entry {
+                    tag: self.u16_at(pos)?,
+                    type_: self.u16_at(pos + 2)?,
+                    count: self.u32_at(pos + 4)?,
+                    value_pos: pos + 8,
+                })
+            })
+            .collect()
+    }
+
+    fn ascii(&self, entry: &Entry) -> Option<String> {
+        if entry.type_ != TYPE_ASCII {
+            return None;
+        }
+        let len = entry.count as usize;
+        // values longer than 4 bytes are stored elsewhere
+        let start = if len <= 4 {
+            entry.value_pos
+        } else {
+            self.u32_at(entry.value_pos)? as usize
+        };
+
+        let raw = self.data.get(start..start.checked_add(len)?)?;
+        let text = String::from_utf8_lossy(raw)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn short(&self, entry: &Entry) -> Option<u16> {
+        (entry.type_ == TYPE_SHORT).then(|| self.u16_at(entry.value_pos))?
+    }
+
+    fn long(&self, entry: &Entry) -> Option<u32> {
+        (entry.type_ == TYPE_LONG).then(|| self.u32_at(entry.value_pos))?
+    }
+}
+
+// Decodes an image with its EXIF orientation applied, as viewers show it.
+pub fn decode_oriented(bytes: &[u8]) -> Result<DynamicImage, String> {
+    // vector images have no pixels of their own, they are rendered
+    if svg_image::is_svg(bytes) {
+        return svg_image::rasterize(bytes).map(DynamicImage::ImageRgba8);
+    }
+
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+
+    let mut image = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+// Removes EXIF, XMP, IPTC and comments from a JPEG or PNG without re-encoding the pixels.
+// Returns `None` for other formats, which have to be re-encoded to lose their metadata.
+pub fn strip(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(bytes)
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        strip_png(bytes)
+    } else {
+        None
+    }
+}
+
+fn strip_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes[..2].to_vec();
+    let mut pos = 2;
+
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+
+        // start of scan: the compressed data runs to the end of the file
+        if marker == 0xDA {
+            out.extend_from_slice(&bytes[pos..]);
+            return Some(out);
+        }
+
+        let len = u16::from_be_bytes([*bytes.get(pos + 2)?, *bytes.get(pos + 3)?]) as usize;
+        let segment = bytes.get(pos..pos + 2 + len)?;
+
+        // APP1 (EXIF, XMP), APP13 (IPTC) and COM; APP0/APP2 keep JFIF and the colour profile
+        if !matches!(marker, 0xE1 | 0xED | 0xFE) {
+            out.extend_from_slice(segment);
+        }
+        pos += 2 + len;
+    }
+}
+
+fn strip_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes[..8].to_vec();
+    let mut pos = 8;
+
+    while pos < bytes.len() {
+        let len = u32::from_be_bytes(bytes.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let chunk_type = bytes.get(pos + 4..pos + 8)?;
+        let chunk = bytes.get(pos..pos + 12 + len)?;
+
+        // eXIf and the text chunks XMP is stored in
+        if !matches!(chunk_type, b"eXIf" | b"tEXt" | b"iTXt" | b"zTXt" | b"tIME") {
+            out.extend_from_slice(chunk);
+        }
+        pos += 12 + len;
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+#[path = "./tests/image_metadata_test.rs"]
+mod image_metadata_test;
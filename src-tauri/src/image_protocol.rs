@@ -0,0 +1,217 @@
+use crate::image_access::{self, ImageAccessError};
+use crate::image_store;
+use crate::state::AppState;
+use crate::thumbnails;
+use std::path::Path;
+use tauri::http::{HeaderValue, Request, Response, StatusCode, header};
+use tauri::{AppHandle, Manager, UriSchemeResponder, Wry};
+
+// Serves item images to the webview, e.g. `yzzo://localhost/image/42` or
+// `yzzo://localhost/thumbnail/42/128` (`http://yzzo.localhost/...` on Windows).
+pub const SCHEME: &str = "yzzo";
+
+// URLs name an item, whose file can be replaced, so every load revalidates. Stored files are
+// named after their content hash, which makes the file name a cheap ETag.
+const CACHE_CONTROL: &str = "private, no-cache";
+// copied SVGs may carry scripts, which must not run if one is opened as a document
+const CONTENT_SECURITY_POLICY: &str = "default-src 'none'; style-src 'unsafe-inline'";
+
+#[derive(Debug, PartialEq)]
+pub enum Resource {
+    Image(i64),
+    Thumbnail(i64, u32),
+}
+
+// Parses the request path; `convertFileSrc` percent-encodes the slashes.
+pub fn parse_resource(path: &str) -> Option<Resource> {
+    let decoded = urlencoding::decode(path).ok()?;
+    let parts: Vec<&str> = decoded.trim_matches('/').split('/').collect();
+
+    match parts.as_slice() {
+        ["image", id] => Some(Resource::Image(id.parse().ok()?)),
+        ["thumbnail", id, size] => Some(Resource::Thumbnail(id.parse().ok()?, size.parse().ok()?)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ByteRange {
+    Full,
+    // inclusive start and end
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+// Resolves a single `Range: bytes=...` header against a body of `len` bytes. Multiple ranges
+// are answered with the full body, which the HTTP spec allows.
+pub fn parse_range(header: Option<&str>, len: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        // the last N bytes
+        ("", suffix) => suffix
+            .parse::<u64>()
+            .ok()
+            .filter(|n| *n > 0 && len > 0)
+            .map(|n| (len.saturating_sub(n), len - 1)),
+        (start, "") => start
+            .parse::<u64>()
+            .ok()
+            .map(|s| (s, len.saturating_sub(1))),
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(s), Ok(e)) if s <= e => Some((s, e.min(len.saturating_sub(1)))),
+            _ => None,
+        },
+    };
+
+    match range {
+        Some((start, end)) if start < len && start <= end => ByteRange::Partial(start, end),
+        _ => ByteRange::Unsatisfiable,
+    }
+}
+
+pub fn build_response(
+    request: &Request<Vec<u8>>,
+    body: Vec<u8>,
+    content_type: &str,
+    etag: &str,
+) -> Response<Vec<u8>> {
+    let etag = format!("\"{}\"", etag);
+    let header_str = |name| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|v: &HeaderValue| v.to_str().ok())
+    };
+
+    let builder = Response::builder()
+        .header(header::CACHE_CONTROL, CACHE_CONTROL)
+        .header(header::ETAG, &etag)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY);
+
+    if header_str(header::IF_NONE_MATCH).is_some_and(|tags| tags.contains(&etag)) {
+        return builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Vec::new())
+            .unwrap();
+    }
+
+    let len = body.len() as u64;
+    let builder = builder.header(header::CONTENT_TYPE, content_type);
+
+    match parse_range(header_str(header::RANGE), len) {
+        ByteRange::Full => builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, len)
+            .body(body),
+        ByteRange::Partial(start, end) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, len),
+            )
+            .header(header::CONTENT_LENGTH, end - start + 1)
+            .body(body[start as usize..=end as usize].to_vec()),
+        ByteRange::Unsatisfiable => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new()),
+    }
+    .unwrap()
+}
+
+pub fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.as_bytes().to_vec())
+        .unwrap()
+}
+
+pub fn handle(app: AppHandle<Wry>, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    tauri::async_runtime::spawn(async move {
+        let response = match serve(&app, &request).await {
+            Ok(response) => response,
+            Err((status, message)) => error_response(status, &message),
+        };
+        responder.respond(response);
+    });
+}
+
+async fn serve(
+    app: &AppHandle<Wry>,
+    request: &Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, (StatusCode, String)> {
+    let resource = parse_resource(request.uri().path())
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "Unknown resource".to_string()))?;
+
+    // the database is set up asynchronously after the window is created
+    let state = app.try_state::<AppState>().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Database not ready".to_string(),
+        )
+    })?;
+
+    let id = match resource {
+        Resource::Image(id) | Resource::Thumbnail(id, _) => id,
+    };
+    let images_dir = image_access::images_dir(app)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let image_path = image_access::resolve_image_path(&state.db, &images_dir, id)
+        .await
+        .map_err(|e| (status_for(&e), e.to_string()))?;
+
+    let path = match resource {
+        Resource::Image(_) => image_path,
+        Resource::Thumbnail(_, size) => {
+            tauri::async_runtime::spawn_blocking(move || thumbnails::ensure(&image_path, size))
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+                .map_err(|e| (StatusCode::NOT_FOUND, e))?
+        }
+    };
+
+    let body = read_file(&path).await?;
+    let content_type = image_store::mime_type(&body);
+    let etag = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(build_response(request, body, content_type, &etag))
+}
+
+fn status_for(error: &ImageAccessError) -> StatusCode {
+    match error {
+        ImageAccessError::NotFound(_) | ImageAccessError::NotAnImage(_) => StatusCode::NOT_FOUND,
+        ImageAccessError::OutsideImagesDir(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn read_file(path: &Path) -> Result<Vec<u8>, (StatusCode, String)> {
+    let path = path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || std::fs::read(path))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Failed to read image: {}", e),
+            )
+        })
+}
+
+#[cfg(test)]
+#[path = "./tests/image_protocol_test.rs"]
+mod image_protocol_test;
@@ -0,0 +1,462 @@
+use crate::clipboard_formats::{ClipboardFormat, IMAGE_PNG, IMAGE_SVG};
+use crate::color;
+use crate::image_metadata;
+use crate::svg_image;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder};
+use image::codecs::webp::WebPEncoder;
+use image::imageops::{self, FilterType};
+use image::{AnimationDecoder, DynamicImage, GenericImageView, ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+// lossy encodes below this quality smear text in screenshots
+pub const MIN_QUALITY: u8 = 40;
+// smallest allowed limit, thumbnails need at least this much
+pub const MIN_MAX_DIMENSION: u32 = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageFormat {
+    Png,
+    WebpLossless,
+    // lossy WebP, bounded by the same quality as JPEG
+    Webp,
+    Jpeg,
+}
+
+impl StorageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            StorageFormat::Png => "png",
+            StorageFormat::WebpLossless | StorageFormat::Webp => "webp",
+            StorageFormat::Jpeg => "jpg",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            StorageFormat::Png => IMAGE_PNG,
+            StorageFormat::WebpLossless | StorageFormat::Webp => "image/webp",
+            StorageFormat::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+// How captured images are written to the images directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageOptions {
+    pub format: StorageFormat,
+    // quality of lossy formats, from MIN_QUALITY to 100
+    pub quality: u8,
+    // longest stored edge in pixels, larger images are downscaled
+    pub max_dimension: Option<u32>,
+    // slower, smaller PNGs
+    pub optimize_png: bool,
+    // copied image files keep their own encoding unless they exceed max_dimension
+    pub keep_originals: bool,
+}
+
+impl Default for StorageOptions {
+    fn default() -> Self {
+        Self {
+            format: StorageFormat::Png,
+            quality: 85,
+            max_dimension: None,
+            optimize_png: false,
+            keep_originals: true,
+        }
+    }
+}
+
+impl StorageOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_QUALITY..=100).contains(&self.quality) {
+            return Err(format!(
+                "Image quality must be between {} and 100",
+                MIN_QUALITY
+            ));
+        }
+        if self
+            .max_dimension
+            .is_some_and(|max| max < MIN_MAX_DIMENSION)
+        {
+            return Err(format!(
+                "Maximum image size must be at least {} pixels",
+                MIN_MAX_DIMENSION
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Storage settings shared between the settings commands and the clipboard watcher.
+#[derive(Clone, Default)]
+pub struct SharedStorageOptions(Arc<RwLock<StorageOptions>>);
+
+impl SharedStorageOptions {
+    pub fn new(options: StorageOptions) -> Self {
+        Self(Arc::new(RwLock::new(options)))
+    }
+
+    pub fn get(&self) -> StorageOptions {
+        self.0.read().unwrap().clone()
+    }
+
+    pub fn set(&self, options: StorageOptions) {
+        *self.0.write().unwrap() = options;
+    }
+}
+
+// An image written to the images directory, ready to be emitted as an item.
+#[derive(Debug)]
+pub struct StoredImage {
+    pub file_name: String,
+    pub path: PathBuf,
+    pub hash: u64,
+    pub metadata: serde_json::Value,
+}
+
+// Identifies an image by its decoded pixels, so the same picture copied from a file or as
+// raw pixels deduplicates to the same item.
+pub fn pixel_hash(width: u32, height: u32, rgba: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    rgba.hash(&mut hasher);
+    width.hash(&mut hasher);
+    height.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Difference hash: one bit per horizontally adjacent pair of a 9x8 grayscale thumbnail.
+// Re-encoding, rescaling or small edits only flip a few bits, unlike the exact pixel hash.
+pub fn perceptual_hash(image: &RgbaImage) -> u64 {
+    let small = imageops::resize(image, 9, 8, FilterType::Triangle);
+    let luma = |x: u32, y: u32| {
+        let [r, g, b, _] = small.get_pixel(x, y).0;
+        299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)
+    };
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if luma(x, y) < luma(x + 1, y) {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+// Stores raw clipboard pixels, which have no original encoding to keep, in the configured
+// format.
+pub fn save_pixels(
+    images_dir: &Path,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+    options: &StorageOptions,
+) -> Result<StoredImage, String> {
+    ensure_dir(images_dir)?;
+    let hash = pixel_hash(width, height, &rgba);
+    let image = RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| "Clipboard image has an invalid size".to_string())?;
+
+    store_image(images_dir, image, hash, options)
+}
+
+// The perceptual hash recorded in an image item's metadata.
+pub fn perceptual_hash_from_metadata(metadata: &str) -> Option<u64> {
+    let meta = serde_json::from_str::<serde_json::Value>(metadata).ok()?;
+    u64::from_str_radix(meta.get("phash")?.as_str()?, 16).ok()
+}
+
+// Copies an image file byte for byte, keeping its encoding, EXIF data and animation, unless
+// the storage settings ask for it to be re-encoded or downscaled.
+pub fn save_original(
+    images_dir: &Path,
+    source: &Path,
+    options: &StorageOptions,
+) -> Result<StoredImage, String> {
+    ensure_dir(images_dir)?;
+    let bytes = std::fs::read(source).map_err(|e| format!("Failed to read image file: {}", e))?;
+
+    if svg_image::is_svg(&bytes) {
+        let mut stored = save_svg(images_dir, &bytes)?;
+        stored.metadata["source_path"] = source.to_string_lossy().into();
+        return Ok(stored);
+    }
+
+    let format = image::guess_format(&bytes).map_err(|e| format!("Unknown image format: {}", e))?;
+
+    // identified by the pixels as viewers show them, like a copy from an image viewer
+    let rgba = image_metadata::decode_oriented(&bytes)?.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let embedded = image_metadata::read(&bytes);
+    let hash = pixel_hash(width, height, rgba.as_raw());
+
+    let animated = is_animated(&bytes, format);
+    let too_large = options
+        .max_dimension
+        .is_some_and(|max| width > max || height > max);
+
+    // re-encoding would drop the animation, so animated images are always kept as they are
+    if !animated && (!options.keep_originals || too_large) {
+        let mut stored = store_image(images_dir, rgba, hash, options)?;
+        stored.metadata["source_path"] = source.to_string_lossy().into();
+        stored.metadata["animated"] = false.into();
+        // re-encoding drops the metadata, but the item still records what the file carried
+        if let Some(embedded) = embedded {
+            stored.metadata["exif"] = serde_json::json!(embedded);
+        }
+        return Ok(stored);
+    }
+
+    let phash = perceptual_hash(&rgba);
+    let palette = color::palette(&rgba);
+    let extension = format.extensions_str().first().copied().unwrap_or("img");
+    let file_name = format!("{}.{}", hash, extension);
+    let path = images_dir.join(&file_name);
+
+    if !path.exists() {
+        std::fs::write(&path, &bytes).map_err(|e| format!("Failed to save image: {}", e))?;
+    }
+
+    let mut metadata = serde_json::json!({
+        "width": width,
+        "height": height,
+        "format": extension,
+        "mime_type": format.to_mime_type(),
+        "hash": hash.to_string(),
+        "phash": format!("{:016x}", phash),
+        "size": bytes.len(),
+        "source_path": source.to_string_lossy(),
+        "animated": animated,
+        "palette": palette
+    });
+    if let Some(embedded) = embedded {
+        metadata["exif"] = serde_json::json!(embedded);
+    }
+
+    Ok(StoredImage {
+        file_name,
+        path,
+        hash,
+        metadata,
+    })
+}
+
+// Keeps a copied SVG as it is. The pixel hash and palette come from a rendering, so the
+// same drawing copied twice deduplicates like any other image; width and height are the size
+// the SVG declares.
+pub fn save_svg(images_dir: &Path, svg: &[u8]) -> Result<StoredImage, String> {
+    ensure_dir(images_dir)?;
+    let tree = svg_image::parse(svg)?;
+    let rendering = svg_image::render(&tree)?;
+    let (width, height) = svg_image::intrinsic_size(&tree);
+    let hash = pixel_hash(rendering.width(), rendering.height(), rendering.as_raw());
+
+    let file_name = format!("{}.svg", hash);
+    let path = images_dir.join(&file_name);
+    if !path.exists() {
+        std::fs::write(&path, svg).map_err(|e| format!("Failed to save image: {}", e))?;
+    }
+
+    let metadata = serde_json::json!({
+        "width": width,
+        "height": height,
+        "format": "svg",
+        "mime_type": IMAGE_SVG,
+        "hash": hash.to_string(),
+        "phash": format!("{:016x}", perceptual_hash(&rendering)),
+        "size": svg.len(),
+        "animated": false,
+        "vector": true,
+        "palette": color::palette(&rendering)
+    });
+
+    Ok(StoredImage {
+        file_name,
+        path,
+        hash,
+        metadata,
+    })
+}
+
+// Downscales and encodes an image according to the storage settings. The exact and perceptual
+// hashes are taken from the pixels as copied, so dedup does not depend on the settings.
+fn store_image(
+    images_dir: &Path,
+    image: RgbaImage,
+    hash: u64,
+    options: &StorageOptions,
+) -> Result<StoredImage, String> {
+    let phash = perceptual_hash(&image);
+    let palette = color::palette(&image);
+    let (original_width, original_height) = image.dimensions();
+
+    let image = downscale(DynamicImage::ImageRgba8(image), options.max_dimension);
+    let (width, height) = image.dimensions();
+
+    let extension = options.format.extension();
+    let file_name = format!("{}.{}", hash, extension);
+    let path = images_dir.join(&file_name);
+
+    // only save if file doesn't already exist (deduplication at file level)
+    if !path.exists() {
+        let bytes = encode(&image, options)?;
+        std::fs::write(&path, bytes).map_err(|e| format!("Failed to save image: {}", e))?;
+    }
+
+    let mut metadata = serde_json::json!({
+        "width": width,
+        "height": height,
+        "format": extension,
+        "mime_type": options.format.mime_type(),
+        "hash": hash.to_string(),
+        "phash": format!("{:016x}", phash),
+        "size": std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        "palette": palette
+    });
+
+    if (width, height) != (original_width, original_height) {
+        metadata["original_width"] = original_width.into();
+        metadata["original_height"] = original_height.into();
+    }
+
+    Ok(StoredImage {
+        file_name,
+        path,
+        hash,
+        metadata,
+    })
+}
+
+// Fits an image within `max_dimension` on its longest edge, keeping the aspect ratio.
+pub fn downscale(image: DynamicImage, max_dimension: Option<u32>) -> DynamicImage {
+    match max_dimension {
+        Some(max) if image.width() > max || image.height() > max => {
+            image.resize(max, max, FilterType::Lanczos3)
+        }
+        _ => image,
+    }
+}
+
+pub fn encode(image: &DynamicImage, options: &StorageOptions) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+
+    let result = match options.format {
+        StorageFormat::Png if options.optimize_png => {
+            // screenshots are usually opaque, which saves a quarter of the raw data
+            let image = if image.color().has_alpha() && is_opaque(image) {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            } else {
+                image.clone()
+            };
+            image.write_with_encoder(PngEncoder::new_with_quality(
+                &mut bytes,
+                CompressionType::Best,
+                PngFilterType::Adaptive,
+            ))
+        }
+        StorageFormat::Png => image.write_with_encoder(PngEncoder::new(&mut bytes)),
+        StorageFormat::WebpLossless => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+        // the image crate only writes lossless WebP, libwebp does the lossy encode
+        StorageFormat::Webp => {
+            let rgba = image.to_rgba8();
+            let encoded = webp::Encoder::from_rgba(rgba.as_raw(), rgba.width(), rgba.height())
+                .encode(f32::from(options.quality));
+            bytes.extend_from_slice(&encoded);
+            Ok(())
+        }
+        // JPEG has no alpha channel
+        StorageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, options.quality)),
+    };
+
+    result.map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(bytes)
+}
+
+fn is_opaque(image: &DynamicImage) -> bool {
+    image.to_rgba8().pixels().all(|p| p.0[3] == u8::MAX)
+}
+
+// the directory is recreated when the history is cleared
+fn ensure_dir(images_dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(images_dir)
+        .map_err(|e| format!("Failed to create images directory: {}", e))
+}
+
+pub fn is_animated(bytes: &[u8], format: ImageFormat) -> bool {
+    if format != ImageFormat::Gif {
+        return false;
+    }
+
+    image::codecs::gif::GifDecoder::new(Cursor::new(bytes))
+        .map(|decoder| decoder.into_frames().take(2).count() > 1)
+        .unwrap_or(false)
+}
+
+// The MIME type of a stored image file, from its content rather than its name.
+pub fn mime_type(bytes: &[u8]) -> &'static str {
+    match image::guess_format(bytes) {
+        Ok(format) => format.to_mime_type(),
+        Err(_) if svg_image::is_svg(bytes) => IMAGE_SVG,
+        Err(_) => IMAGE_PNG,
+    }
+}
+
+// The representations offered when an image is copied back: the original encoding first, so
+// apps that understand it keep animation, metadata or vectors, and PNG for everything else.
+//
+// With `strip_metadata` the original is only offered if its metadata can be removed without
+// re-encoding; the PNG fallback never carries any.
+pub fn clipboard_formats(
+    path: &Path,
+    strip_metadata: bool,
+) -> Result<Vec<ClipboardFormat>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read image file: {}", e))?;
+    let mime_type = mime_type(&bytes);
+
+    let original = if !strip_metadata {
+        Some(bytes.clone())
+    } else if image_metadata::read(&bytes).is_some_and(|m| m.orientation > 1) {
+        // stripping the orientation would show the picture rotated
+        None
+    } else {
+        image_metadata::strip(&bytes)
+    };
+
+    if mime_type == IMAGE_PNG
+        && let Some(original) = original.clone()
+    {
+        return Ok(vec![ClipboardFormat::new(IMAGE_PNG, original)]);
+    }
+
+    let mut png = Vec::new();
+    image_metadata::decode_oriented(&bytes)?
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    let mut formats = Vec::new();
+    if let Some(original) = original {
+        formats.push(ClipboardFormat::new(mime_type, original));
+    }
+    formats.push(ClipboardFormat::new(IMAGE_PNG, png));
+    Ok(formats)
+}
+
+#[cfg(test)]
+#[path = "./tests/image_store_test.rs"]
+mod image_store_test;
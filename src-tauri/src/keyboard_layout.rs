@@ -0,0 +1,265 @@
+// Translates between the symbol printed on a key and the key's physical position. Global
+// shortcuts are registered by position, which only matches the printed symbol on US QWERTY: on
+// AZERTY the key labelled A sits where QWERTY has Q.
+use tauri_plugin_global_shortcut::Code;
+
+// keys whose symbol depends on the layout
+#[cfg(target_os = "linux")]
+const LAYOUT_KEYS: [Code; 48] = [
+    Code::KeyA,
+    Code::KeyB,
+    Code::KeyC,
+    Code::KeyD,
+    Code::KeyE,
+    Code::KeyF,
+    Code::KeyG,
+    Code::KeyH,
+    Code::KeyI,
+    Code::KeyJ,
+    Code::KeyK,
+    Code::KeyL,
+    Code::KeyM,
+    Code::KeyN,
+    Code::KeyO,
+    Code::KeyP,
+    Code::KeyQ,
+    Code::KeyR,
+    Code::KeyS,
+    Code::KeyT,
+    Code::KeyU,
+    Code::KeyV,
+    Code::KeyW,
+    Code::KeyX,
+    Code::KeyY,
+    Code::KeyZ,
+    Code::Digit0,
+    Code::Digit1,
+    Code::Digit2,
+    Code::Digit3,
+    Code::Digit4,
+    Code::Digit5,
+    Code::Digit6,
+    Code::Digit7,
+    Code::Digit8,
+    Code::Digit9,
+    Code::Backquote,
+    Code::Minus,
+    Code::Equal,
+    Code::BracketLeft,
+    Code::BracketRight,
+    Code::Backslash,
+    Code::Semicolon,
+    Code::Quote,
+    Code::Comma,
+    Code::Period,
+    Code::Slash,
+    Code::IntlBackslash,
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyboardLayout {
+    // the symbol each key types, without and with Shift
+    symbols: Vec<(Code, [Option<char>; 2])>,
+}
+
+impl KeyboardLayout {
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub fn new(symbols: Vec<(Code, [Option<char>; 2])>) -> Self {
+        Self { symbols }
+    }
+
+    // The layout in use, or `None` where it cannot be read.
+    pub fn active() -> Option<Self> {
+        platform::active()
+    }
+
+    // The key typing `symbol`, preferring keys that type it without Shift. Letters match either
+    // case.
+    pub fn code_for(&self, symbol: &str) -> Option<Code> {
+        let mut chars = symbol.chars();
+        let (Some(symbol), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        let matches =
+            |c: &Option<char>| c.is_some_and(|c| c.to_lowercase().eq(symbol.to_lowercase()));
+
+        [0, 1].into_iter().find_map(|level| {
+            self.symbols
+                .iter()
+                .find(|(_, symbols)| matches(&symbols[level]))
+                .map(|(code, _)| *code)
+        })
+    }
+
+    // What the key is labelled with: the symbol it types without Shift, letters uppercased.
+    pub fn symbol_for(&self, code: Code) -> Option<String> {
+        self.symbols
+            .iter()
+            .find(|(key, _)| *key == code)
+            .and_then(|(_, symbols)| symbols[0])
+            .map(|symbol| symbol.to_uppercase().to_string())
+    }
+}
+
+// The keymap is compiled with xkbcommon, loaded at runtime so YZZO still starts without it. On
+// X11 the layout is read from the root window like `setxkbmap -query` does; elsewhere the
+// XKB_DEFAULT_* variables or the system default apply.
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{KeyboardLayout, LAYOUT_KEYS};
+    use crate::auto_paste::evdev_code;
+    use std::ffi::CString;
+    use std::ptr;
+    use xkbcommon_dl::{
+        XkbCommon, xkb_context_flags, xkb_keymap, xkb_keymap_compile_flags, xkb_rule_names,
+        xkbcommon_option,
+    };
+
+    // evdev codes are offset by 8 in XKB
+    const EVDEV_OFFSET: u32 = 8;
+
+    // `_XKB_RULES_NAMES`: rules, model, layout, variant and options, each NUL terminated
+    #[derive(Debug, Default, PartialEq)]
+    pub struct RuleNames {
+        pub rules: String,
+        pub model: String,
+        pub layout: String,
+        pub variant: String,
+        pub options: String,
+    }
+
+    pub fn parse_rule_names(value: &[u8]) -> Option<RuleNames> {
+        let mut fields = value
+            .split(|b| *b == 0)
+            .map(|field| String::from_utf8_lossy(field).into_owned());
+        let names = RuleNames {
+            rules: fields.next()?,
+            model: fields.next().unwrap_or_default(),
+            layout: fields.next().unwrap_or_default(),
+            variant: fields.next().unwrap_or_default(),
+            options: fields.next().unwrap_or_default(),
+        };
+        (!names.layout.is_empty()).then_some(names)
+    }
+
+    fn x11_rule_names() -> Option<RuleNames> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+        if std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty()) {
+            return None;
+        }
+        let (conn, screen) = x11rb::rust_connection::RustConnection::connect(None).ok()?;
+        let root = conn.setup().roots[screen].root;
+        let atom = conn
+            .intern_atom(true, b"_XKB_RULES_NAMES")
+            .ok()?
+            .reply()
+            .ok()?
+            .atom;
+        let reply = conn
+            .get_property(false, root, atom, AtomEnum::STRING, 0, 1024)
+            .ok()?
+            .reply()
+            .ok()?;
+        parse_rule_names(&reply.value)
+    }
+
+    pub fn active() -> Option<KeyboardLayout> {
+        compile(x11_rule_names().as_ref())
+    }
+
+    // Compiles the keymap for `names`, the XKB defaults without them, and reads the symbols
+    // of its first layout.
+    pub fn compile(names: Option<&RuleNames>) -> Option<KeyboardLayout> {
+        let xkb = xkbcommon_option()?;
+
+        let fields = names.map(|names| {
+            [
+                &names.rules,
+                &names.model,
+                &names.layout,
+                &names.variant,
+                &names.options,
+            ]
+            .map(|field| CString::new(field.as_str()).ok())
+        });
+        let pointer = |index: usize| {
+            fields
+                .as_ref()
+                .and_then(|fields| fields[index].as_ref())
+                .filter(|field| !field.as_bytes().is_empty())
+                .map_or(ptr::null(), |field| field.as_ptr())
+        };
+        let rule_names = xkb_rule_names {
+            rules: pointer(0),
+            model: pointer(1),
+            layout: pointer(2),
+            variant: pointer(3),
+            options: pointer(4),
+        };
+
+        // SAFETY: the context and keymap are created and released here, and the strings
+        // `rule_names` points into outlive the call that reads them.
+        unsafe {
+            let context = (xkb.xkb_context_new)(xkb_context_flags::XKB_CONTEXT_NO_FLAGS);
+            if context.is_null() {
+                return None;
+            }
+            let keymap = (xkb.xkb_keymap_new_from_names)(
+                context,
+                &rule_names,
+                xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            (xkb.xkb_context_unref)(context);
+            if keymap.is_null() {
+                return None;
+            }
+
+            let symbols = LAYOUT_KEYS
+                .iter()
+                .filter_map(|code| {
+                    let keycode = evdev_code(*code)? + EVDEV_OFFSET;
+                    Some((
+                        *code,
+                        [0, 1].map(|level| symbol(xkb, keymap, keycode, level)),
+                    ))
+                })
+                .collect();
+            (xkb.xkb_keymap_unref)(keymap);
+
+            Some(KeyboardLayout::new(symbols))
+        }
+    }
+
+    // SAFETY: `keymap` must be a live keymap.
+    unsafe fn symbol(
+        xkb: &XkbCommon,
+        keymap: *mut xkb_keymap,
+        keycode: u32,
+        level: u32,
+    ) -> Option<char> {
+        let mut syms = ptr::null();
+        let count =
+            unsafe { (xkb.xkb_keymap_key_get_syms_by_level)(keymap, keycode, 0, level, &mut syms) };
+        if count < 1 || syms.is_null() {
+            return None;
+        }
+        let keysym = unsafe { *syms };
+        char::from_u32(unsafe { (xkb.xkb_keysym_to_utf32)(keysym) })
+            .filter(|c| *c != '\0' && !c.is_control())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::KeyboardLayout;
+
+    pub fn active() -> Option<KeyboardLayout> {
+        None
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/keyboard_layout_test.rs"]
+mod keyboard_layout_test;
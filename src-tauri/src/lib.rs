@@ -1,4 +1,4 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
 use tauri::{
     Manager,
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
@@ -8,18 +8,43 @@ use tauri_plugin_clipboard_manager;
 use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use tauri_plugin_positioner::{self, Position, WindowExt};
 
+mod auto_paste;
+mod barcodes;
+mod chords;
+#[cfg(target_os = "linux")]
+mod clipboard_daemon;
+mod clipboard_formats;
 mod clipboard_watcher;
+mod color;
 mod commands;
 mod db;
+mod file_list;
+mod history_cycle;
+mod hotkey_actions;
+mod image_access;
+mod image_metadata;
+mod image_protocol;
+mod image_store;
+mod keyboard_layout;
 mod models;
+mod ocr;
+mod qr_encoder;
+mod rich_text;
 mod state;
+mod svg_image;
+mod thumbnails;
 
-use commands::hotkeys::{DEFAULT_HOTKEY, get_hotkey, move_to_tray_or_center, parse_hotkey};
-use commands::{hotkeys, items};
+use barcodes::ScanQueue;
+use commands::{codes, colors, hotkeys, items, settings};
 use db::setup_db;
+use image_store::SharedStorageOptions;
+use ocr::OcrQueue;
 use state::AppState;
+use thumbnails::ThumbnailQueue;
 
-pub static HOLD_BEHAVIOR: AtomicBool = AtomicBool::new(false);
+// a `hotkeys::HotkeyBehavior`
+pub static HOTKEY_BEHAVIOR: AtomicU8 = AtomicU8::new(hotkeys::HotkeyBehavior::Toggle as u8);
+pub static HOLD_THRESHOLD_MS: AtomicU64 = AtomicU64::new(hotkeys::DEFAULT_HOLD_THRESHOLD_MS);
 
 fn show_error_and_exit(app: &tauri::AppHandle, title: &str, message: &str) -> ! {
     eprintln!("[X] {}: {}", title, message);
@@ -52,19 +77,52 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_process::init())
+        .register_asynchronous_uri_scheme_protocol(
+            image_protocol::SCHEME,
+            |ctx, request, responder| {
+                image_protocol::handle(ctx.app_handle().clone(), request, responder)
+            },
+        )
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .invoke_handler(tauri::generate_handler![
             items::get_items,
             items::add_item,
             items::bump_item,
             items::delete_item,
-            items::get_image_base64,
+            items::copy_item,
+            items::paste_item,
+            items::copy_item_as,
+            items::get_item_formats,
             items::write_image_to_clipboard,
             items::clear_all_items,
+            colors::convert_color,
+            colors::get_item_palette,
+            colors::find_items_by_color,
+            codes::get_item_codes,
+            codes::copy_item_code,
+            codes::get_item_qr,
             hotkeys::set_hotkey,
             hotkeys::get_hotkey,
-            hotkeys::get_hold_behavior,
-            hotkeys::set_hold_behavior
+            hotkeys::get_hotkey_behavior,
+            hotkeys::get_cycle_release_detected,
+            hotkeys::set_hotkey_behavior,
+            hotkeys::get_hold_threshold,
+            hotkeys::set_hold_threshold,
+            hotkeys::get_hotkey_bindings,
+            hotkeys::bind_hotkey,
+            hotkeys::unbind_hotkey,
+            settings::get_image_similarity_threshold,
+            settings::set_image_similarity_threshold,
+            settings::get_image_storage,
+            settings::set_image_storage,
+            settings::get_strip_image_metadata,
+            settings::set_strip_image_metadata,
+            settings::get_ocr_available,
+            settings::get_auto_paste,
+            settings::get_auto_paste_available,
+            settings::set_auto_paste,
+            settings::get_capture_paused,
+            settings::set_capture_paused
         ])
         .setup(move |app| {
             #[cfg(desktop)]
@@ -83,10 +141,24 @@ pub fn run() {
                         }
                     };
 
-                    let hold_behavior = hotkeys::load_hold_behavior_from_db(&db).await;
-                    HOLD_BEHAVIOR.store(hold_behavior, Ordering::Relaxed);
+                    hotkeys::load_hotkey_behavior_from_db(&db).await.store();
+                    let hold_threshold = hotkeys::load_hold_threshold_from_db(&db).await;
+                    HOLD_THRESHOLD_MS.store(hold_threshold, Ordering::Relaxed);
 
-                    handle.manage(AppState { db });
+                    let image_storage = settings::load_image_storage(&db).await;
+
+                    handle.manage(AppState {
+                        ocr: OcrQueue::spawn(handle.clone(), db.clone()),
+                        codes: ScanQueue::spawn(handle.clone(), db.clone()),
+                        db,
+                        pending_formats: Default::default(),
+                        self_writes: Default::default(),
+                        previous_focus: Default::default(),
+                        history_cycle: Default::default(),
+                        chords: Default::default(),
+                        thumbnails: ThumbnailQueue::spawn(),
+                        image_storage: SharedStorageOptions::new(image_storage),
+                    });
                 });
 
                 if let Err(e) = clipboard_watcher::start_clipboard_watcher(handle.clone()) {
@@ -97,23 +169,17 @@ pub fn run() {
                     );
                 }
 
-                // Register global hotkey after state is managed
-                let state = handle.state::<AppState>();
-                let hotkey = tauri::async_runtime::block_on(async {
-                    get_hotkey(state.clone()).await.unwrap_or_else(|e| {
-                        eprintln!("[X] Failed to get hotkey from DB: {}, using default", e);
-                        DEFAULT_HOTKEY.to_string()
-                    })
-                });
-
-                match parse_hotkey(&hotkey) {
-                    Ok(shortcut) => match hotkeys::register_hotkey_handler(&handle, shortcut) {
-                        Ok(_) => println!("[V] Global shortcut registered: {}", hotkey),
-                        Err(e) => eprintln!("Failed to register: {}", e),
-                    },
-                    Err(e) => eprintln!("Failed to parse hotkey: {}", e),
+                // keep copied content alive after the source app exits (X11 only)
+                #[cfg(target_os = "linux")]
+                if let Err(e) = clipboard_daemon::start_clipboard_daemon() {
+                    eprintln!("[X] Clipboard persistence unavailable: {}", e);
                 }
 
+                // Register global hotkeys after state is managed
+                let state = handle.state::<AppState>();
+                tauri::async_runtime::block_on(hotkeys::register_main_hotkey(&handle, &state.db));
+                tauri::async_runtime::block_on(hotkeys::register_bindings(&handle, &state.db));
+
                 // tray icon setup
                 if let Some(icon) = app.default_window_icon() {
                     if let Err(e) = TrayIconBuilder::new()
@@ -134,6 +200,7 @@ pub fn run() {
                                             if visible {
                                                 let _ = window.hide();
                                             } else {
+                                                auto_paste::remember_focus(app);
                                                 let _ = window.unminimize();
 
                                                 #[cfg(target_os = "macos")]
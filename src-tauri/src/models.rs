@@ -11,4 +11,31 @@ pub struct Item {
     pub item_type: String,
     pub file_path: Option<String>,
     pub metadata: Option<String>,
+    pub preferred_format: Option<String>,
+    pub perceptual_hash: Option<i64>,
+    // text recognised in image items, see ocr.rs
+    pub ocr_text: Option<String>,
+    // times the item was copied back with `copy_item`
+    pub use_count: i64,
+    pub last_used_at: Option<String>,
+    // files items whose files were moved or deleted since they were copied
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub stale: bool,
+    // previews of image items, smallest first
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Thumbnail {
+    pub size: u32,
+    pub path: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ItemFormat {
+    pub mime_type: String,
+    pub data: Vec<u8>,
 }
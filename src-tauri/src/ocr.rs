@@ -0,0 +1,264 @@
+// Recognises text in image items with a locally installed Tesseract so screenshots show up in
+// search. Only runs when built with the `ocr` feature and the binary can be found.
+use crate::image_metadata;
+use crate::state::DbPool;
+use image::ImageFormat;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Wry};
+
+// overrides the `tesseract` binary looked up on PATH
+pub const TESSERACT_ENV: &str = "YZZO_TESSERACT";
+pub const MAX_OCR_TEXT_LENGTH: usize = 10_000;
+// a hung tesseract is killed after this long, so it cannot hold up the queue
+pub const OCR_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, PartialEq)]
+pub enum OcrError {
+    NotInstalled(String),
+    Failed(String),
+}
+
+impl std::fmt::Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::NotInstalled(e) => write!(f, "Tesseract is not installed: {}", e),
+            OcrError::Failed(e) => write!(f, "Text recognition failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+pub fn tesseract_binary() -> PathBuf {
+    std::env::var_os(TESSERACT_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("tesseract"))
+}
+
+pub fn is_available(binary: &Path) -> bool {
+    Command::new(binary)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+// Runs Tesseract on an encoded image piped through stdin. Stored images are converted to PNG
+// first since not every Tesseract build reads WebP.
+pub fn recognize(binary: &Path, image: &[u8]) -> Result<String, OcrError> {
+    recognize_with_timeout(binary, image, OCR_TIMEOUT)
+}
+
+pub fn recognize_with_timeout(
+    binary: &Path,
+    image: &[u8],
+    timeout: Duration,
+) -> Result<String, OcrError> {
+    let mut child = Command::new(binary)
+        .args(["stdin", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => OcrError::NotInstalled(binary.display().to_string()),
+            _ => OcrError::Failed(e.to_string()),
+        })?;
+
+    // tesseract reads the whole image before writing anything, so this cannot deadlock
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(image)
+            .map_err(|e| OcrError::Failed(e.to_string()))?;
+    }
+
+    // the pipes are drained on their own threads so a chatty tesseract cannot block on them
+    let read_pipe = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut bytes);
+            }
+            bytes
+        })
+    };
+    let stdout = read_pipe(child.stdout.take().map(|p| Box::new(p) as _));
+    let stderr = read_pipe(child.stderr.take().map(|p| Box::new(p) as _));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(OcrError::Failed(format!(
+                    "Tesseract did not finish within {} seconds",
+                    timeout.as_secs()
+                )));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(OcrError::Failed(e.to_string())),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        return Err(OcrError::Failed(
+            String::from_utf8_lossy(&stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(clean_text(&String::from_utf8_lossy(&stdout)))
+}
+
+// Collapses the whitespace Tesseract pads its output with and caps the length.
+pub fn clean_text(raw: &str) -> String {
+    let text = raw
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match text.char_indices().nth(MAX_OCR_TEXT_LENGTH) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text,
+    }
+}
+
+fn recognize_file(binary: &Path, image_path: &Path) -> Result<String, OcrError> {
+    let bytes = std::fs::read(image_path).map_err(|e| OcrError::Failed(e.to_string()))?;
+    let image = image_metadata::decode_oriented(&bytes).map_err(OcrError::Failed)?;
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| OcrError::Failed(e.to_string()))?;
+
+    recognize(binary, &png)
+}
+
+struct OcrJob {
+    id: i64,
+    image_path: PathBuf,
+}
+
+// Runs OCR one image at a time on a worker thread. Jobs are dropped when OCR is unavailable,
+// including once tesseract goes missing while running.
+#[derive(Clone)]
+pub struct OcrQueue(Option<Sender<OcrJob>>, Arc<AtomicBool>);
+
+impl OcrQueue {
+    pub fn disabled() -> Self {
+        Self(None, Arc::new(AtomicBool::new(true)))
+    }
+
+    pub fn spawn(app: AppHandle<Wry>, db: DbPool) -> Self {
+        if !cfg!(feature = "ocr") {
+            return Self::disabled();
+        }
+
+        let binary = tesseract_binary();
+        if !is_available(&binary) {
+            println!(
+                "[I] Tesseract not found at {}, OCR disabled",
+                binary.display()
+            );
+            return Self::disabled();
+        }
+
+        let (sender, receiver) = mpsc::channel::<OcrJob>();
+        let missing = Arc::new(AtomicBool::new(false));
+        let worker_missing = missing.clone();
+        thread::spawn(move || {
+            for job in receiver {
+                if worker_missing.load(Ordering::Relaxed) {
+                    continue;
+                }
+                tauri::async_runtime::block_on(process(&app, &db, &binary, &worker_missing, job));
+            }
+        });
+
+        Self(Some(sender), missing)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some() && !self.1.load(Ordering::Relaxed)
+    }
+
+    pub fn enqueue(&self, id: i64, image_path: impl Into<PathBuf>) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Some(ref sender) = self.0 {
+            let _ = sender.send(OcrJob {
+                id,
+                image_path: image_path.into(),
+            });
+        }
+    }
+}
+
+async fn process(
+    app: &AppHandle<Wry>,
+    db: &DbPool,
+    binary: &Path,
+    missing: &AtomicBool,
+    job: OcrJob,
+) {
+    // get_items may queue an image again before its first run finished
+    let pending: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT ocr_text FROM items WHERE id = ?")
+            .bind(job.id)
+            .fetch_optional(db)
+            .await
+            .unwrap_or(None);
+    if !matches!(pending, Some((None,))) {
+        return;
+    }
+
+    let text = match recognize_file(binary, &job.image_path) {
+        Ok(text) => text,
+        // leave the item for the next start, tesseract may be installed again by then
+        Err(e @ OcrError::NotInstalled(_)) => {
+            eprintln!("[X] {}, OCR disabled until restart", e);
+            missing.store(true, Ordering::Relaxed);
+            return;
+        }
+        // an empty result keeps an unreadable image, or one tesseract hangs on, from being
+        // retried forever
+        Err(e) => {
+            eprintln!("[X] OCR failed for item {}: {}", job.id, e);
+            String::new()
+        }
+    };
+
+    if let Err(e) = sqlx::query("UPDATE items SET ocr_text = ? WHERE id = ?")
+        .bind(&text)
+        .bind(job.id)
+        .execute(db)
+        .await
+    {
+        eprintln!("[X] Failed to store OCR text for item {}: {}", job.id, e);
+        return;
+    }
+
+    if !text.is_empty() {
+        let _ = app.emit("item-updated", serde_json::json!({ "id": job.id }));
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/ocr_test.rs"]
+mod ocr_test;
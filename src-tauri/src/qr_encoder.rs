@@ -0,0 +1,143 @@
+// Renders text items as QR codes, so a snippet can be scanned off the screen with a phone
+// without going through any cloud service.
+use image::{ImageFormat, Luma};
+use qrcode::render::svg;
+use qrcode::types::QrError as EncodeError;
+use qrcode::{EcLevel, QrCode, Version};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+pub const DEFAULT_QR_SIZE: u32 = 512;
+pub const MIN_QR_SIZE: u32 = 64;
+pub const MAX_QR_SIZE: u32 = 2048;
+
+// How much of the code can be damaged and still scan: about 7%, 15%, 25% and 30%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QrLevel {
+    L,
+    M,
+    Q,
+    H,
+}
+
+impl QrLevel {
+    // the most bytes a version 40 code holds at this level
+    pub fn max_bytes(self) -> usize {
+        match self {
+            QrLevel::L => 2953,
+            QrLevel::M => 2331,
+            QrLevel::Q => 1663,
+            QrLevel::H => 1273,
+        }
+    }
+
+    fn ec_level(self) -> EcLevel {
+        match self {
+            QrLevel::L => EcLevel::L,
+            QrLevel::M => EcLevel::M,
+            QrLevel::Q => EcLevel::Q,
+            QrLevel::H => EcLevel::H,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum QrError {
+    Empty,
+    TooLong { length: usize, max: usize },
+    Failed(String),
+}
+
+impl std::fmt::Display for QrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QrError::Empty => write!(f, "Nothing to encode"),
+            QrError::TooLong { length, max } => write!(
+                f,
+                "Text is too long for a QR code: {} bytes, at most {} fit",
+                length, max
+            ),
+            QrError::Failed(e) => write!(f, "Failed to render QR code: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for QrError {}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedQr {
+    pub svg: String,
+    // a `data:image/png;base64,` URL
+    pub png: String,
+    pub level: QrLevel,
+    pub version: i16,
+    // modules across, without the quiet zone
+    pub modules: usize,
+    // pixels across the PNG, quiet zone included
+    pub size: u32,
+}
+
+// Encodes `text` at `level`, or at M falling back to L for text that only fits with less
+// error correction. `size` is clamped to MIN_QR_SIZE..=MAX_QR_SIZE and rounded down to a whole
+// number of pixels per module.
+pub fn render(text: &str, level: Option<QrLevel>, size: u32) -> Result<RenderedQr, QrError> {
+    if text.is_empty() {
+        return Err(QrError::Empty);
+    }
+
+    let (code, level) = match level {
+        Some(level) => (encode(text, level)?, level),
+        None => match encode(text, QrLevel::M) {
+            Err(QrError::TooLong { .. }) => (encode(text, QrLevel::L)?, QrLevel::L),
+            result => (result?, QrLevel::M),
+        },
+    };
+    let version = match code.version() {
+        Version::Normal(version) | Version::Micro(version) => version,
+    };
+
+    let size = size.clamp(MIN_QR_SIZE, MAX_QR_SIZE);
+    let image = code
+        .render::<Luma<u8>>()
+        .quiet_zone(true)
+        .max_dimensions(size, size)
+        .build();
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| QrError::Failed(e.to_string()))?;
+
+    let svg = code
+        .render::<svg::Color>()
+        .quiet_zone(true)
+        .max_dimensions(size, size)
+        .dark_color(svg::Color("#000000"))
+        .light_color(svg::Color("#ffffff"))
+        .build();
+
+    Ok(RenderedQr {
+        svg,
+        png: format!(
+            "data:image/png;base64,{}",
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png)
+        ),
+        level,
+        version,
+        modules: code.width(),
+        size: image.width(),
+    })
+}
+
+fn encode(text: &str, level: QrLevel) -> Result<QrCode, QrError> {
+    QrCode::with_error_correction_level(text, level.ec_level()).map_err(|e| match e {
+        EncodeError::DataTooLong => QrError::TooLong {
+            length: text.len(),
+            max: level.max_bytes(),
+        },
+        e => QrError::Failed(e.to_string()),
+    })
+}
+
+#[cfg(test)]
+#[path = "./tests/qr_encoder_test.rs"]
+mod qr_encoder_test;
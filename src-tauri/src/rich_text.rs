@@ -0,0 +1,363 @@
+// Conversions from captured rich text (HTML, RTF) to plain text and Markdown.
+
+// tags whose end starts a new line in the plain text output
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "li",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "pre",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "table",
+    "ul",
+    "ol",
+];
+
+// tags whose content is never shown
+const HIDDEN_TAGS: &[&str] = &["script", "style", "head", "title", "template"];
+
+// RTF destinations that hold metadata rather than document text
+const RTF_SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "header",
+    "footer",
+    "listtable",
+    "listoverridetable",
+    "generator",
+    "themedata",
+    "colorschememapping",
+    "latentstyles",
+    "datastore",
+    "xmlnstbl",
+    "rsidtbl",
+];
+
+pub fn html_to_markdown(html: &str) -> String {
+    html2md::parse_html(html).trim().to_string()
+}
+
+// Strips tags from HTML, keeping line structure and decoding entities.
+pub fn html_to_plain_text(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    let mut hidden_depth = 0usize;
+
+    while let Some(start) = rest.find('<') {
+        if hidden_depth == 0 {
+            out.push_str(&decode_entities(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        // comments may contain '>' so they end at the matching terminator
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|end| &rest[end + 3..]).unwrap_or("");
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        if HIDDEN_TAGS.contains(&name.as_str()) {
+            if closing {
+                hidden_depth = hidden_depth.saturating_sub(1);
+            } else if !tag.ends_with('/') {
+                hidden_depth += 1;
+            }
+            continue;
+        }
+
+        if hidden_depth > 0 {
+            continue;
+        }
+
+        if name == "br" {
+            out.push('\n');
+        } else if closing && BLOCK_TAGS.contains(&name.as_str()) {
+            // nested blocks like </li></ul> end a single line
+            if !out.ends_with('\n') {
+                out.push('\n');
+            }
+        } else if !closing && name == "li" {
+            out.push_str("- ");
+        } else if closing && (name == "td" || name == "th") {
+            out.push('\t');
+        }
+    }
+
+    if hidden_depth == 0 {
+        out.push_str(&decode_entities(rest));
+    }
+
+    normalize_lines(&out)
+}
+
+// Extracts the document text from RTF, dropping formatting and metadata groups.
+pub fn rtf_to_plain_text(rtf: &str) -> String {
+    let mut out = String::new();
+    let chars: Vec<char> = rtf.chars().collect();
+    let mut i = 0;
+
+    // \'hh escapes are bytes in the document's code page, Windows-1252 unless it declares one.
+    // They are decoded together, a character may span several of them.
+    let mut encoding = encoding_rs::WINDOWS_1252;
+    let mut bytes: Vec<u8> = Vec::new();
+
+    // per group: whether its content is skipped, and how many chars follow a \uN escape
+    let mut skip_stack: Vec<bool> = vec![false];
+    let mut unicode_skip = 1usize;
+    let mut pending_skip = 0usize;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let skipping = *skip_stack.last().unwrap_or(&false);
+
+        if !bytes.is_empty() && (c != '\\' || chars.get(i + 1) != Some(&'\'')) {
+            out.push_str(&encoding.decode_without_bom_handling(&bytes).0);
+            bytes.clear();
+        }
+
+        match c {
+            '{' => {
+                skip_stack.push(skipping);
+                i += 1;
+            }
+            '}' => {
+                if skip_stack.len() > 1 {
+                    skip_stack.pop();
+                }
+                i += 1;
+            }
+            '\\' => {
+                i += 1;
+                let Some(&next) = chars.get(i) else { break };
+
+                if next.is_ascii_alphabetic() {
+                    let word_start = i;
+                    while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                        i += 1;
+                    }
+                    let word: String = chars[word_start..i].iter().collect();
+
+                    let param_start = i;
+                    if i < chars.len() && chars[i] == '-' {
+                        i += 1;
+                    }
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                    let param: Option<i32> = chars[param_start..i]
+                        .iter()
+                        .collect::<String>()
+                        .parse()
+                        .ok();
+
+                    // a single space delimits the control word and is not part of the text
+                    if i < chars.len() && chars[i] == ' ' {
+                        i += 1;
+                    }
+
+                    if RTF_SKIPPED_DESTINATIONS.contains(&word.as_str()) {
+                        if let Some(last) = skip_stack.last_mut() {
+                            *last = true;
+                        }
+                        continue;
+                    }
+                    if skipping {
+                        continue;
+                    }
+
+                    match word.as_str() {
+                        "par" | "line" | "row" => out.push('\n'),
+                        "tab" | "cell" => out.push('\t'),
+                        "uc" => unicode_skip = param.unwrap_or(1).max(0) as usize,
+                        "u" => {
+                            if let Some(code) = param {
+                                // negative values encode code points above 32767
+                                let code = if code < 0 { code + 65536 } else { code };
+                                if let Some(ch) = char::from_u32(code as u32) {
+                                    out.push(ch);
+                                }
+                                pending_skip = unicode_skip;
+                            }
+                        }
+                        "ansicpg" => {
+                            if let Some(declared) = param.and_then(code_page_encoding) {
+                                encoding = declared;
+                            }
+                        }
+                        "mac" => encoding = encoding_rs::MACINTOSH,
+                        "emdash" => out.push('\u{2014}'),
+                        "endash" => out.push('\u{2013}'),
+                        "bullet" => out.push('\u{2022}'),
+                        "lquote" => out.push('\u{2018}'),
+                        "rquote" => out.push('\u{2019}'),
+                        "ldblquote" => out.push('\u{201C}'),
+                        "rdblquote" => out.push('\u{201D}'),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                i += 1;
+                match next {
+                    // \* marks a destination unknown readers must ignore
+                    '*' => {
+                        if let Some(last) = skip_stack.last_mut() {
+                            *last = true;
+                        }
+                    }
+                    '\'' => {
+                        let hex: String = chars.iter().skip(i).take(2).collect();
+                        i += hex.len();
+                        if pending_skip > 0 {
+                            pending_skip -= 1;
+                        } else if !skipping && let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                            bytes.push(byte);
+                        }
+                    }
+                    '\\' | '{' | '}' if !skipping => out.push(next),
+                    '~' if !skipping => out.push('\u{00A0}'),
+                    '\n' | '\r' if !skipping => out.push('\n'),
+                    _ => {}
+                }
+            }
+            '\r' | '\n' => i += 1,
+            _ => {
+                if pending_skip > 0 {
+                    pending_skip -= 1;
+                } else if !skipping {
+                    out.push(c);
+                }
+                i += 1;
+            }
+        }
+    }
+    out.push_str(&encoding.decode_without_bom_handling(&bytes).0);
+
+    normalize_lines(&out)
+}
+
+// The encoding of an RTF `\ansicpgN` code page, if encoding_rs knows it.
+fn code_page_encoding(code_page: i32) -> Option<&'static encoding_rs::Encoding> {
+    let label = match code_page {
+        932 => "shift_jis".to_string(),
+        936 => "gbk".to_string(),
+        949 => "euc-kr".to_string(),
+        950 => "big5".to_string(),
+        10000 => "macintosh".to_string(),
+        65001 => "utf-8".to_string(),
+        _ => format!("windows-{}", code_page),
+    };
+    encoding_rs::Encoding::for_label(label.as_bytes())
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return collapse_whitespace(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                    u32::from_str_radix(&entity[2..], 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                }
+                _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+                _ => None,
+            };
+            ch.map(|ch| (ch, end))
+        });
+
+        match decoded {
+            Some((ch, end)) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+
+    collapse_whitespace(&out)
+}
+
+// Source formatting whitespace in HTML renders as a single space.
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !last_space {
+                out.push(' ');
+            }
+            last_space = true;
+        } else {
+            out.push(c);
+            last_space = false;
+        }
+    }
+    out
+}
+
+// Trims every line and keeps at most one blank line between paragraphs.
+fn normalize_lines(text: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text
+        .lines()
+        .map(|l| l.trim_matches(|c| c == ' ' || c == '\t'))
+    {
+        if line.is_empty() && lines.last().is_some_and(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+#[path = "./tests/rich_text_test.rs"]
+mod rich_text_test;
@@ -1,8 +1,27 @@
+use crate::auto_paste::PreviousFocus;
+use crate::barcodes::ScanQueue;
+use crate::chords::Chords;
+use crate::clipboard_formats::{PendingFormats, SelfWrites};
 use crate::db::Database;
+use crate::history_cycle::HistoryCycle;
+use crate::image_store::SharedStorageOptions;
+use crate::ocr::OcrQueue;
+use crate::thumbnails::ThumbnailQueue;
 
 pub type DbPool = Database;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DbPool,
+    pub pending_formats: PendingFormats,
+    pub self_writes: SelfWrites,
+    // the window to paste into when auto-paste is on
+    pub previous_focus: PreviousFocus,
+    pub history_cycle: HistoryCycle,
+    // two-step hotkeys, by leader
+    pub chords: Chords,
+    pub thumbnails: ThumbnailQueue,
+    pub image_storage: SharedStorageOptions,
+    pub ocr: OcrQueue,
+    pub codes: ScanQueue,
 }
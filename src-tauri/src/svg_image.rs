@@ -0,0 +1,114 @@
+// Vector graphics copied from Inkscape, Figma and the like. The SVG is kept as the item's file
+// and rendered with resvg wherever pixels are needed: thumbnails, palettes, OCR and the PNG
+// offered next to the SVG on copy.
+use image::RgbaImage;
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{self, ImageHrefResolver, fontdb};
+use std::sync::{Arc, OnceLock};
+
+// the longest edge of a rendering: small icons are scaled up, posters down
+pub const MIN_RASTER_SIZE: u32 = 512;
+pub const MAX_RASTER_SIZE: u32 = 2048;
+
+// how far into the data the root element has to start
+const SNIFF_LENGTH: usize = 4096;
+
+static FONTS: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+
+// Whether the data is an SVG document: an `<svg>` root element, optionally after an XML
+// declaration, comments and a doctype.
+pub fn is_svg(bytes: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(SNIFF_LENGTH)]);
+    let mut rest = head.trim_start_matches('\u{feff}').trim_start();
+
+    loop {
+        let end = if rest.starts_with("<?") {
+            rest.find("?>").map(|i| i + 2)
+        } else if rest.starts_with("<!--") {
+            rest.find("-->").map(|i| i + 3)
+        } else if rest.starts_with("<!DOCTYPE") || rest.starts_with("<!doctype") {
+            // Illustrator declares entities in an internal subset
+            match (rest.find('['), rest.find('>')) {
+                (Some(open), Some(close)) if open < close => rest.find("]>").map(|i| i + 2),
+                (_, close) => close.map(|i| i + 1),
+            }
+        } else {
+            break;
+        };
+
+        match end {
+            Some(end) => rest = rest[end..].trim_start(),
+            None => return false,
+        }
+    }
+
+    rest.strip_prefix("<svg").is_some_and(|after| {
+        after
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_whitespace() || c == '>' || c == '/')
+    })
+}
+
+pub fn parse(svg: &[u8]) -> Result<usvg::Tree, String> {
+    let options = usvg::Options {
+        // only images embedded as data URLs are drawn, a copied SVG must not read local files
+        image_href_resolver: ImageHrefResolver {
+            resolve_string: Box::new(|_, _| None),
+            ..ImageHrefResolver::default()
+        },
+        fontdb: FONTS
+            .get_or_init(|| {
+                let mut fonts = fontdb::Database::new();
+                fonts.load_system_fonts();
+                Arc::new(fonts)
+            })
+            .clone(),
+        ..usvg::Options::default()
+    };
+
+    usvg::Tree::from_data(svg, &options).map_err(|e| format!("Failed to parse SVG: {}", e))
+}
+
+// The size the SVG declares, in CSS pixels rounded up.
+pub fn intrinsic_size(tree: &usvg::Tree) -> (u32, u32) {
+    let size = tree.size();
+    (size.width().ceil() as u32, size.height().ceil() as u32)
+}
+
+// Renders the SVG with its longest edge between MIN_RASTER_SIZE and MAX_RASTER_SIZE.
+pub fn render(tree: &usvg::Tree) -> Result<RgbaImage, String> {
+    let size = tree.size();
+    let longest = size.width().max(size.height());
+    let scale = longest.clamp(MIN_RASTER_SIZE as f32, MAX_RASTER_SIZE as f32) / longest;
+
+    let width = ((size.width() * scale).round() as u32).max(1);
+    let height = ((size.height() * scale).round() as u32).max(1);
+    let mut pixmap = Pixmap::new(width, height)
+        .ok_or_else(|| format!("Failed to render SVG: invalid size {}x{}", width, height))?;
+    resvg::render(
+        tree,
+        Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    // tiny-skia works with premultiplied alpha
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| "Failed to render SVG: invalid pixel data".to_string())
+}
+
+pub fn rasterize(svg: &[u8]) -> Result<RgbaImage, String> {
+    render(&parse(svg)?)
+}
+
+#[cfg(test)]
+#[path = "./tests/svg_image_test.rs"]
+mod svg_image_test;
@@ -0,0 +1,177 @@
+use super::{AutoPasteOptions, PasteTarget, evdev_code, key_symbol, modifier_symbols};
+use crate::commands::hotkeys::parse_hotkey;
+use tauri_plugin_global_shortcut::{Code, Modifiers};
+
+#[test]
+fn test_terminals_paste_with_shift() {
+    let options = AutoPasteOptions::default();
+
+    assert!(!options.enabled);
+    assert_eq!(options.shortcut_for(None), "Ctrl+V");
+    assert_eq!(options.shortcut_for(Some("firefox")), "Ctrl+V");
+    assert_eq!(options.shortcut_for(Some("kitty")), "Ctrl+Shift+V");
+    // WM_CLASS capitalisation differs between versions
+    assert_eq!(options.shortcut_for(Some("alacritty")), "Ctrl+Shift+V");
+}
+
+#[test]
+fn test_targets_override_the_default_shortcut() {
+    let options = AutoPasteOptions {
+        enabled: true,
+        shortcut: "Ctrl+V".to_string(),
+        targets: vec![PasteTarget {
+            app: "Emacs".to_string(),
+            shortcut: "Ctrl+Y".to_string(),
+        }],
+    };
+
+    assert!(options.validate().is_ok());
+    assert_eq!(options.shortcut_for(Some("emacs")), "Ctrl+Y");
+    assert_eq!(options.shortcut_for(Some("kitty")), "Ctrl+V");
+}
+
+#[test]
+fn test_missing_fields_take_defaults() {
+    let options: AutoPasteOptions = serde_json::from_str(r#"{"enabled":true}"#).unwrap();
+
+    assert!(options.enabled);
+    assert_eq!(options.shortcut, "Ctrl+V");
+    assert_eq!(options.targets, AutoPasteOptions::default().targets);
+}
+
+#[test]
+fn test_saved_options_are_disabled_where_pasting_is_unsupported() {
+    use crate::commands::settings::{AUTO_PASTE, load_auto_paste, set_setting};
+
+    tauri::async_runtime::block_on(async {
+        let db = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        set_setting(&db, AUTO_PASTE, r#"{"enabled":true,"shortcut":"Ctrl+Y"}"#)
+            .await
+            .unwrap();
+        let options = load_auto_paste(&db).await;
+
+        assert_eq!(options.enabled, super::SUPPORTED);
+        assert_eq!(options.shortcut, "Ctrl+Y");
+    });
+}
+
+#[test]
+fn test_validate_rejects_bad_shortcuts() {
+    let mut options = AutoPasteOptions::default();
+    assert!(options.validate().is_ok());
+
+    options.shortcut = "Ctrl+Nope".to_string();
+    assert!(options.validate().is_err());
+
+    options.shortcut = "Ctrl+V".to_string();
+    options.targets.push(PasteTarget {
+        app: " ".to_string(),
+        shortcut: "Ctrl+V".to_string(),
+    });
+    assert!(options.validate().is_err());
+}
+
+#[test]
+fn test_every_parsed_key_can_be_typed() {
+    for key in "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789`'".chars() {
+        let shortcut = parse_hotkey(&format!("Ctrl+{}", key)).unwrap();
+        assert!(key_symbol(shortcut.key).is_some(), "{}", key);
+        assert!(evdev_code(shortcut.key).is_some(), "{}", key);
+    }
+
+    assert_eq!(key_symbol(Code::KeyV), Some(0x76));
+    assert_eq!(evdev_code(Code::KeyV), Some(47));
+}
+
+#[test]
+fn test_modifiers_are_held_in_order() {
+    assert_eq!(
+        modifier_symbols(Modifiers::SHIFT | Modifiers::CONTROL),
+        vec![0xffe3, 0xffe1]
+    );
+    assert!(modifier_symbols(Modifiers::empty()).is_empty());
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::super::x11::{self, parse_wm_class};
+    use crate::commands::hotkeys::parse_hotkey;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use x11rb::connection::Connection;
+    use x11rb::protocol::Event;
+    use x11rb::protocol::xproto::{
+        ConnectionExt, CreateWindowAux, EventMask, KeyButMask, WindowClass,
+    };
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::{COPY_DEPTH_FROM_PARENT, COPY_FROM_PARENT};
+
+    #[test]
+    fn test_parse_wm_class() {
+        assert_eq!(
+            parse_wm_class(b"gnome-terminal-server\0Gnome-terminal\0"),
+            Some("Gnome-terminal".to_string())
+        );
+        assert_eq!(parse_wm_class(b"xterm\0\0"), Some("xterm".to_string()));
+        assert_eq!(parse_wm_class(b""), None);
+    }
+
+    // Needs an X server without Wayland: `xvfb-run cargo test -- --ignored`.
+    #[test]
+    #[ignore = "needs an X server"]
+    fn test_paste_types_into_the_focused_window() {
+        let (conn, screen) = RustConnection::connect(None).expect("needs an X server");
+        let screen = &conn.setup().roots[screen];
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            screen.root,
+            0,
+            0,
+            100,
+            100,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new().event_mask(EventMask::KEY_PRESS | EventMask::FOCUS_CHANGE),
+        )
+        .unwrap();
+        conn.map_window(window).unwrap();
+        conn.get_input_focus().unwrap().reply().unwrap();
+
+        x11::paste(Some(window), &parse_hotkey("Ctrl+V").unwrap()).unwrap();
+
+        let setup = conn.setup();
+        let mapping = conn
+            .get_keyboard_mapping(setup.min_keycode, setup.max_keycode - setup.min_keycode + 1)
+            .unwrap()
+            .reply()
+            .unwrap();
+        let per_keycode = usize::from(mapping.keysyms_per_keycode);
+
+        // XTest events reach the window some time after paste returns
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let state = loop {
+            assert!(Instant::now() < deadline, "V was not pressed in the window");
+            let Some(event) = conn.poll_for_event().unwrap() else {
+                thread::sleep(Duration::from_millis(5));
+                continue;
+            };
+            if let Event::KeyPress(key) = event {
+                let index = usize::from(key.detail - setup.min_keycode) * per_keycode;
+                if mapping.keysyms[index..index + per_keycode].contains(&0x76) {
+                    break key.state;
+                }
+            }
+        };
+
+        assert!(state.contains(KeyButMask::CONTROL));
+        assert!(!state.contains(KeyButMask::SHIFT));
+    }
+}
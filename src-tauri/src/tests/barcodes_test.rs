@@ -0,0 +1,154 @@
+use super::{CodeFormat, PayloadKind, classify, scan};
+use image::{Rgba, RgbaImage, imageops};
+use qrcode::{Color, QrCode};
+
+const L_CODES: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011",
+    "0110111", "0001011",
+];
+const PARITY: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL",
+    "LGGLGL",
+];
+
+// Draws an EAN-13 from its 13 digits with a quiet zone, 2 pixels per module.
+fn render_ean(digits: &str) -> RgbaImage {
+    let digits: Vec<usize> = digits.bytes().map(|b| (b - b'0') as usize).collect();
+    let invert = |code: &str| -> String {
+        code.chars()
+            .map(|c| if c == '0' { '1' } else { '0' })
+            .collect()
+    };
+
+    let mut modules = "0".repeat(10) + "101";
+    for (i, digit) in digits[1..7].iter().enumerate() {
+        let l = L_CODES[*digit];
+        modules += &match PARITY[digits[0]].as_bytes()[i] {
+            b'L' => l.to_string(),
+            // G codes are the R codes reversed
+            _ => invert(l).chars().rev().collect(),
+        };
+    }
+    modules += "01010";
+    for digit in &digits[7..] {
+        modules += &invert(L_CODES[*digit]);
+    }
+    modules += "101";
+    modules += &"0".repeat(10);
+
+    let modules: Vec<bool> = modules.chars().map(|c| c == '1').collect();
+    RgbaImage::from_fn(modules.len() as u32 * 2, 60, |x, _| {
+        if modules[x as usize / 2] {
+            Rgba([20, 20, 20, 255])
+        } else {
+            Rgba([250, 250, 250, 255])
+        }
+    })
+}
+
+#[test]
+fn test_scan_ean13() {
+    let codes = scan(&render_ean("4006381333931"));
+
+    assert_eq!(codes.len(), 1);
+    assert_eq!(codes[0].format, CodeFormat::Ean13);
+    assert_eq!(codes[0].text, "4006381333931");
+    assert_eq!(codes[0].kind, PayloadKind::Text);
+}
+
+#[test]
+fn test_scan_upc_a() {
+    let codes = scan(&render_ean("0036000291452"));
+
+    assert_eq!(codes.len(), 1);
+    assert_eq!(codes[0].format, CodeFormat::UpcA);
+    assert_eq!(codes[0].text, "036000291452");
+}
+
+#[test]
+fn test_scan_upside_down_barcode() {
+    let codes = scan(&imageops::rotate180(&render_ean("4006381333931")));
+    assert_eq!(codes[0].text, "4006381333931");
+}
+
+#[test]
+fn test_scan_rejects_bad_checksum() {
+    assert!(scan(&render_ean("4006381333932")).is_empty());
+}
+
+#[test]
+fn test_scan_qr_code_with_transparent_background() {
+    let code = QrCode::new("https://example.com").unwrap();
+    let width = code.width();
+    let colors = code.to_colors();
+    let image = RgbaImage::from_fn((width as u32 + 8) * 2, (width as u32 + 8) * 2, |x, y| {
+        let (mx, my) = ((x / 2) as i64 - 4, (y / 2) as i64 - 4);
+        let dark = mx >= 0
+            && my >= 0
+            && (mx as usize) < width
+            && (my as usize) < width
+            && colors[my as usize * width + mx as usize] == Color::Dark;
+        if dark {
+            Rgba([0, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    });
+
+    let codes = scan(&image);
+    assert_eq!(codes.len(), 1);
+    assert_eq!(codes[0].format, CodeFormat::QrCode);
+    assert_eq!(codes[0].text, "https://example.com");
+    assert_eq!(codes[0].kind, PayloadKind::Url);
+}
+
+#[test]
+fn test_scan_plain_image() {
+    assert!(scan(&RgbaImage::from_pixel(50, 50, Rgba([90, 120, 200, 255]))).is_empty());
+}
+
+#[test]
+fn test_classify() {
+    assert_eq!(classify("WIFI:S:home;T:WPA;P:secret;;"), PayloadKind::Wifi);
+    assert_eq!(
+        classify("otpauth://totp/Example?secret=JBSWY3DP"),
+        PayloadKind::Otp
+    );
+    assert_eq!(classify("HTTPS://EXAMPLE.COM"), PayloadKind::Url);
+    assert_eq!(classify("just some text"), PayloadKind::Text);
+}
+
+#[test]
+fn test_save_codes_keeps_the_rest_of_the_metadata() {
+    use super::{DecodedCode, save_codes};
+
+    tauri::async_runtime::block_on(async {
+        let db = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE items (id INTEGER PRIMARY KEY, metadata TEXT)")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO items (id, metadata) VALUES (1, '{\"width\":10}'), (2, NULL)")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let codes = vec![DecodedCode {
+            format: CodeFormat::QrCode,
+            text: "https://example.com".to_string(),
+            kind: PayloadKind::Url,
+        }];
+        save_codes(&db, 1, &codes).await.unwrap();
+        save_codes(&db, 2, &[]).await.unwrap();
+
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT metadata FROM items ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        let first: serde_json::Value = serde_json::from_str(&rows[0].0).unwrap();
+        assert_eq!(first["width"], 10);
+        assert_eq!(first["codes"][0]["text"], "https://example.com");
+        assert_eq!(first["codes"][0]["kind"], "url");
+        assert_eq!(rows[1].0, "{\"codes\":[]}");
+    });
+}
@@ -0,0 +1,103 @@
+use super::Chords;
+use crate::commands::hotkeys::parse_hotkey;
+use crate::hotkey_actions::HotkeyAction;
+use tauri_plugin_global_shortcut::Shortcut;
+
+fn shortcut(hotkey: &str) -> Shortcut {
+    parse_hotkey(hotkey).unwrap()
+}
+
+#[test]
+fn test_leader_is_registered_once() {
+    let chords = Chords::default();
+    let leader = shortcut("Ctrl+Alt+V");
+
+    assert!(chords.add(
+        leader,
+        shortcut("1"),
+        HotkeyAction::PasteRecent { index: 1 }
+    ));
+    assert!(!chords.add(
+        leader,
+        shortcut("2"),
+        HotkeyAction::PasteRecent { index: 2 }
+    ));
+
+    assert!(!chords.remove(leader, shortcut("1")));
+    assert!(chords.remove(leader, shortcut("2")));
+    assert!(!chords.remove(leader, shortcut("2")));
+}
+
+#[test]
+fn test_key_after_leader_runs_its_action() {
+    let chords = Chords::default();
+    let leader = shortcut("Ctrl+Alt+V");
+    chords.add(
+        leader,
+        shortcut("1"),
+        HotkeyAction::PasteRecent { index: 1 },
+    );
+    chords.add(leader, shortcut("2"), HotkeyAction::PastePrevious);
+
+    let (_, keys) = chords.start(leader.id()).unwrap();
+    assert_eq!(keys, vec![shortcut("1"), shortcut("2")]);
+
+    let (action, released) = chords.press(&shortcut("2")).unwrap();
+    assert_eq!(action, HotkeyAction::PastePrevious);
+    assert_eq!(released, keys);
+    // the chord is over
+    assert!(chords.press(&shortcut("1")).is_none());
+}
+
+#[test]
+fn test_adding_a_chord_again_replaces_its_action() {
+    let chords = Chords::default();
+    let leader = shortcut("Ctrl+Alt+V");
+    chords.add(
+        leader,
+        shortcut("1"),
+        HotkeyAction::PasteRecent { index: 1 },
+    );
+    chords.add(leader, shortcut("1"), HotkeyAction::ClearClipboard);
+
+    let (_, keys) = chords.start(leader.id()).unwrap();
+    assert_eq!(keys.len(), 1);
+    let (action, _) = chords.press(&shortcut("1")).unwrap();
+    assert_eq!(action, HotkeyAction::ClearClipboard);
+}
+
+#[test]
+fn test_chord_expires() {
+    let chords = Chords::default();
+    let leader = shortcut("Ctrl+Alt+V");
+    chords.add(leader, shortcut("1"), HotkeyAction::ClearClipboard);
+
+    let (press, keys) = chords.start(leader.id()).unwrap();
+    // another leader press while waiting does not start over
+    assert!(chords.start(leader.id()).is_none());
+
+    assert_eq!(chords.expire(press), keys);
+    assert!(chords.press(&shortcut("1")).is_none());
+    assert!(chords.expire(press).is_empty());
+}
+
+#[test]
+fn test_expiry_of_an_earlier_press_is_ignored() {
+    let chords = Chords::default();
+    let leader = shortcut("Ctrl+Alt+V");
+    chords.add(leader, shortcut("1"), HotkeyAction::ClearClipboard);
+
+    let (first, _) = chords.start(leader.id()).unwrap();
+    chords.press(&shortcut("1")).unwrap();
+    let (second, _) = chords.start(leader.id()).unwrap();
+
+    assert!(chords.expire(first).is_empty());
+    assert!(chords.press(&shortcut("1")).is_some());
+    assert!(chords.expire(second).is_empty());
+}
+
+#[test]
+fn test_unknown_leader_starts_nothing() {
+    let chords = Chords::default();
+    assert!(chords.start(shortcut("Ctrl+Alt+V").id()).is_none());
+}
@@ -0,0 +1,340 @@
+use super::{Atoms, is_meta_target, latin1, mime_type_for_target};
+use x11rb::NONE;
+
+fn test_atoms() -> Atoms {
+    Atoms {
+        CLIPBOARD: 1,
+        CLIPBOARD_MANAGER: 2,
+        SAVE_TARGETS: 3,
+        TARGETS: 4,
+        MULTIPLE: 5,
+        TIMESTAMP: 6,
+        DELETE: 7,
+        INSERT_SELECTION: 8,
+        INSERT_PROPERTY: 9,
+        INCR: 10,
+        MANAGER: 11,
+        UTF8_STRING: 12,
+        STRING: 13,
+        TEXT: 14,
+        YZZO_SELECTION: 15,
+        YZZO_RESTORE: 16,
+        YZZO_TIMESTAMP: 17,
+    }
+}
+
+#[test]
+fn test_meta_targets_are_not_stored() {
+    let atoms = test_atoms();
+
+    assert!(is_meta_target(&atoms, NONE));
+    assert!(is_meta_target(&atoms, atoms.TARGETS));
+    assert!(is_meta_target(&atoms, atoms.MULTIPLE));
+    assert!(is_meta_target(&atoms, atoms.TIMESTAMP));
+    assert!(is_meta_target(&atoms, atoms.SAVE_TARGETS));
+    assert!(is_meta_target(&atoms, atoms.DELETE));
+}
+
+#[test]
+fn test_content_targets_are_stored() {
+    let atoms = test_atoms();
+
+    // atoms interned for e.g. UTF8_STRING, text/html or image/png
+    assert!(!is_meta_target(&atoms, 100));
+    assert!(!is_meta_target(&atoms, 200));
+}
+
+#[test]
+fn test_mime_targets_keep_their_name() {
+    assert_eq!(
+        mime_type_for_target("text/html"),
+        Some("text/html".to_string())
+    );
+    assert_eq!(
+        mime_type_for_target("application/x-qt-image"),
+        Some("application/x-qt-image".to_string())
+    );
+}
+
+#[test]
+fn test_utf8_string_maps_to_plain_text() {
+    assert_eq!(
+        mime_type_for_target("UTF8_STRING"),
+        Some("text/plain".to_string())
+    );
+}
+
+#[test]
+fn test_legacy_and_private_targets_are_skipped() {
+    assert_eq!(mime_type_for_target("STRING"), None);
+    assert_eq!(mime_type_for_target("COMPOUND_TEXT"), None);
+    assert_eq!(mime_type_for_target("_GTK_TEXT_BUFFER_CONTENTS"), None);
+}
+
+#[test]
+fn test_legacy_text_is_latin1() {
+    assert_eq!(latin1("café".as_bytes()), Some(b"caf\xe9".to_vec()));
+    assert_eq!(latin1(b"plain"), Some(b"plain".to_vec()));
+    // no Latin-1 form, only UTF8_STRING is offered
+    assert_eq!(latin1("€5".as_bytes()), None);
+    assert_eq!(latin1("日本".as_bytes()), None);
+}
+
+mod x11 {
+    use super::super::{Atoms, start_clipboard_daemon};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use x11rb::connection::Connection;
+    use x11rb::protocol::Event;
+    use x11rb::protocol::xproto::{
+        AtomEnum, ConnectionExt, CreateWindowAux, EventMask, PropMode, SELECTION_NOTIFY_EVENT,
+        SelectionNotifyEvent, Window, WindowClass,
+    };
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::wrapper::ConnectionExt as _;
+    use x11rb::{COPY_DEPTH_FROM_PARENT, COPY_FROM_PARENT, CURRENT_TIME, NONE};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    // A client of its own, like a separate application.
+    struct Client {
+        conn: RustConnection,
+        window: Window,
+        atoms: Atoms,
+    }
+
+    impl Client {
+        fn connect() -> Self {
+            let (conn, screen) = RustConnection::connect(None).expect("needs an X server");
+            let root = conn.setup().roots[screen].root;
+            let window = conn.generate_id().unwrap();
+            conn.create_window(
+                COPY_DEPTH_FROM_PARENT,
+                window,
+                root,
+                0,
+                0,
+                1,
+                1,
+                0,
+                WindowClass::INPUT_OUTPUT,
+                COPY_FROM_PARENT,
+                &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+            )
+            .unwrap();
+            let atoms = Atoms::new(&conn).unwrap().reply().unwrap();
+            conn.flush().unwrap();
+            Self {
+                conn,
+                window,
+                atoms,
+            }
+        }
+
+        fn copy(&self) {
+            self.conn
+                .set_selection_owner(self.window, self.atoms.CLIPBOARD, CURRENT_TIME)
+                .unwrap();
+            self.conn.flush().unwrap();
+        }
+
+        // Answers requests for `text` until `done` returns true for an event, or the deadline.
+        fn serve(&self, text: &str, mut done: impl FnMut(&Event) -> bool) {
+            let deadline = Instant::now() + TIMEOUT;
+            while Instant::now() < deadline {
+                let Some(event) = self.conn.poll_for_event().unwrap() else {
+                    thread::sleep(Duration::from_millis(5));
+                    continue;
+                };
+
+                if let Event::SelectionRequest(request) = &event {
+                    let served = if request.target == self.atoms.TARGETS {
+                        self.conn
+                            .change_property32(
+                                PropMode::REPLACE,
+                                request.requestor,
+                                request.property,
+                                AtomEnum::ATOM,
+                                &[self.atoms.TARGETS, self.atoms.UTF8_STRING],
+                            )
+                            .unwrap();
+                        true
+                    } else if request.target == self.atoms.UTF8_STRING {
+                        self.conn
+                            .change_property8(
+                                PropMode::REPLACE,
+                                request.requestor,
+                                request.property,
+                                self.atoms.UTF8_STRING,
+                                text.as_bytes(),
+                            )
+                            .unwrap();
+                        true
+                    } else {
+                        false
+                    };
+                    let notify = SelectionNotifyEvent {
+                        response_type: SELECTION_NOTIFY_EVENT,
+                        sequence: 0,
+                        time: request.time,
+                        requestor: request.requestor,
+                        selection: request.selection,
+                        target: request.target,
+                        property: if served { request.property } else { NONE },
+                    };
+                    self.conn
+                        .send_event(false, request.requestor, EventMask::NO_EVENT, notify)
+                        .unwrap();
+                    self.conn.flush().unwrap();
+                }
+                if done(&event) {
+                    return;
+                }
+            }
+            panic!("timed out serving the clipboard");
+        }
+
+        // Refuses every request until the daemon asked for the targets.
+        fn refuse(&self) {
+            let deadline = Instant::now() + TIMEOUT;
+            while Instant::now() < deadline {
+                let Some(event) = self.conn.poll_for_event().unwrap() else {
+                    thread::sleep(Duration::from_millis(5));
+                    continue;
+                };
+
+                if let Event::SelectionRequest(request) = event {
+                    let notify = SelectionNotifyEvent {
+                        response_type: SELECTION_NOTIFY_EVENT,
+                        sequence: 0,
+                        time: request.time,
+                        requestor: request.requestor,
+                        selection: request.selection,
+                        target: request.target,
+                        property: NONE,
+                    };
+                    self.conn
+                        .send_event(false, request.requestor, EventMask::NO_EVENT, notify)
+                        .unwrap();
+                    self.conn.flush().unwrap();
+                    if request.target == self.atoms.TARGETS {
+                        return;
+                    }
+                }
+            }
+            panic!("timed out refusing the clipboard");
+        }
+
+        // Waits for the clipboard to change hands away from `owner`, then reads its text.
+        fn paste(&self, owner: Window) -> String {
+            let deadline = Instant::now() + TIMEOUT;
+            loop {
+                let current = self
+                    .conn
+                    .get_selection_owner(self.atoms.CLIPBOARD)
+                    .unwrap()
+                    .reply()
+                    .unwrap()
+                    .owner;
+                if current != NONE && current != owner {
+                    break;
+                }
+                assert!(Instant::now() < deadline, "the clipboard was not kept");
+                thread::sleep(Duration::from_millis(20));
+            }
+
+            let property = self.atoms.YZZO_SELECTION;
+            self.conn
+                .convert_selection(
+                    self.window,
+                    self.atoms.CLIPBOARD,
+                    self.atoms.UTF8_STRING,
+                    property,
+                    CURRENT_TIME,
+                )
+                .unwrap();
+            self.conn.flush().unwrap();
+
+            loop {
+                assert!(Instant::now() < deadline, "no reply to the paste");
+                match self.conn.poll_for_event().unwrap() {
+                    Some(Event::SelectionNotify(notify)) => {
+                        assert_ne!(notify.property, NONE, "the paste was refused");
+                        let reply = self
+                            .conn
+                            .get_property(true, self.window, property, AtomEnum::ANY, 0, 1024)
+                            .unwrap()
+                            .reply()
+                            .unwrap();
+                        return String::from_utf8(reply.value).unwrap();
+                    }
+                    Some(_) => {}
+                    None => thread::sleep(Duration::from_millis(5)),
+                }
+            }
+        }
+    }
+
+    // Needs an X server without Wayland or another clipboard manager:
+    // `xvfb-run cargo test -- --ignored`. All cases share one daemon, it can only start once.
+    #[test]
+    #[ignore = "needs an X server"]
+    fn test_clipboard_outlives_its_owner() {
+        start_clipboard_daemon().unwrap();
+        let reader = Client::connect();
+
+        // an owner that exits without a word, the daemon snapshotted it when it copied
+        let owner = Client::connect();
+        owner.copy();
+        let atoms = owner.atoms;
+        owner.serve("kept", |event| {
+            matches!(event, Event::SelectionRequest(request)
+                if request.target == atoms.UTF8_STRING)
+        });
+        let window = owner.window;
+        drop(owner);
+        assert_eq!(reader.paste(window), "kept");
+
+        // an owner that hands its clipboard over through SAVE_TARGETS before exiting
+        let owner = Client::connect();
+        owner.copy();
+        owner
+            .conn
+            .convert_selection(
+                owner.window,
+                atoms.CLIPBOARD_MANAGER,
+                atoms.SAVE_TARGETS,
+                atoms.YZZO_SELECTION,
+                CURRENT_TIME,
+            )
+            .unwrap();
+        owner.conn.flush().unwrap();
+        let mut saved = false;
+        owner.serve("saved", |event| match event {
+            Event::SelectionNotify(notify) if notify.selection == atoms.CLIPBOARD_MANAGER => {
+                saved = notify.property != NONE;
+                true
+            }
+            _ => false,
+        });
+        assert!(saved, "SAVE_TARGETS was refused");
+        let window = owner.window;
+        drop(owner);
+        assert_eq!(reader.paste(window), "saved");
+
+        // an owner whose content cannot be read, the previous copy must not come back
+        let owner = Client::connect();
+        owner.copy();
+        owner.refuse();
+        drop(owner);
+        thread::sleep(Duration::from_millis(500));
+        let current = reader
+            .conn
+            .get_selection_owner(atoms.CLIPBOARD)
+            .unwrap()
+            .reply()
+            .unwrap()
+            .owner;
+        assert_eq!(current, NONE, "the previous copy was restored");
+    }
+}
@@ -0,0 +1,141 @@
+use super::{
+    ClipboardFormat, IMAGE_PNG, PendingFormats, SelfWrites, TEXT_HTML, TEXT_PLAIN, TEXT_RTF,
+    TEXT_URI_LIST, find_format, preferred_format, push_format,
+};
+use crate::image_store;
+use image::{ImageFormat, Rgba, RgbaImage};
+use std::io::Cursor;
+
+#[test]
+fn test_push_format_keeps_first_of_each_mime_type() {
+    let mut formats = Vec::new();
+    push_format(&mut formats, ClipboardFormat::new(TEXT_PLAIN, "first"));
+    push_format(&mut formats, ClipboardFormat::new(TEXT_HTML, "<b>html</b>"));
+    push_format(&mut formats, ClipboardFormat::new(TEXT_PLAIN, "second"));
+
+    assert_eq!(formats.len(), 2);
+    assert_eq!(find_format(&formats, TEXT_PLAIN), Some("first".as_bytes()));
+}
+
+#[test]
+fn test_preferred_format_for_images() {
+    let formats = vec![ClipboardFormat::new(TEXT_HTML, "<img src=\"a.png\">")];
+    assert_eq!(
+        preferred_format("image", &formats),
+        Some("image/png".to_string())
+    );
+}
+
+#[test]
+fn test_preferred_format_prefers_plain_text() {
+    let formats = vec![
+        ClipboardFormat::new(TEXT_RTF, "{\\rtf1 hi}"),
+        ClipboardFormat::new(TEXT_HTML, "<p>hi</p>"),
+        ClipboardFormat::new(TEXT_PLAIN, "hi"),
+    ];
+    assert_eq!(
+        preferred_format("text", &formats),
+        Some(TEXT_PLAIN.to_string())
+    );
+}
+
+#[test]
+fn test_preferred_format_falls_back_to_rich_text() {
+    let formats = vec![
+        ClipboardFormat::new("application/x-custom", "data"),
+        ClipboardFormat::new(TEXT_HTML, "<p>hi</p>"),
+    ];
+    assert_eq!(
+        preferred_format("text", &formats),
+        Some(TEXT_HTML.to_string())
+    );
+}
+
+#[test]
+fn test_preferred_format_without_formats() {
+    assert_eq!(preferred_format("text", &[]), Some(TEXT_PLAIN.to_string()));
+}
+
+#[test]
+fn test_pending_formats_take_once() {
+    let pending = PendingFormats::default();
+    pending.insert(
+        "hello".to_string(),
+        vec![ClipboardFormat::new(TEXT_PLAIN, "hello")],
+    );
+
+    assert_eq!(pending.take("hello").len(), 1);
+    assert!(pending.take("hello").is_empty());
+}
+
+#[test]
+fn test_pending_formats_unknown_content() {
+    let pending = PendingFormats::default();
+    assert!(pending.take("never captured").is_empty());
+}
+
+#[test]
+fn test_pending_formats_drops_oldest_when_full() {
+    let pending = PendingFormats::default();
+    for i in 0..20 {
+        pending.insert(i.to_string(), vec![ClipboardFormat::new(TEXT_PLAIN, "x")]);
+    }
+
+    assert!(pending.take("0").is_empty());
+    assert_eq!(pending.take("19").len(), 1);
+}
+
+#[test]
+fn test_self_writes_match_text() {
+    let self_writes = SelfWrites::default();
+    assert!(!self_writes.is_own_text("hello"));
+
+    self_writes.expect(&[
+        ClipboardFormat::new(TEXT_HTML, "<b>hello</b>"),
+        ClipboardFormat::new(TEXT_PLAIN, "hello"),
+    ]);
+
+    assert!(self_writes.is_own_text("hello"));
+    assert!(!self_writes.is_own_text("<b>hello</b>"));
+    assert!(!self_writes.is_own_image(0));
+}
+
+#[test]
+fn test_self_writes_match_file_lists() {
+    let self_writes = SelfWrites::default();
+    self_writes.expect(&[ClipboardFormat::new(
+        TEXT_URI_LIST,
+        "file:///tmp/a.txt\r\nfile:///tmp/b%20c.txt\r\n",
+    )]);
+
+    assert!(self_writes.is_own_text("/tmp/a.txt\n/tmp/b c.txt"));
+    assert!(self_writes.is_own_text("/tmp/b c.txt"));
+}
+
+#[test]
+fn test_self_writes_match_image_pixels() {
+    let image = RgbaImage::from_pixel(3, 2, Rgba([10, 20, 30, 255]));
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+
+    let self_writes = SelfWrites::default();
+    self_writes.expect(&[
+        ClipboardFormat::new("image/jpeg", vec![0xFF, 0xD8]),
+        ClipboardFormat::new(IMAGE_PNG, png),
+    ]);
+
+    assert!(self_writes.is_own_image(image_store::pixel_hash(3, 2, image.as_raw())));
+    assert!(!self_writes.is_own_image(image_store::pixel_hash(2, 3, image.as_raw())));
+}
+
+#[test]
+fn test_self_writes_keep_only_the_last_write() {
+    let self_writes = SelfWrites::default();
+    self_writes.expect(&[ClipboardFormat::new(TEXT_PLAIN, "first")]);
+    self_writes.expect(&[ClipboardFormat::new(TEXT_PLAIN, "second")]);
+
+    assert!(!self_writes.is_own_text("first"));
+    assert!(self_writes.is_own_text("second"));
+}
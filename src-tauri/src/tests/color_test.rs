@@ -0,0 +1,180 @@
+use super::{ColorNotation, PALETTE_SIZE, detect, distance, palette, parse};
+use image::{Rgba, RgbaImage};
+
+#[test]
+fn test_parse_hex() {
+    let value = parse("#FF8000").unwrap();
+
+    assert_eq!(value.notation, ColorNotation::Hex);
+    assert_eq!(value.hex, "#ff8000");
+    assert_eq!(value.rgb, "rgb(255, 128, 0)");
+    assert_eq!(value.hsl, "hsl(30.1, 100%, 50%)");
+    assert_eq!(value.alpha, 1.0);
+    assert!(value.in_gamut);
+}
+
+#[test]
+fn test_parse_short_hex_with_alpha() {
+    let value = parse("  #f008 ").unwrap();
+
+    assert_eq!(value.hex, "#ff000088");
+    assert_eq!(value.rgb, "rgba(255, 0, 0, 0.533)");
+    assert_eq!(value.hsl, "hsla(0, 100%, 50%, 0.533)");
+}
+
+#[test]
+fn test_issue_references_stay_text() {
+    assert!(detect("#123").is_none());
+    assert!(detect(" #1234").is_none());
+
+    assert!(detect("#0af").is_some());
+    assert!(detect("#f008").is_some());
+    assert!(detect("#123456").is_some());
+    // still converted when asked for explicitly
+    assert_eq!(parse("#123").unwrap().hex, "#112233");
+}
+
+#[test]
+fn test_parse_rgb_syntaxes() {
+    for text in [
+        "rgb(255, 0, 0)",
+        "rgb(255 0 0)",
+        "RGB(100%, 0%, 0%)",
+        "rgba(255,0,0,1)",
+    ] {
+        let value = parse(text).unwrap();
+        assert_eq!(value.notation, ColorNotation::Rgb, "{}", text);
+        assert_eq!(value.hex, "#ff0000", "{}", text);
+    }
+
+    let value = parse("rgb(0 0 255 / 50%)").unwrap();
+    assert_eq!(value.hex, "#0000ff80");
+    assert_eq!(value.alpha, 0.5);
+}
+
+#[test]
+fn test_parse_hsl() {
+    assert_eq!(parse("hsl(120, 100%, 25%)").unwrap().hex, "#008000");
+    assert_eq!(parse("hsl(0.5turn 100% 50%)").unwrap().hex, "#00ffff");
+    assert_eq!(
+        parse("hsla(240deg, 100%, 50%, 0.5)").unwrap().hex,
+        "#0000ff80"
+    );
+    // hues wrap around
+    assert_eq!(parse("hsl(-120, 100%, 50%)").unwrap().hex, "#0000ff");
+}
+
+#[test]
+fn test_parse_oklch() {
+    let value = parse("oklch(62.8% 0.2577 29.23)").unwrap();
+
+    assert_eq!(value.notation, ColorNotation::Oklch);
+    assert_eq!(value.hex, "#ff0000");
+    assert_eq!(value.oklch, "oklch(62.8% 0.2577 29.23)");
+    assert!(value.in_gamut);
+}
+
+#[test]
+fn test_parse_oklch_out_of_gamut() {
+    let value = parse("oklch(0.7 0.4 145)").unwrap();
+
+    assert!(!value.in_gamut);
+    // the other notations are clipped, the oklch value is kept
+    assert_eq!(value.oklch, "oklch(70% 0.4 145)");
+    assert!(value.hex.starts_with('#') && value.hex.len() == 7);
+}
+
+#[test]
+fn test_oklch_round_trip() {
+    let value = parse("#3366cc").unwrap();
+    assert_eq!(parse(&value.oklch).unwrap().hex, "#3366cc");
+    assert_eq!(parse(&value.hsl).unwrap().hex, "#3366cc");
+}
+
+#[test]
+fn test_greys_have_no_hue() {
+    let value = parse("#808080").unwrap();
+    assert_eq!(value.hsl, "hsl(0, 0%, 50.2%)");
+    assert!(value.oklch.ends_with(" 0 0)"), "{}", value.oklch);
+}
+
+#[test]
+fn test_parse_rejects_other_text() {
+    for text in [
+        "",
+        "#",
+        "#12",
+        "#12345",
+        "#ggg",
+        "ff0000",
+        "rgb(1, 2)",
+        "rgb(1, 2, 3, 4, 5)",
+        "rgb(nan, 0, 0)",
+        "cmyk(0, 0, 0, 0)",
+        "color: #ff0000",
+        "#ff0000 and #00ff00",
+        "#ff0000\n#00ff00",
+    ] {
+        assert_eq!(parse(text), None, "{:?}", text);
+    }
+}
+
+#[test]
+fn test_distance() {
+    assert_eq!(distance("#ff0000", "rgb(255, 0, 0)"), Some(0.0));
+    assert!(distance("#ff0000", "#fe0101").unwrap() < 0.01);
+    assert!(distance("#ff0000", "#0000ff").unwrap() > 0.3);
+    assert_eq!(distance("#ff0000", "red"), None);
+}
+
+#[test]
+fn test_palette_orders_by_proportion() {
+    // three quarters red, one quarter blue
+    let image = RgbaImage::from_fn(100, 100, |x, _| {
+        if x < 75 {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 255, 255])
+        }
+    });
+
+    let colors = palette(&image);
+    assert_eq!(colors.len(), 2);
+    assert_eq!(colors[0].hex, "#ff0000");
+    assert_eq!(colors[1].hex, "#0000ff");
+    assert!((colors[0].proportion - 0.75).abs() < 0.03);
+}
+
+#[test]
+fn test_palette_merges_similar_colors() {
+    let image = RgbaImage::from_fn(10, 10, |x, _| {
+        if x % 2 == 0 {
+            Rgba([200, 0, 0, 255])
+        } else {
+            Rgba([205, 4, 2, 255])
+        }
+    });
+
+    let colors = palette(&image);
+    assert_eq!(colors.len(), 1);
+    assert_eq!(colors[0].proportion, 1.0);
+}
+
+#[test]
+fn test_palette_is_capped_and_ignores_transparency() {
+    let image = RgbaImage::from_fn(80, 10, |x, _| {
+        // eight clearly different hues, and a transparent strip
+        let hue = (x / 10) as f64 * 45.0;
+        let hex = parse(&format!("hsl({}, 100%, 50%)", hue)).unwrap().hex;
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        let alpha = if x < 5 { 0 } else { 255 };
+        Rgba([channel(1), channel(3), channel(5), alpha])
+    });
+
+    let colors = palette(&image);
+    assert_eq!(colors.len(), PALETTE_SIZE);
+    let total: f64 = colors.iter().map(|c| c.proportion).sum();
+    assert!((total - 1.0).abs() < 0.01);
+
+    assert!(palette(&RgbaImage::new(4, 4)).is_empty());
+}
@@ -0,0 +1,118 @@
+use super::{
+    FilesMetadata, clipboard_formats, describe, display_content, is_single_image, is_stale,
+    parse_uri_list, path_to_uri,
+};
+use crate::clipboard_formats::{GNOME_COPIED_FILES, TEXT_PLAIN, TEXT_URI_LIST, find_format};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+#[test]
+fn test_parse_uri_list() {
+    let paths = parse_uri_list("file:///home/me/a.pdf\r\nfile:///home/me/My%20Folder\r\n").unwrap();
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("/home/me/a.pdf"),
+            PathBuf::from("/home/me/My Folder")
+        ]
+    );
+}
+
+#[test]
+fn test_parse_gnome_copied_files() {
+    let paths = parse_uri_list("cut\nfile:///tmp/one.txt\nfile:///tmp/two.txt").unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[1], PathBuf::from("/tmp/two.txt"));
+}
+
+#[test]
+fn test_parse_uri_list_with_comments_and_localhost() {
+    let paths = parse_uri_list("# copied by a file manager\nfile://localhost/tmp/doc.txt").unwrap();
+    assert_eq!(paths, vec![PathBuf::from("/tmp/doc.txt")]);
+}
+
+#[test]
+fn test_parse_uri_list_rejects_plain_text() {
+    assert_eq!(parse_uri_list("just some text"), None);
+    assert_eq!(parse_uri_list("file:///tmp/a.txt\nnot a uri"), None);
+    assert_eq!(parse_uri_list("https://yzzo.app"), None);
+    assert_eq!(parse_uri_list(""), None);
+}
+
+#[test]
+fn test_path_to_uri_round_trip() {
+    let path = PathBuf::from("/home/me/My Files/report #1.pdf");
+    let uri = path_to_uri(&path);
+
+    assert_eq!(uri, "file:///home/me/My%20Files/report%20%231.pdf");
+    assert_eq!(parse_uri_list(&uri).unwrap(), vec![path]);
+}
+
+#[test]
+fn test_single_image_detection() {
+    assert!(is_single_image(&[PathBuf::from("/tmp/photo.JPG")]));
+    assert!(is_single_image(&[PathBuf::from("/tmp/logo.svg")]));
+    assert!(!is_single_image(&[PathBuf::from("/tmp/doc.pdf")]));
+    assert!(!is_single_image(&[
+        PathBuf::from("/tmp/a.png"),
+        PathBuf::from("/tmp/b.png")
+    ]));
+}
+
+#[test]
+fn test_clipboard_formats_restore_exact_list() {
+    let paths = vec![PathBuf::from("/tmp/a b.txt"), PathBuf::from("/tmp/dir")];
+    let formats = clipboard_formats(&paths);
+
+    assert_eq!(
+        find_format(&formats, TEXT_URI_LIST),
+        Some("file:///tmp/a%20b.txt\r\nfile:///tmp/dir\r\n".as_bytes())
+    );
+    assert_eq!(
+        find_format(&formats, GNOME_COPIED_FILES),
+        Some("copy\nfile:///tmp/a%20b.txt\nfile:///tmp/dir".as_bytes())
+    );
+    assert_eq!(
+        find_format(&formats, TEXT_PLAIN),
+        Some(display_content(&paths).as_bytes())
+    );
+}
+
+#[test]
+fn test_describe_files_and_folders() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("notes.txt");
+    fs::write(&file, b"hello").unwrap();
+    let folder = temp_dir.path().join("folder");
+    fs::create_dir(&folder).unwrap();
+
+    let meta = describe(&[file, folder]);
+
+    assert_eq!(meta.files[0].name, "notes.txt");
+    assert_eq!(meta.files[0].size, Some(5));
+    assert!(!meta.files[0].is_dir);
+    assert!(meta.files[0].modified.is_some());
+    assert!(meta.files[1].is_dir);
+    assert_eq!(meta.files[1].size, None);
+}
+
+#[test]
+fn test_missing_files_are_stale() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("notes.txt");
+    fs::write(&file, b"hello").unwrap();
+
+    let metadata = serde_json::to_string(&describe(std::slice::from_ref(&file))).unwrap();
+    assert!(!is_stale(&metadata));
+
+    fs::remove_file(&file).unwrap();
+    assert!(is_stale(&metadata));
+}
+
+#[test]
+fn test_invalid_metadata_is_not_stale() {
+    assert!(!is_stale("not json"));
+    let empty = serde_json::to_string(&FilesMetadata { files: vec![] }).unwrap();
+    assert!(!is_stale(&empty));
+}
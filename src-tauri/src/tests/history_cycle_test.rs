@@ -0,0 +1,81 @@
+use super::{HistoryCycle, IDLE_TIMEOUT, Press, wait_for_release};
+use std::time::Instant;
+use tauri_plugin_global_shortcut::Modifiers;
+
+#[test]
+fn test_first_press_selects_the_previous_item() {
+    let cycle = HistoryCycle::default();
+
+    assert_eq!(cycle.press(), Press::Started);
+    assert!(cycle.is_active());
+    assert_eq!(cycle.load(vec![30, 20, 10]), Some(20));
+}
+
+#[test]
+fn test_presses_move_back_and_wrap_around() {
+    let cycle = HistoryCycle::default();
+    cycle.press();
+    cycle.load(vec![30, 20, 10]);
+
+    assert_eq!(cycle.press(), Press::Moved(Some(10)));
+    assert_eq!(cycle.press(), Press::Moved(Some(30)));
+    assert_eq!(cycle.finish(), Some(30));
+    assert!(!cycle.is_active());
+}
+
+#[test]
+fn test_presses_while_loading_are_counted() {
+    let cycle = HistoryCycle::default();
+    cycle.press();
+    assert_eq!(cycle.press(), Press::Moved(None));
+
+    assert_eq!(cycle.load(vec![30, 20, 10]), Some(10));
+}
+
+#[test]
+fn test_single_item_stays_selected() {
+    let cycle = HistoryCycle::default();
+    cycle.press();
+
+    assert_eq!(cycle.load(vec![7]), Some(7));
+    assert_eq!(cycle.press(), Press::Moved(Some(7)));
+}
+
+#[test]
+fn test_finish_starts_over() {
+    let cycle = HistoryCycle::default();
+    cycle.press();
+    assert_eq!(cycle.load(Vec::new()), None);
+    assert_eq!(cycle.finish(), None);
+
+    assert_eq!(cycle.press(), Press::Started);
+    assert_eq!(cycle.load(vec![2, 1]), Some(1));
+}
+
+#[cfg(target_os = "linux")]
+// Without modifiers to watch, as wherever they cannot be read, the cycle ends once the key
+// has not been pressed for a while.
+#[test]
+fn test_cycle_ends_when_idle_without_modifiers() {
+    let cycle = HistoryCycle::default();
+    cycle.press();
+    let started = Instant::now();
+
+    wait_for_release(&cycle, Modifiers::empty());
+
+    assert!(started.elapsed() >= IDLE_TIMEOUT);
+    assert!(cycle.is_active());
+}
+
+#[test]
+fn test_modifier_masks() {
+    use super::modifier_masks;
+    use tauri_plugin_global_shortcut::Modifiers;
+    use x11rb::protocol::xproto::KeyButMask;
+
+    assert_eq!(
+        modifier_masks(Modifiers::ALT | Modifiers::SUPER),
+        vec![KeyButMask::MOD1, KeyButMask::MOD4]
+    );
+    assert!(modifier_masks(Modifiers::empty()).is_empty());
+}
@@ -0,0 +1,35 @@
+use super::{HotkeyAction, MAX_PASTE_INDEX};
+
+#[test]
+fn test_actions_serialize_with_a_type_tag() {
+    assert_eq!(
+        serde_json::to_string(&HotkeyAction::PasteRecent { index: 3 }).unwrap(),
+        r#"{"type":"paste_recent","index":3}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<HotkeyAction>(r#"{"type":"clear_clipboard"}"#).unwrap(),
+        HotkeyAction::ClearClipboard
+    );
+    assert!(serde_json::from_str::<HotkeyAction>(r#"{"type":"format_disk"}"#).is_err());
+}
+
+#[test]
+fn test_paste_recent_index_is_validated() {
+    assert!(HotkeyAction::PasteRecent { index: 1 }.validate().is_ok());
+    assert!(
+        HotkeyAction::PasteRecent {
+            index: MAX_PASTE_INDEX
+        }
+        .validate()
+        .is_ok()
+    );
+    assert!(HotkeyAction::PasteRecent { index: 0 }.validate().is_err());
+    assert!(
+        HotkeyAction::PasteRecent {
+            index: MAX_PASTE_INDEX + 1
+        }
+        .validate()
+        .is_err()
+    );
+    assert!(HotkeyAction::OpenSearch.validate().is_ok());
+}
@@ -0,0 +1,64 @@
+use super::{ImageAccessError, ensure_within};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_file_inside_images_dir_is_allowed() {
+    let temp_dir = TempDir::new().unwrap();
+    let images_dir = temp_dir.path().join("images");
+    fs::create_dir_all(&images_dir).unwrap();
+    let image = images_dir.join("123.png");
+    fs::write(&image, b"png").unwrap();
+
+    let resolved = ensure_within(&images_dir, &image).unwrap();
+    assert_eq!(resolved, image.canonicalize().unwrap());
+}
+
+#[test]
+fn test_file_outside_images_dir_is_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    let images_dir = temp_dir.path().join("images");
+    fs::create_dir_all(&images_dir).unwrap();
+    let secret = temp_dir.path().join("secret.txt");
+    fs::write(&secret, b"secret").unwrap();
+
+    let result = ensure_within(&images_dir, &secret);
+    assert!(matches!(result, Err(ImageAccessError::OutsideImagesDir(_))));
+
+    // `..` is resolved before the check
+    let traversal = images_dir.join("..").join("secret.txt");
+    let result = ensure_within(&images_dir, &traversal);
+    assert!(matches!(result, Err(ImageAccessError::OutsideImagesDir(_))));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlink_out_of_images_dir_is_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    let images_dir = temp_dir.path().join("images");
+    fs::create_dir_all(&images_dir).unwrap();
+    let secret = temp_dir.path().join("secret.txt");
+    fs::write(&secret, b"secret").unwrap();
+    let link = images_dir.join("link.png");
+    std::os::unix::fs::symlink(&secret, &link).unwrap();
+
+    let result = ensure_within(&images_dir, &link);
+    assert!(matches!(result, Err(ImageAccessError::OutsideImagesDir(_))));
+}
+
+#[test]
+fn test_missing_file_is_not_found() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let result = ensure_within(temp_dir.path(), &temp_dir.path().join("gone.png"));
+    assert!(matches!(result, Err(ImageAccessError::NotFound(_))));
+}
+
+#[test]
+fn test_error_serializes_with_kind() {
+    let error = ImageAccessError::OutsideImagesDir("/etc/passwd".to_string());
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        serde_json::json!({ "kind": "outside_images_dir", "message": "/etc/passwd" })
+    );
+}
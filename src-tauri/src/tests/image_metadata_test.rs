@@ -0,0 +1,233 @@
+use super::{EmbeddedMetadata, decode_oriented, parse_exif, read, strip, xmp_value};
+use image::{ImageFormat, Rgb, RgbImage};
+use std::io::Cursor;
+
+// A TIFF structure with Make, Model, Orientation, an EXIF IFD holding DateTimeOriginal and a
+// GPS IFD with a single entry.
+fn exif_chunk(little_endian: bool, orientation: u16) -> Vec<u8> {
+    let u16_bytes = |v: u16| {
+        if little_endian {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        }
+    };
+    let u32_bytes = |v: u32| {
+        if little_endian {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        }
+    };
+    let entry = |out: &mut Vec<u8>, tag: u16, type_: u16, count: u32, value: [u8; 4]| {
+        out.extend_from_slice(&u16_bytes(tag));
+        out.extend_from_slice(&u16_bytes(type_));
+        out.extend_from_slice(&u32_bytes(count));
+        out.extend_from_slice(&value);
+    };
+
+    let mut out = Vec::new();
+    out.extend_from_slice(if little_endian { b"II" } else { b"MM" });
+    out.extend_from_slice(&u16_bytes(42));
+    out.extend_from_slice(&u32_bytes(8));
+
+    // IFD0 at 8 with 5 entries ends at 74
+    let (make_at, model_at, exif_at) = (74u32, 80u32, 86u32);
+    let date_at = exif_at + 2 + 12 + 4;
+    let gps_at = date_at + 20;
+
+    out.extend_from_slice(&u16_bytes(5));
+    entry(&mut out, 0x010F, 2, 6, u32_bytes(make_at));
+    entry(&mut out, 0x0110, 2, 6, u32_bytes(model_at));
+    let mut short = [0u8; 4];
+    short[..2].copy_from_slice(&u16_bytes(orientation));
+    entry(&mut out, 0x0112, 3, 1, short);
+    entry(&mut out, 0x8769, 4, 1, u32_bytes(exif_at));
+    entry(&mut out, 0x8825, 4, 1, u32_bytes(gps_at));
+    out.extend_from_slice(&u32_bytes(0));
+
+    out.extend_from_slice(b"Canon\0");
+    out.extend_from_slice(b"EOS R\0");
+
+    out.extend_from_slice(&u16_bytes(1));
+    entry(&mut out, 0x9003, 2, 20, u32_bytes(date_at));
+    out.extend_from_slice(&u32_bytes(0));
+    out.extend_from_slice(b"2024:05:17 14:03:22\0");
+
+    out.extend_from_slice(&u16_bytes(1));
+    // GPSVersionID, four BYTEs stored inline
+    entry(&mut out, 0x0000, 1, 4, [2, 3, 0, 0]);
+    out.extend_from_slice(&u32_bytes(0));
+
+    out
+}
+
+// A 4x2 JPEG, left half red and right half blue, with an APP1 EXIF segment after SOI.
+fn jpeg_with_exif(orientation: u16) -> Vec<u8> {
+    let image = RgbImage::from_fn(4, 2, |x, _| {
+        if x < 2 {
+            Rgb([255, 0, 0])
+        } else {
+            Rgb([0, 0, 255])
+        }
+    });
+    let mut jpeg = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+        .unwrap();
+
+    let mut payload = b"Exif\0\0".to_vec();
+    payload.extend(exif_chunk(false, orientation));
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+    segment.extend(payload);
+
+    let mut out = jpeg[..2].to_vec();
+    out.extend(segment);
+    out.extend_from_slice(&jpeg[2..]);
+    out
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn png_with_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut png = Vec::new();
+    RgbImage::from_pixel(3, 3, Rgb([10, 20, 30]))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    let mut body = chunk_type.to_vec();
+    body.extend_from_slice(data);
+    chunk.extend_from_slice(&body);
+    chunk.extend_from_slice(&crc32(&body).to_be_bytes());
+
+    // right after the 8-byte signature and the 25-byte IHDR chunk
+    let mut out = png[..33].to_vec();
+    out.extend(chunk);
+    out.extend_from_slice(&png[33..]);
+    out
+}
+
+#[test]
+fn test_parse_exif_little_endian() {
+    let metadata = parse_exif(&exif_chunk(true, 6));
+
+    assert_eq!(metadata.camera_make.as_deref(), Some("Canon"));
+    assert_eq!(metadata.camera_model.as_deref(), Some("EOS R"));
+    assert_eq!(metadata.taken_at.as_deref(), Some("2024:05:17 14:03:22"));
+    assert_eq!(metadata.orientation, 6);
+    assert!(metadata.has_gps);
+}
+
+#[test]
+fn test_parse_exif_big_endian_with_prefix() {
+    let mut chunk = b"Exif\0\0".to_vec();
+    chunk.extend(exif_chunk(false, 3));
+
+    let metadata = parse_exif(&chunk);
+    assert_eq!(metadata.camera_make.as_deref(), Some("Canon"));
+    assert_eq!(metadata.orientation, 3);
+}
+
+#[test]
+fn test_parse_exif_rejects_garbage() {
+    assert_eq!(parse_exif(b"not tiff"), EmbeddedMetadata::default());
+    // an offset pointing past the end must not panic
+    assert_eq!(
+        parse_exif(b"II\x2a\x00\xff\xff\x00\x00"),
+        EmbeddedMetadata::default()
+    );
+}
+
+#[test]
+fn test_parse_exif_ignores_invalid_orientation() {
+    assert_eq!(parse_exif(&exif_chunk(true, 42)).orientation, 1);
+}
+
+#[test]
+fn test_xmp_value_attribute_and_element() {
+    let xmp =
+        r#"<rdf:Description tiff:Make="Nikon"><tiff:Model> Z 6 </tiff:Model></rdf:Description>"#;
+
+    assert_eq!(xmp_value(xmp, "tiff:Make").as_deref(), Some("Nikon"));
+    assert_eq!(xmp_value(xmp, "tiff:Model").as_deref(), Some("Z 6"));
+    assert_eq!(xmp_value(xmp, "exif:DateTimeOriginal"), None);
+}
+
+#[test]
+fn test_read_jpeg_exif() {
+    let metadata = read(&jpeg_with_exif(1)).unwrap();
+
+    assert_eq!(metadata.camera_model.as_deref(), Some("EOS R"));
+    assert!(metadata.has_gps);
+}
+
+#[test]
+fn test_read_without_metadata() {
+    let mut png = Vec::new();
+    RgbImage::new(2, 2)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+
+    assert_eq!(read(&png), None);
+    assert_eq!(read(b"not an image"), None);
+}
+
+#[test]
+fn test_decode_oriented_rotates() {
+    // orientation 6: rotate 90 degrees clockwise, so the red half ends up on top
+    let image = decode_oriented(&jpeg_with_exif(6)).unwrap().to_rgb8();
+
+    assert_eq!(image.dimensions(), (2, 4));
+    assert!(image.get_pixel(0, 0)[0] > 200);
+    assert!(image.get_pixel(0, 3)[2] > 200);
+}
+
+#[test]
+fn test_strip_jpeg_removes_exif() {
+    let jpeg = jpeg_with_exif(1);
+    let stripped = strip(&jpeg).unwrap();
+
+    assert!(stripped.len() < jpeg.len());
+    assert!(!stripped.windows(6).any(|w| w == b"Exif\0\0"));
+    assert_eq!(read(&stripped), None);
+    assert_eq!(
+        image::load_from_memory(&stripped)
+            .unwrap()
+            .to_rgb8()
+            .dimensions(),
+        (4, 2)
+    );
+}
+
+#[test]
+fn test_strip_png_removes_text_chunks() {
+    let png = png_with_chunk(b"tEXt", b"Comment\0taken at home");
+    assert!(image::load_from_memory(&png).is_ok());
+
+    let stripped = strip(&png).unwrap();
+    assert!(!stripped.windows(4).any(|w| w == b"tEXt"));
+    assert_eq!(
+        image::load_from_memory(&stripped).unwrap().to_rgb8(),
+        RgbImage::from_pixel(3, 3, Rgb([10, 20, 30]))
+    );
+}
+
+#[test]
+fn test_strip_unsupported_format() {
+    assert_eq!(strip(b"GIF89a"), None);
+}
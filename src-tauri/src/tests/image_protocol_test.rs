@@ -0,0 +1,118 @@
+use super::{ByteRange, Resource, build_response, parse_range, parse_resource};
+use tauri::http::{Request, StatusCode, header};
+
+fn request(headers: &[(&str, &str)]) -> Request<Vec<u8>> {
+    let mut builder = Request::builder().uri("yzzo://localhost/image/1");
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    builder.body(Vec::new()).unwrap()
+}
+
+#[test]
+fn test_parse_resource() {
+    assert_eq!(parse_resource("/image/42"), Some(Resource::Image(42)));
+    assert_eq!(
+        parse_resource("/thumbnail/42/128"),
+        Some(Resource::Thumbnail(42, 128))
+    );
+    // convertFileSrc encodes the whole path as one component
+    assert_eq!(
+        parse_resource("/thumbnail%2F7%2F512"),
+        Some(Resource::Thumbnail(7, 512))
+    );
+    assert_eq!(parse_resource("/image/abc"), None);
+    assert_eq!(parse_resource("/../../etc/passwd"), None);
+}
+
+#[test]
+fn test_parse_range() {
+    assert_eq!(parse_range(None, 100), ByteRange::Full);
+    assert_eq!(
+        parse_range(Some("bytes=0-9"), 100),
+        ByteRange::Partial(0, 9)
+    );
+    assert_eq!(
+        parse_range(Some("bytes=90-"), 100),
+        ByteRange::Partial(90, 99)
+    );
+    assert_eq!(
+        parse_range(Some("bytes=-10"), 100),
+        ByteRange::Partial(90, 99)
+    );
+    assert_eq!(
+        parse_range(Some("bytes=50-500"), 100),
+        ByteRange::Partial(50, 99)
+    );
+    assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), ByteRange::Full);
+    assert_eq!(
+        parse_range(Some("bytes=100-"), 100),
+        ByteRange::Unsatisfiable
+    );
+    assert_eq!(
+        parse_range(Some("bytes=9-2"), 100),
+        ByteRange::Unsatisfiable
+    );
+}
+
+#[test]
+fn test_full_response_headers() {
+    let response = build_response(&request(&[]), vec![1, 2, 3], "image/gif", "123.gif");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/gif");
+    assert_eq!(response.headers()[header::CONTENT_LENGTH], "3");
+    assert_eq!(response.headers()[header::ETAG], "\"123.gif\"");
+    assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+    assert_eq!(
+        response.headers()[header::CACHE_CONTROL],
+        "private, no-cache"
+    );
+    assert!(
+        response.headers()[header::CONTENT_SECURITY_POLICY]
+            .to_str()
+            .unwrap()
+            .starts_with("default-src 'none'")
+    );
+    assert_eq!(response.body(), &vec![1, 2, 3]);
+}
+
+#[test]
+fn test_partial_response() {
+    let response = build_response(
+        &request(&[("Range", "bytes=1-2")]),
+        vec![1, 2, 3, 4],
+        "image/png",
+        "1.png",
+    );
+
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 1-2/4");
+    assert_eq!(response.body(), &vec![2, 3]);
+}
+
+#[test]
+fn test_unsatisfiable_range() {
+    let response = build_response(
+        &request(&[("Range", "bytes=10-")]),
+        vec![1, 2, 3],
+        "image/png",
+        "1.png",
+    );
+
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes */3");
+}
+
+#[test]
+fn test_matching_etag_is_not_modified() {
+    let response = build_response(
+        &request(&[("If-None-Match", "\"1.png\"")]),
+        vec![1, 2, 3],
+        "image/png",
+        "1.png",
+    );
+
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(response.body().is_empty());
+}
@@ -0,0 +1,378 @@
+use super::{
+    MIN_QUALITY, StorageFormat, StorageOptions, clipboard_formats, encode, hamming_distance,
+    is_animated, mime_type, perceptual_hash, perceptual_hash_from_metadata, pixel_hash,
+    save_original, save_pixels, save_svg,
+};
+use crate::clipboard_formats::{IMAGE_PNG, IMAGE_SVG};
+use image::codecs::gif::GifEncoder;
+use image::{Delay, Frame, ImageFormat, Rgba, RgbaImage};
+use std::fs;
+use tempfile::TempDir;
+
+fn write_gif(path: &std::path::Path, frames: u8) {
+    let file = fs::File::create(path).unwrap();
+    let mut encoder = GifEncoder::new(file);
+    for i in 0..frames {
+        let image = RgbaImage::from_pixel(4, 4, Rgba([i * 60, 0, 0, 255]));
+        encoder
+            .encode_frame(Frame::from_parts(
+                image,
+                0,
+                0,
+                Delay::from_numer_denom_ms(100, 1),
+            ))
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_save_original_keeps_jpeg_bytes() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("photo.jpg");
+    // JPEG has no alpha channel
+    image::RgbImage::from_pixel(8, 6, image::Rgb([200, 100, 50]))
+        .save_with_format(&source, ImageFormat::Jpeg)
+        .unwrap();
+    let images_dir = temp_dir.path().join("images");
+
+    let stored = save_original(&images_dir, &source, &StorageOptions::default()).unwrap();
+
+    assert!(stored.file_name.ends_with(".jpg"));
+    assert_eq!(fs::read(&stored.path).unwrap(), fs::read(&source).unwrap());
+    assert_eq!(stored.metadata["mime_type"], "image/jpeg");
+    assert_eq!(stored.metadata["width"], 8);
+    assert_eq!(stored.metadata["height"], 6);
+    assert_eq!(
+        stored.metadata["source_path"],
+        source.to_string_lossy().as_ref()
+    );
+    assert_eq!(stored.metadata["animated"], false);
+}
+
+#[test]
+fn test_save_original_keeps_gif_animation() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("spinner.gif");
+    write_gif(&source, 3);
+
+    let stored = save_original(temp_dir.path(), &source, &StorageOptions::default()).unwrap();
+
+    assert!(stored.file_name.ends_with(".gif"));
+    assert_eq!(stored.metadata["animated"], true);
+    assert_eq!(fs::read(&stored.path).unwrap(), fs::read(&source).unwrap());
+}
+
+#[test]
+fn test_single_frame_gif_is_not_animated() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("still.gif");
+    write_gif(&source, 1);
+
+    assert!(!is_animated(&fs::read(&source).unwrap(), ImageFormat::Gif));
+}
+
+#[test]
+fn test_save_pixels_stores_png() {
+    let temp_dir = TempDir::new().unwrap();
+    let rgba = RgbaImage::from_pixel(3, 2, Rgba([1, 2, 3, 255])).into_raw();
+    let hash = pixel_hash(3, 2, &rgba);
+
+    let stored = save_pixels(temp_dir.path(), 3, 2, rgba, &StorageOptions::default()).unwrap();
+
+    assert_eq!(stored.hash, hash);
+    assert_eq!(stored.file_name, format!("{}.png", hash));
+    assert_eq!(mime_type(&fs::read(&stored.path).unwrap()), IMAGE_PNG);
+    assert!(stored.metadata.get("source_path").is_none());
+}
+
+#[test]
+fn test_same_pixels_hash_the_same_from_file_and_clipboard() {
+    let temp_dir = TempDir::new().unwrap();
+    let image = RgbaImage::from_pixel(5, 5, Rgba([10, 20, 30, 255]));
+    let source = temp_dir.path().join("copy.png");
+    image.save(&source).unwrap();
+
+    let from_file = save_original(
+        &temp_dir.path().join("a"),
+        &source,
+        &StorageOptions::default(),
+    )
+    .unwrap();
+    let from_pixels = save_pixels(
+        &temp_dir.path().join("b"),
+        5,
+        5,
+        image.into_raw(),
+        &StorageOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(from_file.hash, from_pixels.hash);
+}
+
+#[test]
+fn test_clipboard_formats_offer_original_and_png() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("anim.gif");
+    write_gif(&source, 2);
+
+    let formats = clipboard_formats(&source, false).unwrap();
+
+    assert_eq!(formats.len(), 2);
+    assert_eq!(formats[0].mime_type, "image/gif");
+    assert_eq!(formats[0].data, fs::read(&source).unwrap());
+    assert_eq!(formats[1].mime_type, IMAGE_PNG);
+    assert_eq!(mime_type(&formats[1].data), IMAGE_PNG);
+}
+
+const GREEN_CIRCLE: &str = r##"<?xml version="1.0"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+  <circle cx="12" cy="12" r="10" fill="#00aa00"/>
+</svg>"##;
+
+#[test]
+fn test_save_svg_keeps_the_markup() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let stored = save_svg(temp_dir.path(), GREEN_CIRCLE.as_bytes()).unwrap();
+
+    assert!(stored.file_name.ends_with(".svg"));
+    assert_eq!(fs::read_to_string(&stored.path).unwrap(), GREEN_CIRCLE);
+    assert_eq!(stored.metadata["mime_type"], IMAGE_SVG);
+    assert_eq!(stored.metadata["vector"], true);
+    assert_eq!(stored.metadata["width"], 24);
+    assert_eq!(stored.metadata["palette"][0]["hex"], "#00aa00");
+    assert_eq!(mime_type(GREEN_CIRCLE.as_bytes()), IMAGE_SVG);
+
+    // the same drawing deduplicates to the same file
+    let again = save_svg(temp_dir.path(), GREEN_CIRCLE.as_bytes()).unwrap();
+    assert_eq!(again.file_name, stored.file_name);
+}
+
+#[test]
+fn test_save_original_svg_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("icon.svg");
+    fs::write(&source, GREEN_CIRCLE).unwrap();
+
+    let stored = save_original(
+        &temp_dir.path().join("images"),
+        &source,
+        &StorageOptions::default(),
+    )
+    .unwrap();
+
+    assert!(stored.file_name.ends_with(".svg"));
+    assert_eq!(
+        stored.metadata["source_path"],
+        source.to_string_lossy().as_ref()
+    );
+}
+
+#[test]
+fn test_clipboard_formats_offer_svg_and_png() {
+    let temp_dir = TempDir::new().unwrap();
+    let stored = save_svg(temp_dir.path(), GREEN_CIRCLE.as_bytes()).unwrap();
+
+    let formats = clipboard_formats(&stored.path, false).unwrap();
+
+    assert_eq!(formats.len(), 2);
+    assert_eq!(formats[0].mime_type, IMAGE_SVG);
+    assert_eq!(formats[0].data, GREEN_CIRCLE.as_bytes());
+    assert_eq!(formats[1].mime_type, IMAGE_PNG);
+    let png = image::load_from_memory(&formats[1].data).unwrap();
+    assert_eq!((png.width(), png.height()), (512, 512));
+}
+
+#[test]
+fn test_clipboard_formats_png_only_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("plain.png");
+    RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 255]))
+        .save(&source)
+        .unwrap();
+
+    let formats = clipboard_formats(&source, false).unwrap();
+
+    assert_eq!(formats.len(), 1);
+    assert_eq!(formats[0].mime_type, IMAGE_PNG);
+}
+
+fn gradient(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        let v = ((x * 255 / width) ^ (y * 255 / height)) as u8;
+        Rgba([v, v / 2, 255 - v, 255])
+    })
+}
+
+#[test]
+fn test_perceptual_hash_survives_reencoding_and_scaling() {
+    let temp_dir = TempDir::new().unwrap();
+    let original = gradient(64, 48);
+    let phash = perceptual_hash(&original);
+
+    let jpeg = temp_dir.path().join("copy.jpg");
+    image::DynamicImage::ImageRgba8(original.clone())
+        .to_rgb8()
+        .save_with_format(&jpeg, ImageFormat::Jpeg)
+        .unwrap();
+    let reencoded = image::open(&jpeg).unwrap().to_rgba8();
+    assert!(hamming_distance(phash, perceptual_hash(&reencoded)) <= 5);
+
+    let scaled = image::imageops::resize(&original, 128, 96, image::imageops::FilterType::Nearest);
+    assert!(hamming_distance(phash, perceptual_hash(&scaled)) <= 5);
+}
+
+#[test]
+fn test_perceptual_hash_differs_for_different_images() {
+    let a = gradient(64, 48);
+    let b = image::imageops::flip_horizontal(&a);
+
+    assert!(hamming_distance(perceptual_hash(&a), perceptual_hash(&b)) > 16);
+}
+
+#[test]
+fn test_stored_metadata_records_perceptual_hash() {
+    let temp_dir = TempDir::new().unwrap();
+    let image = gradient(16, 16);
+    let phash = perceptual_hash(&image);
+
+    let stored = save_pixels(
+        temp_dir.path(),
+        16,
+        16,
+        image.into_raw(),
+        &StorageOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        perceptual_hash_from_metadata(&stored.metadata.to_string()),
+        Some(phash)
+    );
+    assert_eq!(perceptual_hash_from_metadata("{}"), None);
+}
+
+#[test]
+fn test_storage_format_and_downscaling_keep_hashes() {
+    let temp_dir = TempDir::new().unwrap();
+    let image = gradient(1200, 600);
+    let raw = image.clone().into_raw();
+    let hash = pixel_hash(1200, 600, &raw);
+    let phash = perceptual_hash(&image);
+
+    let options = StorageOptions {
+        format: StorageFormat::Jpeg,
+        quality: 70,
+        max_dimension: Some(600),
+        ..StorageOptions::default()
+    };
+    let stored = save_pixels(temp_dir.path(), 1200, 600, raw, &options).unwrap();
+
+    assert!(stored.file_name.ends_with(".jpg"));
+    assert_eq!(stored.hash, hash);
+    assert_eq!(
+        perceptual_hash_from_metadata(&stored.metadata.to_string()),
+        Some(phash)
+    );
+    assert_eq!(stored.metadata["width"], 600);
+    assert_eq!(stored.metadata["height"], 300);
+    assert_eq!(stored.metadata["original_width"], 1200);
+    assert_eq!(mime_type(&fs::read(&stored.path).unwrap()), "image/jpeg");
+}
+
+#[test]
+fn test_lossless_webp_storage() {
+    let temp_dir = TempDir::new().unwrap();
+    let image = gradient(32, 32);
+
+    let options = StorageOptions {
+        format: StorageFormat::WebpLossless,
+        ..StorageOptions::default()
+    };
+    let stored = save_pixels(temp_dir.path(), 32, 32, image.clone().into_raw(), &options).unwrap();
+
+    let decoded = image::open(&stored.path).unwrap().to_rgba8();
+    assert_eq!(decoded, image);
+    assert_eq!(stored.metadata["mime_type"], "image/webp");
+}
+
+#[test]
+fn test_lossy_webp_storage() {
+    let temp_dir = TempDir::new().unwrap();
+    let image = gradient(256, 256);
+
+    let lossless = StorageOptions {
+        format: StorageFormat::WebpLossless,
+        ..StorageOptions::default()
+    };
+    let lossy = StorageOptions {
+        format: StorageFormat::Webp,
+        quality: MIN_QUALITY,
+        ..StorageOptions::default()
+    };
+    let dynamic = image::DynamicImage::ImageRgba8(image.clone());
+    assert!(encode(&dynamic, &lossy).unwrap().len() < encode(&dynamic, &lossless).unwrap().len());
+
+    let stored = save_pixels(temp_dir.path(), 256, 256, image.into_raw(), &lossy).unwrap();
+    assert!(stored.file_name.ends_with(".webp"));
+    assert_eq!(stored.metadata["mime_type"], "image/webp");
+    let decoded = image::open(&stored.path).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (256, 256));
+}
+
+#[test]
+fn test_optimized_png_is_lossless_and_smaller() {
+    let image = gradient(256, 256);
+    let dynamic = image::DynamicImage::ImageRgba8(image.clone());
+
+    let plain = encode(&dynamic, &StorageOptions::default()).unwrap();
+    let optimized = encode(
+        &dynamic,
+        &StorageOptions {
+            optimize_png: true,
+            ..StorageOptions::default()
+        },
+    )
+    .unwrap();
+
+    assert!(optimized.len() <= plain.len());
+    assert_eq!(
+        image::load_from_memory(&optimized).unwrap().to_rgba8(),
+        image
+    );
+}
+
+#[test]
+fn test_oversized_original_is_reencoded() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("big.png");
+    gradient(1024, 512).save(&source).unwrap();
+
+    let options = StorageOptions {
+        max_dimension: Some(512),
+        ..StorageOptions::default()
+    };
+    let stored = save_original(&temp_dir.path().join("images"), &source, &options).unwrap();
+
+    assert_eq!(stored.metadata["width"], 512);
+    assert_eq!(
+        stored.metadata["source_path"],
+        source.to_string_lossy().as_ref()
+    );
+}
+
+#[test]
+fn test_storage_options_validation() {
+    assert!(StorageOptions::default().validate().is_ok());
+    let low_quality = StorageOptions {
+        quality: 5,
+        ..StorageOptions::default()
+    };
+    assert!(low_quality.validate().is_err());
+    let tiny = StorageOptions {
+        max_dimension: Some(16),
+        ..StorageOptions::default()
+    };
+    assert!(tiny.validate().is_err());
+}
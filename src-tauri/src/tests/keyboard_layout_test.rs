@@ -0,0 +1,101 @@
+use super::KeyboardLayout;
+use tauri_plugin_global_shortcut::Code;
+
+fn azerty() -> KeyboardLayout {
+    KeyboardLayout::new(vec![
+        (Code::KeyQ, [Some('a'), Some('A')]),
+        (Code::KeyA, [Some('q'), Some('Q')]),
+        (Code::Digit1, [Some('&'), Some('1')]),
+        (Code::Digit2, [Some('é'), Some('2')]),
+    ])
+}
+
+#[test]
+fn test_symbols_map_to_their_physical_key() {
+    let layout = azerty();
+
+    assert_eq!(layout.code_for("A"), Some(Code::KeyQ));
+    assert_eq!(layout.code_for("a"), Some(Code::KeyQ));
+    assert_eq!(layout.code_for("Q"), Some(Code::KeyA));
+    assert_eq!(layout.code_for("é"), Some(Code::Digit2));
+    assert_eq!(layout.code_for("É"), Some(Code::Digit2));
+}
+
+#[test]
+fn test_unshifted_symbols_win() {
+    let layout = KeyboardLayout::new(vec![
+        (Code::Digit1, [Some('&'), Some('1')]),
+        (Code::Numpad1, [Some('1'), None]),
+    ]);
+    assert_eq!(layout.code_for("1"), Some(Code::Numpad1));
+
+    // digits only reachable with Shift on AZERTY
+    assert_eq!(azerty().code_for("1"), Some(Code::Digit1));
+}
+
+#[test]
+fn test_names_and_unknown_symbols_are_not_translated() {
+    let layout = azerty();
+
+    assert_eq!(layout.code_for("Space"), None);
+    assert_eq!(layout.code_for("F5"), None);
+    assert_eq!(layout.code_for("ß"), None);
+    assert_eq!(layout.code_for(""), None);
+}
+
+#[test]
+fn test_keys_are_labelled_with_their_unshifted_symbol() {
+    let layout = azerty();
+
+    assert_eq!(layout.symbol_for(Code::KeyQ), Some("A".to_string()));
+    assert_eq!(layout.symbol_for(Code::Digit2), Some("É".to_string()));
+    assert_eq!(layout.symbol_for(Code::KeyZ), None);
+}
+
+#[cfg(target_os = "linux")]
+mod xkb {
+    use super::super::platform::{RuleNames, compile, parse_rule_names};
+    use tauri_plugin_global_shortcut::Code;
+
+    #[test]
+    fn test_parse_rule_names() {
+        assert_eq!(
+            parse_rule_names(b"evdev\0pc105\0fr,us\0\0grp:alt_shift_toggle\0"),
+            Some(RuleNames {
+                rules: "evdev".to_string(),
+                model: "pc105".to_string(),
+                layout: "fr,us".to_string(),
+                variant: String::new(),
+                options: "grp:alt_shift_toggle".to_string(),
+            })
+        );
+        assert_eq!(parse_rule_names(b""), None);
+        assert_eq!(parse_rule_names(b"evdev\0pc105\0\0\0\0"), None);
+    }
+
+    // Needs libxkbcommon and the xkeyboard-config data, skipped without them.
+    #[test]
+    fn test_compiles_named_layouts() {
+        let names = |layout: &str| RuleNames {
+            rules: "evdev".to_string(),
+            model: "pc105".to_string(),
+            layout: layout.to_string(),
+            ..Default::default()
+        };
+        let Some(us) = compile(Some(&names("us"))) else {
+            println!("xkbcommon unavailable, skipping");
+            return;
+        };
+        assert_eq!(us.code_for("A"), Some(Code::KeyA));
+        assert_eq!(us.code_for("`"), Some(Code::Backquote));
+
+        let azerty = compile(Some(&names("fr"))).unwrap();
+        assert_eq!(azerty.code_for("A"), Some(Code::KeyQ));
+        assert_eq!(azerty.code_for("Z"), Some(Code::KeyW));
+        assert_eq!(azerty.symbol_for(Code::KeyQ), Some("A".to_string()));
+
+        let qwertz = compile(Some(&names("de"))).unwrap();
+        assert_eq!(qwertz.code_for("Y"), Some(Code::KeyZ));
+        assert_eq!(qwertz.code_for("ß"), Some(Code::Minus));
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(unix)]
+use super::recognize_with_timeout;
+use super::{MAX_OCR_TEXT_LENGTH, OcrError, clean_text, is_available, recognize};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+#[cfg(unix)]
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+#[cfg(unix)]
+use tempfile::TempDir;
+
+// A stand-in for tesseract that swallows the image and prints `output`.
+#[cfg(unix)]
+fn fake_tesseract(dir: &Path, output: &str, exit_code: i32) -> PathBuf {
+    write_script(
+        dir,
+        &format!(
+            "#!/bin/sh\ncat > /dev/null\nprintf '%s' '{}'\nexit {}\n",
+            output, exit_code
+        ),
+    )
+}
+
+#[cfg(unix)]
+fn write_script(dir: &Path, script: &str) -> PathBuf {
+    let path = dir.join("tesseract");
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn test_clean_text() {
+    assert_eq!(
+        clean_text("  Error:   file\tnot found \n\n\n  at line 3  \n\x0c"),
+        "Error: file not found\nat line 3"
+    );
+    assert_eq!(clean_text(" \n \n"), "");
+}
+
+#[test]
+fn test_clean_text_caps_length() {
+    let text = clean_text(&"é".repeat(MAX_OCR_TEXT_LENGTH + 10));
+    assert_eq!(text.chars().count(), MAX_OCR_TEXT_LENGTH);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_recognize_with_tesseract() {
+    let temp_dir = TempDir::new().unwrap();
+    let binary = fake_tesseract(temp_dir.path(), "Permission denied  \n\n", 0);
+
+    assert!(is_available(&binary));
+    assert_eq!(
+        recognize(&binary, b"png bytes"),
+        Ok("Permission denied".to_string())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_recognize_reports_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let binary = fake_tesseract(temp_dir.path(), "", 1);
+
+    assert!(matches!(
+        recognize(&binary, b"png bytes"),
+        Err(OcrError::Failed(_))
+    ));
+}
+
+#[test]
+fn test_missing_tesseract() {
+    let binary = Path::new("/nonexistent/tesseract");
+
+    assert!(!is_available(binary));
+    assert!(matches!(
+        recognize(binary, b"png bytes"),
+        Err(OcrError::NotInstalled(_))
+    ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hung_tesseract_is_killed() {
+    let temp_dir = TempDir::new().unwrap();
+    let binary = write_script(
+        temp_dir.path(),
+        "#!/bin/sh\ncat > /dev/null\nexec sleep 30\n",
+    );
+
+    let started = Instant::now();
+    let result = recognize_with_timeout(&binary, b"png bytes", Duration::from_millis(200));
+
+    assert!(matches!(result, Err(OcrError::Failed(_))));
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
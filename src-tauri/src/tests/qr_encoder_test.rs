@@ -0,0 +1,108 @@
+use super::{MAX_QR_SIZE, MIN_QR_SIZE, QrError, QrLevel, RenderedQr, render};
+use crate::barcodes;
+
+fn decode_png(rendered: &RenderedQr) -> Vec<String> {
+    let data = rendered.png.strip_prefix("data:image/png;base64,").unwrap();
+    let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, data).unwrap();
+    let image = image::load_from_memory(&bytes).unwrap().to_rgba8();
+    assert_eq!(image.dimensions(), (rendered.size, rendered.size));
+
+    barcodes::scan(&image)
+        .into_iter()
+        .map(|code| code.text)
+        .collect()
+}
+
+#[test]
+fn test_render_round_trip() {
+    let rendered = render("https://example.com/a?b=c", None, 300).unwrap();
+
+    assert_eq!(rendered.level, QrLevel::M);
+    assert_eq!(rendered.version, 2);
+    assert_eq!(rendered.modules, 25);
+    // 33 modules with the quiet zone at 9 pixels each
+    assert_eq!(rendered.size, 297);
+    assert_eq!(decode_png(&rendered), vec!["https://example.com/a?b=c"]);
+}
+
+#[test]
+fn test_render_svg() {
+    let rendered = render("hello", Some(QrLevel::H), 200).unwrap();
+
+    assert!(rendered.svg.contains("<svg"));
+    assert!(
+        rendered
+            .svg
+            .contains(&format!("width=\"{}\"", rendered.size))
+    );
+    assert!(rendered.svg.ends_with("</svg>"));
+}
+
+#[test]
+fn test_render_levels() {
+    let text = "The quick brown fox jumps over the lazy dog";
+    let versions: Vec<i16> = [QrLevel::L, QrLevel::M, QrLevel::Q, QrLevel::H]
+        .into_iter()
+        .map(|level| {
+            let rendered = render(text, Some(level), 512).unwrap();
+            assert_eq!(rendered.level, level);
+            assert_eq!(decode_png(&rendered), vec![text]);
+            rendered.version
+        })
+        .collect();
+
+    // more error correction needs a bigger code for the same text
+    assert!(versions.windows(2).all(|w| w[0] <= w[1]));
+    assert!(versions[0] < versions[3]);
+}
+
+#[test]
+fn test_render_clamps_size() {
+    // a version 1 code is 29 modules across with its quiet zone
+    assert_eq!(render("tiny", None, 1).unwrap().size, MIN_QR_SIZE / 29 * 29);
+    assert_eq!(
+        render("huge", None, 100_000).unwrap().size,
+        MAX_QR_SIZE / 29 * 29
+    );
+}
+
+#[test]
+fn test_render_falls_back_to_low_error_correction() {
+    let text = "x".repeat(QrLevel::M.max_bytes() + 100);
+    let rendered = render(&text, None, 1024).unwrap();
+
+    assert_eq!(rendered.level, QrLevel::L);
+    assert!(rendered.version > 30);
+    assert_eq!(decode_png(&rendered), vec![text]);
+}
+
+#[test]
+fn test_render_rejects_long_text() {
+    let text = "x".repeat(QrLevel::M.max_bytes() + 100);
+    assert_eq!(
+        render(&text, Some(QrLevel::M), 512).unwrap_err(),
+        QrError::TooLong {
+            length: text.len(),
+            max: QrLevel::M.max_bytes()
+        }
+    );
+
+    let text = "x".repeat(QrLevel::L.max_bytes() + 1);
+    assert!(matches!(
+        render(&text, None, 512),
+        Err(QrError::TooLong { max: 2953, .. })
+    ));
+}
+
+#[test]
+fn test_render_empty() {
+    assert_eq!(render("", None, 512).unwrap_err(), QrError::Empty);
+}
+
+#[test]
+fn test_level_deserializes_from_letter() {
+    assert_eq!(
+        serde_json::from_str::<QrLevel>("\"Q\"").unwrap(),
+        QrLevel::Q
+    );
+}
@@ -0,0 +1,92 @@
+use super::{html_to_markdown, html_to_plain_text, rtf_to_plain_text};
+
+#[test]
+fn test_html_to_plain_text_strips_tags() {
+    let html = "<p>Hello <b>world</b></p><p>Second <a href=\"https://yzzo.app\">link</a></p>";
+    assert_eq!(html_to_plain_text(html), "Hello world\nSecond link");
+}
+
+#[test]
+fn test_html_to_plain_text_line_breaks_and_lists() {
+    let html = "<ul><li>one</li><li>two</li></ul>line<br>break";
+    assert_eq!(html_to_plain_text(html), "- one\n- two\nline\nbreak");
+}
+
+#[test]
+fn test_html_to_plain_text_skips_hidden_content() {
+    let html = "<html><head><style>p { color: red; }</style></head>\
+                <body><script>alert(1)</script><p>Visible</p><!-- a > b --></body></html>";
+    assert_eq!(html_to_plain_text(html), "Visible");
+}
+
+#[test]
+fn test_html_to_plain_text_decodes_entities() {
+    let html = "<p>Tom &amp; Jerry &lt;3 &#169; &#x2014; &unknown;</p>";
+    assert_eq!(
+        html_to_plain_text(html),
+        "Tom & Jerry <3 \u{a9} \u{2014} &unknown;"
+    );
+}
+
+#[test]
+fn test_html_to_plain_text_collapses_source_whitespace() {
+    let html = "<p>\n    spread\n    over   lines\n</p>";
+    assert_eq!(html_to_plain_text(html), "spread over lines");
+}
+
+#[test]
+fn test_html_to_markdown() {
+    let html = "<h1>Title</h1><p>Some <strong>bold</strong> and <a href=\"https://yzzo.app\">a link</a></p>";
+    let markdown = html_to_markdown(html);
+
+    assert!(markdown.contains("Title"));
+    assert!(markdown.contains("**bold**"));
+    assert!(markdown.contains("[a link](https://yzzo.app)"));
+}
+
+#[test]
+fn test_rtf_to_plain_text() {
+    let rtf = r"{\rtf1\ansi{\fonttbl\f0\fswiss Helvetica;}{\colortbl;\red255\green0\blue0;}
+\f0\pard Hello {\b bold} world\par
+Second line}";
+    assert_eq!(rtf_to_plain_text(rtf), "Hello bold world\nSecond line");
+}
+
+#[test]
+fn test_rtf_to_plain_text_escapes() {
+    let rtf = r"{\rtf1 caf\'e9 \{braces\} back\\slash \u8364?}";
+    assert_eq!(
+        rtf_to_plain_text(rtf),
+        "caf\u{e9} {braces} back\\slash \u{20ac}"
+    );
+}
+
+#[test]
+fn test_rtf_to_plain_text_decodes_the_code_page() {
+    // Word and macOS write smart quotes and the euro sign as Windows-1252 bytes
+    let rtf = r"{\rtf1\ansi\ansicpg1252 \'93quoted\'94 \'96 5\'80}";
+    assert_eq!(
+        rtf_to_plain_text(rtf),
+        "\u{201C}quoted\u{201D} \u{2013} 5\u{20AC}"
+    );
+    // the default without a declaration is Windows-1252 as well
+    assert_eq!(
+        rtf_to_plain_text(r"{\rtf1\ansi \'93a\'94}"),
+        "\u{201C}a\u{201D}"
+    );
+    // double-byte code pages spread a character over two escapes
+    assert_eq!(
+        rtf_to_plain_text(r"{\rtf1\ansi\ansicpg932 \'93\'fa\'96\'7b}"),
+        "\u{65E5}\u{672C}"
+    );
+    assert_eq!(
+        rtf_to_plain_text(r"{\rtf1\mac \'d2x\'d3}"),
+        "\u{201C}x\u{201D}"
+    );
+}
+
+#[test]
+fn test_rtf_to_plain_text_skips_ignorable_destinations() {
+    let rtf = r"{\rtf1{\*\generator Writer;}{\info{\author Someone}}Text}";
+    assert_eq!(rtf_to_plain_text(rtf), "Text");
+}
@@ -0,0 +1,89 @@
+use super::{MAX_RASTER_SIZE, MIN_RASTER_SIZE, intrinsic_size, is_svg, parse, rasterize};
+
+const RED_SQUARE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="20">
+  <rect width="10" height="10" fill="#ff0000"/>
+</svg>"##;
+
+#[test]
+fn test_is_svg() {
+    assert!(is_svg(RED_SQUARE.as_bytes()));
+    assert!(is_svg(b"<svg/>"));
+    assert!(is_svg(
+        b"\xef\xbb\xbf<?xml version=\"1.0\"?>\n<!-- Generator: Inkscape -->\n<svg>"
+    ));
+    assert!(is_svg(
+        b"<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" [\n<!ENTITY ns \"x\">\n]>\n<svg>"
+    ));
+}
+
+#[test]
+fn test_is_svg_rejects_other_markup() {
+    for text in [
+        "",
+        "<svgfoo>",
+        "<html><svg></svg></html>",
+        "use <svg> for icons",
+        "<?xml version=\"1.0\"?><rss/>",
+        "<!-- unterminated <svg>",
+    ] {
+        assert!(!is_svg(text.as_bytes()), "{:?}", text);
+    }
+}
+
+#[test]
+fn test_rasterize_scales_small_drawings_up() {
+    let image = rasterize(RED_SQUARE.as_bytes()).unwrap();
+
+    assert_eq!(image.dimensions(), (MIN_RASTER_SIZE / 2, MIN_RASTER_SIZE));
+    assert_eq!(image.get_pixel(10, 10).0, [255, 0, 0, 255]);
+    // the lower half is not drawn
+    assert_eq!(image.get_pixel(10, MIN_RASTER_SIZE - 10).0[3], 0);
+}
+
+#[test]
+fn test_rasterize_scales_large_drawings_down() {
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="8000" height="1000"/>"#;
+    let tree = parse(svg.as_bytes()).unwrap();
+
+    assert_eq!(intrinsic_size(&tree), (8000, 1000));
+    assert_eq!(
+        rasterize(svg.as_bytes()).unwrap().dimensions(),
+        (MAX_RASTER_SIZE, MAX_RASTER_SIZE / 8)
+    );
+}
+
+#[test]
+fn test_rasterize_keeps_translucency() {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="600" height="600">
+  <rect width="600" height="600" fill="#0000ff" fill-opacity="0.5"/>
+</svg>"##;
+    let [r, g, b, a] = rasterize(svg.as_bytes()).unwrap().get_pixel(0, 0).0;
+
+    assert_eq!((r, g), (0, 0));
+    assert!(b >= 254, "{}", b);
+    assert!((127..=128).contains(&a), "{}", a);
+}
+
+#[test]
+fn test_local_files_are_not_loaded() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let nested = dir.path().join("nested.svg");
+    std::fs::write(
+        &nested,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4"/></svg>"#,
+    )
+    .unwrap();
+
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="600" height="600"><image href="{}" width="600" height="600"/></svg>"#,
+        nested.display()
+    );
+    let image = rasterize(svg.as_bytes()).unwrap();
+    assert!(image.pixels().all(|p| p.0[3] == 0));
+}
+
+#[test]
+fn test_parse_rejects_invalid_svg() {
+    assert!(parse(b"<svg").is_err());
+    assert!(parse(b"not svg").is_err());
+}
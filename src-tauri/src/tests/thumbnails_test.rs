@@ -0,0 +1,88 @@
+use super::{THUMBNAIL_SIZES, ThumbnailQueue, ensure, existing, generate, remove, thumbnail_path};
+use image::{GenericImageView, Rgba, RgbaImage};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write_image(dir: &Path, width: u32, height: u32) -> std::path::PathBuf {
+    let path = dir.join("123.png");
+    RgbaImage::from_pixel(width, height, Rgba([40, 80, 120, 255]))
+        .save(&path)
+        .unwrap();
+    path
+}
+
+#[test]
+fn test_thumbnail_path_is_next_to_image() {
+    let path = thumbnail_path(Path::new("/data/images/123.jpg"), 128);
+    assert_eq!(path, Path::new("/data/images/thumbnails/123_128.webp"));
+}
+
+#[test]
+fn test_generate_all_sizes_keeping_aspect_ratio() {
+    let temp_dir = TempDir::new().unwrap();
+    let image_path = write_image(temp_dir.path(), 1600, 800);
+
+    generate(&image_path).unwrap();
+
+    let small = image::open(thumbnail_path(&image_path, 128)).unwrap();
+    assert_eq!(small.dimensions(), (128, 64));
+    let large = image::open(thumbnail_path(&image_path, 512)).unwrap();
+    assert_eq!(large.dimensions(), (512, 256));
+    assert_eq!(existing(&image_path).len(), THUMBNAIL_SIZES.len());
+}
+
+#[test]
+fn test_small_images_are_not_upscaled() {
+    let temp_dir = TempDir::new().unwrap();
+    let image_path = write_image(temp_dir.path(), 40, 30);
+
+    generate(&image_path).unwrap();
+
+    let large = image::open(thumbnail_path(&image_path, 512)).unwrap();
+    assert_eq!(large.dimensions(), (40, 30));
+}
+
+#[test]
+fn test_ensure_regenerates_missing_thumbnail() {
+    let temp_dir = TempDir::new().unwrap();
+    let image_path = write_image(temp_dir.path(), 300, 300);
+
+    generate(&image_path).unwrap();
+    std::fs::remove_file(thumbnail_path(&image_path, 128)).unwrap();
+    assert_eq!(existing(&image_path).len(), 1);
+
+    let path = ensure(&image_path, 128).unwrap();
+    assert!(path.exists());
+    assert!(ensure(&image_path, 64).is_err());
+}
+
+#[test]
+fn test_remove_thumbnails() {
+    let temp_dir = TempDir::new().unwrap();
+    let image_path = write_image(temp_dir.path(), 200, 200);
+
+    generate(&image_path).unwrap();
+    remove(&image_path);
+
+    assert!(existing(&image_path).is_empty());
+}
+
+#[test]
+fn test_queue_does_not_retry_failed_images() {
+    let dir = TempDir::new().unwrap();
+    let image_path = dir.path().join("broken.png");
+    std::fs::write(&image_path, b"not an image").unwrap();
+
+    let queue = ThumbnailQueue::spawn();
+    queue.enqueue(&image_path);
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !queue.has_failed(&image_path) {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "the failure was not recorded"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(existing(&image_path).is_empty());
+}
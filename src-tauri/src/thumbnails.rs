@@ -0,0 +1,148 @@
+use crate::image_metadata;
+use crate::models::Thumbnail;
+use image::{DynamicImage, ImageFormat};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// longest edge of each generated preview, in pixels
+pub const THUMBNAIL_SIZES: &[u32] = &[128, 512];
+
+const THUMBNAILS_DIR: &str = "thumbnails";
+
+// Thumbnails live next to the image they preview, in a `thumbnails` subdirectory.
+pub fn thumbnail_path(image_path: &Path, size: u32) -> PathBuf {
+    let stem = image_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir = image_path.parent().unwrap_or(Path::new("."));
+
+    dir.join(THUMBNAILS_DIR)
+        .join(format!("{}_{}.webp", stem, size))
+}
+
+// Writes every missing thumbnail of an image, decoding it at most once.
+pub fn generate(image_path: &Path) -> Result<(), String> {
+    let missing: Vec<u32> = THUMBNAIL_SIZES
+        .iter()
+        .copied()
+        .filter(|size| !thumbnail_path(image_path, *size).exists())
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let bytes = std::fs::read(image_path).map_err(|e| format!("Failed to read image: {}", e))?;
+    // thumbnails carry no EXIF, so the orientation is baked into the pixels
+    let image = image_metadata::decode_oriented(&bytes)?;
+
+    for size in missing {
+        write_thumbnail(&image, &thumbnail_path(image_path, size), size)?;
+    }
+
+    Ok(())
+}
+
+fn write_thumbnail(image: &DynamicImage, path: &Path, size: u32) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create thumbnails directory: {}", e))?;
+    }
+
+    // images already smaller than the thumbnail are re-encoded but never upscaled
+    let thumbnail = if image.width() > size || image.height() > size {
+        image.thumbnail(size, size)
+    } else {
+        image.clone()
+    };
+
+    // the WebP encoder only takes 8-bit RGB(A)
+    DynamicImage::ImageRgba8(thumbnail.to_rgba8())
+        .save_with_format(path, ImageFormat::WebP)
+        .map_err(|e| format!("Failed to save thumbnail: {}", e))
+}
+
+// Returns the thumbnail of the given size, generating it first if it went missing.
+pub fn ensure(image_path: &Path, size: u32) -> Result<PathBuf, String> {
+    if !THUMBNAIL_SIZES.contains(&size) {
+        return Err(format!("Unsupported thumbnail size: {}", size));
+    }
+
+    let path = thumbnail_path(image_path, size);
+    if !path.exists() {
+        generate(image_path)?;
+    }
+    Ok(path)
+}
+
+// References to the thumbnails of an image that exist on disk.
+pub fn existing(image_path: &Path) -> Vec<Thumbnail> {
+    THUMBNAIL_SIZES
+        .iter()
+        .map(|size| (*size, thumbnail_path(image_path, *size)))
+        .filter(|(_, path)| path.exists())
+        .map(|(size, path)| Thumbnail {
+            size,
+            path: path.to_string_lossy().into_owned(),
+        })
+        .collect()
+}
+
+pub fn remove(image_path: &Path) {
+    for size in THUMBNAIL_SIZES {
+        let _ = std::fs::remove_file(thumbnail_path(image_path, *size));
+    }
+}
+
+// Generates thumbnails off the command and watcher threads. Images that fail are not tried
+// again until the next start, listing the items would otherwise queue them every time.
+#[derive(Clone)]
+pub struct ThumbnailQueue {
+    sender: Sender<PathBuf>,
+    failed: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl ThumbnailQueue {
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel::<PathBuf>();
+        let failed: Arc<Mutex<HashSet<PathBuf>>> = Default::default();
+
+        let worker_failed = failed.clone();
+        thread::spawn(move || {
+            for image_path in receiver {
+                if worker_failed.lock().unwrap().contains(&image_path) {
+                    continue;
+                }
+                if let Err(e) = generate(&image_path) {
+                    eprintln!(
+                        "[X] Failed to generate thumbnails for {}: {}",
+                        image_path.display(),
+                        e
+                    );
+                    worker_failed.lock().unwrap().insert(image_path);
+                }
+            }
+        });
+
+        Self { sender, failed }
+    }
+
+    pub fn enqueue(&self, image_path: impl Into<PathBuf>) {
+        let image_path = image_path.into();
+        if self.has_failed(&image_path) {
+            return;
+        }
+        let _ = self.sender.send(image_path);
+    }
+
+    pub fn has_failed(&self, image_path: &Path) -> bool {
+        self.failed.lock().unwrap().contains(image_path)
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/thumbnails_test.rs"]
+mod thumbnails_test;
@@ -1,5 +1,13 @@
-import { invoke } from "@tauri-apps/api/core";
-import type { Item } from "@yzzo/models/Item";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import type {
+  ColorMetadata,
+  DecodedCode,
+  Item,
+  PaletteColor,
+  QrLevel,
+  RenderedQr,
+} from "@yzzo/models/Item";
+import type { HotkeyAction, HotkeyBinding } from "@yzzo/types";
 
 export async function addItem(
   content: string,
@@ -44,18 +52,71 @@ export async function deleteItem(id: number): Promise<void> {
   }
 }
 
-export async function getImageBase64(filePath: string): Promise<string> {
+// puts any item back on the clipboard in all its formats and moves it to the top, without the
+// watcher capturing it again
+export async function copyItem(id: number): Promise<void> {
   try {
-    return await invoke("get_image_base64", { filePath });
+    await invoke("copy_item", { id });
   } catch (err) {
-    console.error("Failed to get image base64:", err);
+    console.error("Failed to copy item:", err);
     throw err;
   }
 }
 
-export async function writeImageToClipboard(filePath: string): Promise<void> {
+// copies the item, hides the window and pastes it into the application focused before
+export async function pasteItem(id: number): Promise<void> {
   try {
-    await invoke("write_image_to_clipboard", { filePath });
+    await invoke("paste_item", { id });
+  } catch (err) {
+    console.error("Failed to paste item:", err);
+    throw err;
+  }
+}
+
+export type CopyMode = "rich" | "plain" | "markdown";
+
+export async function copyItemAs(id: number, mode: CopyMode): Promise<void> {
+  try {
+    await invoke("copy_item_as", { id, mode });
+  } catch (err) {
+    console.error("Failed to copy item:", err);
+    throw err;
+  }
+}
+
+export async function getItemFormats(id: number): Promise<string[]> {
+  try {
+    return await invoke("get_item_formats", { id });
+  } catch (err) {
+    console.error("Failed to get item formats:", err);
+    return [];
+  }
+}
+
+// Error returned by the id-based image commands.
+export interface ImageAccessError {
+  kind:
+    | "not_found"
+    | "not_an_image"
+    | "outside_images_dir"
+    | "io"
+    | "database"
+    | "clipboard";
+  message: string;
+}
+
+// Images are served by the backend's yzzo:// protocol, so they load as plain <img> URLs.
+export function thumbnailUrl(id: number, size: number): string {
+  return convertFileSrc(`thumbnail/${id}/${size}`, "yzzo");
+}
+
+// `stripMetadata` overrides the strip_image_metadata setting for this copy
+export async function writeImageToClipboard(
+  id: number,
+  stripMetadata?: boolean,
+): Promise<void> {
+  try {
+    await invoke("write_image_to_clipboard", { id, stripMetadata });
   } catch (err) {
     console.error("Failed to write image to clipboard:", err);
     throw err;
@@ -70,3 +131,250 @@ export async function clearAllItems(): Promise<void> {
     throw err;
   }
 }
+
+export async function getImageSimilarityThreshold(): Promise<number> {
+  try {
+    return await invoke("get_image_similarity_threshold");
+  } catch (err) {
+    console.error("Failed to get image similarity threshold:", err);
+    throw err;
+  }
+}
+
+export async function setImageSimilarityThreshold(
+  threshold: number,
+): Promise<void> {
+  try {
+    await invoke("set_image_similarity_threshold", { threshold });
+  } catch (err) {
+    console.error("Failed to set image similarity threshold:", err);
+    throw err;
+  }
+}
+
+export type ImageStorageFormat = "png" | "webp_lossless" | "webp" | "jpeg";
+
+export interface ImageStorageOptions {
+  format: ImageStorageFormat;
+  quality: number;
+  max_dimension: number | null;
+  optimize_png: boolean;
+  keep_originals: boolean;
+}
+
+export async function getImageStorage(): Promise<ImageStorageOptions> {
+  try {
+    return await invoke("get_image_storage");
+  } catch (err) {
+    console.error("Failed to get image storage settings:", err);
+    throw err;
+  }
+}
+
+export async function setImageStorage(
+  options: ImageStorageOptions,
+): Promise<void> {
+  try {
+    await invoke("set_image_storage", { options });
+  } catch (err) {
+    console.error("Failed to set image storage settings:", err);
+    throw err;
+  }
+}
+
+export async function getStripImageMetadata(): Promise<boolean> {
+  try {
+    return await invoke("get_strip_image_metadata");
+  } catch (err) {
+    console.error("Failed to get strip image metadata setting:", err);
+    throw err;
+  }
+}
+
+export async function setStripImageMetadata(
+  stripMetadata: boolean,
+): Promise<void> {
+  try {
+    await invoke("set_strip_image_metadata", { stripMetadata });
+  } catch (err) {
+    console.error("Failed to set strip image metadata setting:", err);
+    throw err;
+  }
+}
+
+export interface PasteTarget {
+  // X11 WM_CLASS or Wayland app id, compared without case
+  app: string;
+  shortcut: string;
+}
+
+export interface AutoPasteOptions {
+  enabled: boolean;
+  shortcut: string;
+  targets: PasteTarget[];
+}
+
+export async function getAutoPaste(): Promise<AutoPasteOptions> {
+  try {
+    return await invoke("get_auto_paste");
+  } catch (err) {
+    console.error("Failed to get auto-paste settings:", err);
+    throw err;
+  }
+}
+
+export async function setAutoPaste(options: AutoPasteOptions): Promise<void> {
+  try {
+    await invoke("set_auto_paste", { options });
+  } catch (err) {
+    console.error("Failed to set auto-paste settings:", err);
+    throw err;
+  }
+}
+
+// Pasting into other applications is only implemented on Linux.
+export async function getAutoPasteAvailable(): Promise<boolean> {
+  try {
+    return await invoke("get_auto_paste_available");
+  } catch (err) {
+    console.error("Failed to get auto-paste availability:", err);
+    throw err;
+  }
+}
+
+export async function getHotkeyBindings(): Promise<HotkeyBinding[]> {
+  try {
+    return await invoke("get_hotkey_bindings");
+  } catch (err) {
+    console.error("Failed to get hotkey bindings:", err);
+    throw err;
+  }
+}
+
+// `hotkey` may be a chord such as "Ctrl+Alt+V, 3", the leader followed by the key pressed
+// within a moment of it. Rejects with a `HotkeyError`.
+export async function bindHotkey(
+  hotkey: string,
+  action: HotkeyAction,
+  code?: string,
+): Promise<void> {
+  try {
+    await invoke("bind_hotkey", { hotkey, code, action });
+  } catch (err) {
+    console.error("Failed to bind hotkey:", err);
+    throw err;
+  }
+}
+
+export async function unbindHotkey(hotkey: string): Promise<void> {
+  try {
+    await invoke("unbind_hotkey", { hotkey });
+  } catch (err) {
+    console.error("Failed to unbind hotkey:", err);
+    throw err;
+  }
+}
+
+// Wayland and the other platforms only end a history cycle after a pause in the presses.
+export async function getCycleReleaseDetected(): Promise<boolean> {
+  try {
+    return await invoke("get_cycle_release_detected");
+  } catch (err) {
+    console.error("Failed to get cycle release detection:", err);
+    throw err;
+  }
+}
+
+export async function getCapturePaused(): Promise<boolean> {
+  try {
+    return await invoke("get_capture_paused");
+  } catch (err) {
+    console.error("Failed to get capture state:", err);
+    throw err;
+  }
+}
+
+export async function setCapturePaused(paused: boolean): Promise<void> {
+  try {
+    await invoke("set_capture_paused", { paused });
+  } catch (err) {
+    console.error("Failed to set capture state:", err);
+    throw err;
+  }
+}
+
+export async function convertColor(value: string): Promise<ColorMetadata> {
+  try {
+    return await invoke("convert_color", { value });
+  } catch (err) {
+    console.error("Failed to convert color:", err);
+    throw err;
+  }
+}
+
+export async function getItemPalette(id: number): Promise<PaletteColor[]> {
+  try {
+    return await invoke("get_item_palette", { id });
+  } catch (err) {
+    console.error("Failed to get item palette:", err);
+    throw err;
+  }
+}
+
+// `maxDistance` is a distance in OKLab, 0.08 by default
+export async function findItemsByColor(
+  value: string,
+  maxDistance?: number,
+): Promise<Item[]> {
+  try {
+    return await invoke("find_items_by_color", { value, maxDistance });
+  } catch (err) {
+    console.error("Failed to find items by color:", err);
+    throw err;
+  }
+}
+
+export async function getItemCodes(id: number): Promise<DecodedCode[]> {
+  try {
+    return await invoke("get_item_codes", { id });
+  } catch (err) {
+    console.error("Failed to get item codes:", err);
+    throw err;
+  }
+}
+
+// copies the text of the `index`th code found in an image item, the first one by default
+export async function copyItemCode(
+  id: number,
+  index?: number,
+): Promise<string> {
+  try {
+    return await invoke("copy_item_code", { id, index });
+  } catch (err) {
+    console.error("Failed to copy item code:", err);
+    throw err;
+  }
+}
+
+// `size` is the PNG width in pixels, 512 by default
+export async function getItemQr(
+  id: number,
+  level?: QrLevel,
+  size?: number,
+): Promise<RenderedQr> {
+  try {
+    return await invoke("get_item_qr", { id, level, size });
+  } catch (err) {
+    console.error("Failed to get item QR code:", err);
+    throw err;
+  }
+}
+
+export async function getOcrAvailable(): Promise<boolean> {
+  try {
+    return await invoke("get_ocr_available");
+  } catch (err) {
+    console.error("Failed to get OCR availability:", err);
+    throw err;
+  }
+}
@@ -0,0 +1,13 @@
+interface ColorSwatchProps {
+  color: string;
+}
+
+const ColorSwatch: React.FC<ColorSwatchProps> = ({ color }) => (
+  <span
+    aria-hidden="true"
+    className="h-4 w-4 shrink-0 rounded-sm border border-neutral-black/10 dark:border-neutral-white/10"
+    style={{ backgroundColor: color }}
+  />
+);
+
+export default ColorSwatch;
@@ -1,58 +1,21 @@
-import { useEffect, useState } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { useState } from "react";
+import { thumbnailUrl } from "@yzzo/api/tauriApi";
 
 interface ImagePreviewProps {
-  filePath: string;
+  itemId: number;
   alt?: string;
 }
 
-// Simple in-memory cache for loaded images
-const imageCache = new Map<string, string>();
+// the 64px preview loads the smallest thumbnail, sharp on HiDPI screens
+const THUMBNAIL_SIZE = 128;
 
 const ImagePreview: React.FC<ImagePreviewProps> = ({
-  filePath,
+  itemId,
   alt = "Image preview",
 }) => {
-  const [base64Image, setBase64Image] = useState<string>("");
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState(false);
 
-  useEffect(() => {
-    const loadImage = async () => {
-      try {
-        setLoading(true);
-        setError(false);
-
-        const cached = imageCache.get(filePath);
-        if (cached) {
-          setBase64Image(cached);
-          setLoading(false);
-          return;
-        }
-
-        const result = await invoke<string>("get_image_base64", { filePath });
-
-        imageCache.set(filePath, result);
-        setBase64Image(result);
-      } catch (err) {
-        console.error("Failed to load image:", err);
-        setError(true);
-      } finally {
-        setLoading(false);
-      }
-    };
-
-    loadImage();
-  }, [filePath]);
-
-  if (loading) {
-    return (
-      <div className="flex h-16 w-16 items-center justify-center rounded bg-secondary/20">
-        <span className="text-xs text-secondary">...</span>
-      </div>
-    );
-  }
-
   if (error) {
     return (
       <div className="flex h-16 w-16 items-center justify-center rounded bg-secondary/20">
@@ -62,11 +25,24 @@ const ImagePreview: React.FC<ImagePreviewProps> = ({
   }
 
   return (
-    <img
-      src={base64Image}
-      alt={alt}
-      className="h-16 w-16 rounded object-cover"
-    />
+    <div className="relative h-16 w-16 shrink-0">
+      {loading && (
+        <div className="absolute inset-0 flex items-center justify-center rounded bg-secondary/20">
+          <span className="text-xs text-secondary">...</span>
+        </div>
+      )}
+      <img
+        src={thumbnailUrl(itemId, THUMBNAIL_SIZE)}
+        alt={alt}
+        loading="lazy"
+        onLoad={() => setLoading(false)}
+        onError={() => {
+          console.error("Failed to load image:", itemId);
+          setError(true);
+        }}
+        className="h-16 w-16 rounded object-cover"
+      />
+    </div>
   );
 };
 
@@ -1,2 +1,3 @@
 export { default as HighlightedText } from "./HighlightedText";
 export { default as ImagePreview } from "./ImagePreview";
+export { default as ColorSwatch } from "./ColorSwatch";
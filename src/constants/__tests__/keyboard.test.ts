@@ -0,0 +1,89 @@
+import { describe, expect, test } from "bun:test";
+import {
+  SUPER_LABEL,
+  formatHotkey,
+  keyLabel,
+  parseKeyCode,
+} from "@yzzo/constants";
+
+const none = { ctrlKey: false, altKey: false, shiftKey: false, metaKey: false };
+
+describe("parseKeyCode", () => {
+  test("should shorten letters and digits", () => {
+    expect(parseKeyCode("KeyV")).toBe("V");
+    expect(parseKeyCode("Digit7")).toBe("7");
+  });
+
+  test("should write punctuation as typed", () => {
+    expect(parseKeyCode("Backquote")).toBe("`");
+    expect(parseKeyCode("Backslash")).toBe("\\");
+    expect(parseKeyCode("Period")).toBe(".");
+  });
+
+  test("should keep the code name of other keys", () => {
+    expect(parseKeyCode("F12")).toBe("F12");
+    expect(parseKeyCode("ArrowUp")).toBe("ArrowUp");
+    expect(parseKeyCode("NumpadAdd")).toBe("NumpadAdd");
+    expect(parseKeyCode("Space")).toBe("Space");
+  });
+});
+
+describe("keyLabel", () => {
+  test("should use the symbol the layout puts on the key", () => {
+    const azerty = new Map([
+      ["KeyQ", "a"],
+      ["Digit2", "é"],
+      ["Minus", "ß"],
+    ]);
+    expect(keyLabel("KeyQ", azerty)).toBe("A");
+    expect(keyLabel("Digit2", azerty)).toBe("É");
+    expect(keyLabel("Minus", azerty)).toBe("ß");
+  });
+
+  test("should fall back to the code for named and ambiguous keys", () => {
+    const layout = new Map([
+      ["Space", " "],
+      ["Numpad7", "7"],
+      ["Equal", "+"],
+    ]);
+    expect(keyLabel("Space", layout)).toBe("Space");
+    expect(keyLabel("F5", layout)).toBe("F5");
+    expect(keyLabel("Numpad7", layout)).toBe("Numpad7");
+    expect(keyLabel("Equal", layout)).toBe("=");
+    expect(keyLabel("BracketLeft")).toBe("[");
+    expect(keyLabel("KeyV")).toBe("V");
+  });
+});
+
+describe("formatHotkey", () => {
+  test("should order modifiers like the backend", () => {
+    expect(
+      formatHotkey(
+        { ctrlKey: true, altKey: true, shiftKey: true, metaKey: false },
+        "KeyV",
+      ),
+    ).toBe("Ctrl+Alt+Shift+V");
+    expect(formatHotkey({ ...none, metaKey: true }, "Space")).toBe(
+      `${SUPER_LABEL}+Space`,
+    );
+  });
+
+  test("should name the key, not what the modifiers made it type", () => {
+    const qwerty = new Map([
+      ["Digit1", "1"],
+      ["KeyQ", "q"],
+    ]);
+    // Shift+1 types "!", Option+Q types "Œ" on macOS
+    expect(formatHotkey({ ...none, shiftKey: true }, "Digit1", qwerty)).toBe(
+      "Shift+1",
+    );
+    expect(formatHotkey({ ...none, altKey: true }, "KeyQ", qwerty)).toBe(
+      "Alt+Q",
+    );
+    expect(formatHotkey({ ...none, altKey: true }, "KeyQ")).toBe("Alt+Q");
+  });
+
+  test("should end in ... while only modifiers are held", () => {
+    expect(formatHotkey({ ...none, ctrlKey: true })).toBe("Ctrl+...");
+  });
+});
@@ -1,18 +1,18 @@
 export const MODIFIER_KEYS = ["Control", "Alt", "Shift", "Meta"] as const;
 
 export const KEY_CODE_MAP: Record<string, string> = {
-  // Special characters
-  Backquote: "Backquote",
-  Minus: "Minus",
-  Equal: "Equal",
-  BracketLeft: "BracketLeft",
-  BracketRight: "BracketRight",
-  Backslash: "Backslash",
-  Semicolon: "Semicolon",
-  Quote: "Quote",
-  Comma: "Comma",
-  Period: "Period",
-  Slash: "Slash",
+  // Special characters, written as they are typed
+  Backquote: "`",
+  Minus: "-",
+  Equal: "=",
+  BracketLeft: "[",
+  BracketRight: "]",
+  Backslash: "\\",
+  Semicolon: ";",
+  Quote: "'",
+  Comma: ",",
+  Period: ".",
+  Slash: "/",
 
   // Control keys
   Space: "Space",
@@ -50,15 +50,73 @@ export function parseKeyCode(code: string): string {
     return code.replace("Digit", "");
   }
 
-  // Handle function keys (F1-F12)
-  if (/^F([1-9]|1[0-2])$/.test(code)) {
-    return code;
+  // function keys, numpad keys and the rest keep their code name
+  return code;
+}
+
+export const SUPER_LABEL =
+  typeof navigator !== "undefined" && navigator.userAgent.includes("Mac")
+    ? "Cmd"
+    : "Super";
+
+export interface HotkeyModifiers {
+  ctrlKey: boolean;
+  altKey: boolean;
+  shiftKey: boolean;
+  metaKey: boolean;
+}
+
+// The unmodified symbol of each key on the active layout, by code. Only Chromium based webviews
+// have `navigator.keyboard`; elsewhere keys keep their US QWERTY names, which the backend
+// relabels on save where it can read the layout.
+export type KeyboardLayoutMap = ReadonlyMap<string, string>;
+
+export async function getKeyboardLayoutMap(): Promise<
+  KeyboardLayoutMap | undefined
+> {
+  const keyboard = (
+    navigator as Navigator & {
+      keyboard?: { getLayoutMap(): Promise<KeyboardLayoutMap> };
+    }
+  ).keyboard;
+  try {
+    return await keyboard?.getLayoutMap();
+  } catch {
+    return undefined;
   }
+}
 
-  // Handle numpad keys
-  if (code.startsWith("Numpad")) {
-    return code;
+// What a key is called in a hotkey: the symbol the layout puts on it, or its code name for
+// named keys and the numpad, where the symbol is ambiguous. `event.key` is not used, it
+// carries the modifiers, so Shift+1 would read "!" and Option+Q on macOS "Œ".
+export function keyLabel(code: string, layout?: KeyboardLayoutMap): string {
+  const symbol = layout?.get(code);
+  if (
+    symbol &&
+    [...symbol].length === 1 &&
+    symbol.trim() &&
+    symbol !== "+" &&
+    !code.startsWith("Numpad")
+  ) {
+    const upper = symbol.toUpperCase();
+    return [...upper].length === 1 ? upper : symbol;
   }
+  return parseKeyCode(code);
+}
 
-  return code;
+// The canonical spelling of a hotkey, the same as `format_hotkey` in the backend. Without a
+// key, while only modifiers are held, it ends in "+...".
+export function formatHotkey(
+  modifiers: HotkeyModifiers,
+  code?: string,
+  layout?: KeyboardLayoutMap,
+): string {
+  const parts: string[] = [];
+  if (modifiers.ctrlKey) parts.push("Ctrl");
+  if (modifiers.altKey) parts.push("Alt");
+  if (modifiers.shiftKey) parts.push("Shift");
+  if (modifiers.metaKey) parts.push(SUPER_LABEL);
+
+  parts.push(code ? keyLabel(code, layout) : "...");
+  return parts.join("+");
 }
@@ -152,6 +152,66 @@ describe("useClipboardEventWatcher", () => {
     });
   });
 
+  describe("files clipboard events", () => {
+    test("should call addItem with files type and metadata when files event is received", async () => {
+      const { result } = renderHook(() => useClipboardEventWatcher());
+
+      await waitFor(() => {
+        expect(eventListeners.has("clipboard-changed")).toBe(true);
+      });
+
+      const callback = eventListeners.get("clipboard-changed")!;
+
+      await act(async () => {
+        callback({
+          payload: {
+            type: "files",
+            content: "/home/me/a.pdf\n/home/me/photos",
+            metadata: '{"files":[]}',
+          },
+        });
+      });
+
+      expect(result.current).toBe("files:/home/me/a.pdf\n/home/me/photos");
+      expect(mockAddItem).toHaveBeenCalledWith(
+        "/home/me/a.pdf\n/home/me/photos",
+        "files",
+        undefined,
+        '{"files":[]}',
+      );
+    });
+  });
+
+  describe("color clipboard events", () => {
+    test("should call addItem with color type and metadata when color event is received", async () => {
+      const { result } = renderHook(() => useClipboardEventWatcher());
+
+      await waitFor(() => {
+        expect(eventListeners.has("clipboard-changed")).toBe(true);
+      });
+
+      const callback = eventListeners.get("clipboard-changed")!;
+
+      await act(async () => {
+        callback({
+          payload: {
+            type: "color",
+            content: "#ff8000",
+            metadata: '{"hex":"#ff8000"}',
+          },
+        });
+      });
+
+      expect(result.current).toBe("#ff8000");
+      expect(mockAddItem).toHaveBeenCalledWith(
+        "#ff8000",
+        "color",
+        undefined,
+        '{"hex":"#ff8000"}',
+      );
+    });
+  });
+
   test("should cleanup listener on unmount", async () => {
     const { unmount } = renderHook(() => useClipboardEventWatcher());
 
@@ -1,6 +1,6 @@
 import { describe, test, expect, beforeEach, mock } from "bun:test";
 import { act, renderHook, waitFor } from "@testing-library/react";
-import { useGlobalHotkey } from "../useGlobalHotkey";
+import { DEFAULT_HOLD_THRESHOLD, useGlobalHotkey } from "../useGlobalHotkey";
 import type { InvokeArgs } from "@tauri-apps/api/core";
 
 const mockInvoke = mock(
@@ -20,7 +20,8 @@ describe("useGlobalHotkey", () => {
   test("loads hotkey on mount", async () => {
     mockInvoke.mockImplementation((cmd: string): Promise<any> => {
       if (cmd === "get_hotkey") return Promise.resolve("Ctrl+A");
-      if (cmd === "get_hold_behavior") return Promise.resolve(false);
+      if (cmd === "get_hotkey_behavior") return Promise.resolve("hybrid");
+      if (cmd === "get_hold_threshold") return Promise.resolve(500);
       return Promise.resolve(null);
     });
 
@@ -31,11 +32,15 @@ describe("useGlobalHotkey", () => {
     });
 
     expect(result.current.hotkey).toBe("Ctrl+A");
-    expect(result.current.holdBehavior).toBe(false);
+    expect(result.current.behavior).toBe("hybrid");
+    expect(result.current.holdThreshold).toBe(500);
   });
 
   test("updates hotkey", async () => {
-    mockInvoke.mockResolvedValue(undefined);
+    mockInvoke.mockImplementation((cmd: string): Promise<any> => {
+      if (cmd === "set_hotkey") return Promise.resolve("Ctrl+A");
+      return Promise.resolve(undefined);
+    });
 
     const { result } = renderHook(() => useGlobalHotkey());
 
@@ -44,20 +49,47 @@ describe("useGlobalHotkey", () => {
     });
 
     await act(async () => {
-      await result.current.updateHotkey("Cmd+B");
+      await result.current.updateHotkey("Ctrl+A", "KeyQ");
     });
 
     expect(mockInvoke).toHaveBeenLastCalledWith(
       "set_hotkey",
       {
-        hotkey: "Cmd+B",
+        hotkey: "Ctrl+A",
+        code: "KeyQ",
+      },
+      undefined,
+    );
+    // named as the backend saved it
+    expect(result.current.hotkey).toBe("Ctrl+A");
+  });
+
+  test("updates behavior with one call", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    const { result } = renderHook(() => useGlobalHotkey());
+
+    await waitFor(() => {
+      expect(result.current.isLoading).toBe(false);
+    });
+    mockInvoke.mockClear();
+
+    await act(async () => {
+      await result.current.updateBehavior("cycle");
+    });
+
+    expect(mockInvoke).toHaveBeenCalledTimes(1);
+    expect(mockInvoke).toHaveBeenLastCalledWith(
+      "set_hotkey_behavior",
+      {
+        behavior: "cycle",
       },
       undefined,
     );
-    expect(result.current.hotkey).toBe("Cmd+B");
+    expect(result.current.behavior).toBe("cycle");
   });
 
-  test("updates hold behavior", async () => {
+  test("updates hold threshold", async () => {
     mockInvoke.mockResolvedValue(undefined);
 
     const { result } = renderHook(() => useGlobalHotkey());
@@ -67,17 +99,17 @@ describe("useGlobalHotkey", () => {
     });
 
     await act(async () => {
-      await result.current.updateHoldBehavior(true);
+      await result.current.updateHoldThreshold(500);
     });
 
     expect(mockInvoke).toHaveBeenLastCalledWith(
-      "set_hold_behavior",
+      "set_hold_threshold",
       {
-        holdBehavior: true,
+        thresholdMs: 500,
       },
       undefined,
     );
-    expect(result.current.holdBehavior).toBe(true);
+    expect(result.current.holdThreshold).toBe(500);
   });
 
   test("handles error when loading fails", async () => {
@@ -93,7 +125,8 @@ describe("useGlobalHotkey", () => {
     });
 
     expect(result.current.hotkey).toBe("Alt+`");
-    expect(result.current.holdBehavior).toBe(false);
+    expect(result.current.behavior).toBe("toggle");
+    expect(result.current.holdThreshold).toBe(DEFAULT_HOLD_THRESHOLD);
 
     expect(console.error).toHaveBeenCalledWith(
       "Failed to load hotkey settings:",
@@ -21,9 +21,15 @@ export function useClipboardEventWatcher() {
         if (data.type === "text") {
           setClipboardText(data.content);
           await addItem(data.content, "text");
+        } else if (data.type === "color") {
+          setClipboardText(data.content);
+          await addItem(data.content, "color", undefined, data.metadata);
         } else if (data.type === "image") {
           setClipboardText(`image:${data.content}`); // Trigger re-fetch
           await addItem(data.content, "image", data.file_path, data.metadata);
+        } else if (data.type === "files") {
+          setClipboardText(`files:${data.content}`);
+          await addItem(data.content, "files", undefined, data.metadata);
         }
       },
     );
@@ -1,29 +1,41 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import type { HotkeyBehavior } from "@yzzo/types";
 
 export interface HotkeySettings {
   hotkey: string;
-  holdBehavior: boolean;
+  behavior: HotkeyBehavior;
+  holdThreshold: number;
 }
 
+// mirrors `DEFAULT_HOLD_THRESHOLD_MS` on the backend
+export const DEFAULT_HOLD_THRESHOLD = 300;
+
 export function useGlobalHotkey() {
   const [hotkey, setHotkey] = useState<string>("");
-  const [holdBehavior, setHoldBehavior] = useState<boolean>(false);
+  const [behavior, setBehavior] = useState<HotkeyBehavior>("toggle");
+  const [holdThreshold, setHoldThreshold] = useState<number>(
+    DEFAULT_HOLD_THRESHOLD,
+  );
   const [isLoading, setIsLoading] = useState(true);
 
   useEffect(() => {
     const fetchSettings = async () => {
       try {
-        const [savedHotkey, savedHoldBehavior] = await Promise.all([
-          invoke<string>("get_hotkey"),
-          invoke<boolean>("get_hold_behavior"),
-        ]);
+        const [savedHotkey, savedBehavior, savedHoldThreshold] =
+          await Promise.all([
+            invoke<string>("get_hotkey"),
+            invoke<HotkeyBehavior>("get_hotkey_behavior"),
+            invoke<number>("get_hold_threshold"),
+          ]);
         setHotkey(savedHotkey || "Alt+`");
-        setHoldBehavior(savedHoldBehavior);
+        setBehavior(savedBehavior || "toggle");
+        setHoldThreshold(savedHoldThreshold || DEFAULT_HOLD_THRESHOLD);
       } catch (error) {
         console.error("Failed to load hotkey settings:", error);
         setHotkey("Alt+`");
-        setHoldBehavior(false);
+        setBehavior("toggle");
+        setHoldThreshold(DEFAULT_HOLD_THRESHOLD);
       } finally {
         setIsLoading(false);
       }
@@ -31,31 +43,49 @@ export function useGlobalHotkey() {
     fetchSettings();
   }, []);
 
-  const updateHotkey = async (newHotkey: string) => {
+  // `code` is the physical key the hotkey was recorded on, the backend names it after the
+  // active layout
+  const updateHotkey = async (newHotkey: string, code?: string) => {
     try {
-      await invoke("set_hotkey", { hotkey: newHotkey });
-      setHotkey(newHotkey);
+      const saved = await invoke<string>("set_hotkey", {
+        hotkey: newHotkey,
+        code,
+      });
+      setHotkey(saved);
     } catch (error) {
       console.error("Failed to save hotkey:", error);
       throw error;
     }
   };
 
-  const updateHoldBehavior = async (enabled: boolean) => {
+  const updateBehavior = async (newBehavior: HotkeyBehavior) => {
+    try {
+      await invoke("set_hotkey_behavior", { behavior: newBehavior });
+      setBehavior(newBehavior);
+    } catch (error) {
+      console.error("Failed to update hotkey behavior:", error);
+      throw error;
+    }
+  };
+
+  // milliseconds the hotkey is held before hybrid mode treats it as holding
+  const updateHoldThreshold = async (thresholdMs: number) => {
     try {
-      await invoke("set_hold_behavior", { holdBehavior: enabled });
-      setHoldBehavior(enabled);
+      await invoke("set_hold_threshold", { thresholdMs });
+      setHoldThreshold(thresholdMs);
     } catch (error) {
-      console.error("Failed to update hold behavior:", error);
+      console.error("Failed to update hold threshold:", error);
       throw error;
     }
   };
 
   return {
     hotkey,
-    holdBehavior,
+    behavior,
+    holdThreshold,
     isLoading,
     updateHotkey,
-    updateHoldBehavior,
+    updateBehavior,
+    updateHoldThreshold,
   };
 }
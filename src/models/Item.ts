@@ -4,6 +4,18 @@ export interface Item {
   item_type: string;
   file_path?: string;
   metadata?: string;
+  preferred_format?: string;
+  perceptual_hash?: number;
+  ocr_text?: string;
+  stale?: boolean;
+  thumbnails?: Thumbnail[];
+  use_count?: number;
+  last_used_at?: string;
+}
+
+export interface Thumbnail {
+  size: number;
+  path: string;
 }
 
 export interface ImageMetadata {
@@ -11,4 +23,65 @@ export interface ImageMetadata {
   height: number;
   format: string;
   size: number;
+  mime_type?: string;
+  source_path?: string;
+  animated?: boolean;
+  // an SVG, previewed from a rendering
+  vector?: boolean;
+  exif?: EmbeddedMetadata;
+  palette?: PaletteColor[];
+  codes?: DecodedCode[];
+}
+
+export interface PaletteColor {
+  hex: string;
+  proportion: number;
+}
+
+export interface DecodedCode {
+  format: "qr_code" | "ean13" | "upc_a";
+  text: string;
+  kind: "text" | "url" | "wifi" | "otp";
+}
+
+export type QrLevel = "L" | "M" | "Q" | "H";
+
+export interface RenderedQr {
+  svg: string;
+  png: string;
+  level: QrLevel;
+  version: number;
+  modules: number;
+  size: number;
+}
+
+export interface ColorMetadata {
+  notation: "hex" | "rgb" | "hsl" | "oklch";
+  hex: string;
+  rgb: string;
+  hsl: string;
+  oklch: string;
+  alpha: number;
+  in_gamut: boolean;
+}
+
+export interface EmbeddedMetadata {
+  camera_make: string | null;
+  camera_model: string | null;
+  taken_at: string | null;
+  orientation: number;
+  has_gps: boolean;
+  has_xmp: boolean;
+}
+
+export interface FileEntry {
+  path: string;
+  name: string;
+  is_dir: boolean;
+  size?: number;
+  modified?: number;
+}
+
+export interface FilesMetadata {
+  files: FileEntry[];
 }
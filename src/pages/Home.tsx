@@ -1,11 +1,17 @@
-import { getItems, bumpItem, writeImageToClipboard } from "@yzzo/api/tauriApi";
+import {
+  getItems,
+  copyItem,
+  pasteItem,
+  getAutoPaste,
+  type AutoPasteOptions,
+} from "@yzzo/api/tauriApi";
 import { Header, HighlightedText, Input } from "@yzzo/components";
 import ImagePreview from "@yzzo/components/home/ImagePreview";
+import ColorSwatch from "@yzzo/components/home/ColorSwatch";
 import { Item } from "@yzzo/models/Item";
 import { BORDER_BOTTOM } from "@yzzo/styles/constants";
 import { useEffect, useState, useRef } from "react";
 import { useTranslation } from "react-i18next";
-import { writeText } from "@tauri-apps/plugin-clipboard-manager";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { listen } from "@tauri-apps/api/event";
 
@@ -29,23 +35,32 @@ const Home = () => {
   const selectedItemRef = useRef<HTMLLIElement>(null);
   const [refreshTrigger, setRefreshTrigger] = useState(0);
   const [hasLoaded, setHasLoaded] = useState(false);
+  // the backend hides the window itself when it pastes
+  const autoPasteRef = useRef(false);
 
   const filteredItems = items.filter((item) => {
     if (searchQuery === "") {
       return true; // show all items when no search query
     }
 
-    // Search both text and image items by their content (filename for images)
-    return item.content.toLowerCase().includes(searchQuery.toLowerCase());
+    // Search both text and image items by their content (filename for images),
+    // and images by the text recognised in them
+    const query = searchQuery.toLowerCase();
+    return (
+      item.content.toLowerCase().includes(query) ||
+      (item.ocr_text?.toLowerCase().includes(query) ?? false)
+    );
   });
 
   // Keep refs updated for use in event listeners
+  const itemsRef = useRef(items);
   const filteredItemsRef = useRef(filteredItems);
   const selectedIndexRef = useRef(selectedIndex);
   useEffect(() => {
+    itemsRef.current = items;
     filteredItemsRef.current = filteredItems;
     selectedIndexRef.current = selectedIndex;
-  }, [filteredItems, selectedIndex]);
+  }, [items, filteredItems, selectedIndex]);
 
   // Copy selected item to clipboard and reset state
   const copySelectedItem = async () => {
@@ -59,12 +74,18 @@ const Home = () => {
     const selectedItem = currentFilteredItems[currentSelectedIndex];
     if (!selectedItem) return;
 
-    await bumpItem(selectedItem.id);
+    await pickItem(selectedItem);
+  };
 
-    if (selectedItem.item_type === "image" && selectedItem.file_path) {
-      await writeImageToClipboard(selectedItem.file_path);
+  const pickItem = async (item: Item) => {
+    if (autoPasteRef.current) {
+      try {
+        await pasteItem(item.id);
+      } catch (error) {
+        console.error("Failed to paste item:", error);
+      }
     } else {
-      await writeText(selectedItem.content);
+      await copyItem(item.id);
     }
 
     setSearchQuery("");
@@ -102,6 +123,7 @@ const Home = () => {
       ) {
         e.preventDefault();
         await copySelectedItem();
+        if (autoPasteRef.current) return;
         try {
           await hideWindow();
         } catch (error) {
@@ -142,6 +164,26 @@ const Home = () => {
     })();
   }, []);
 
+  // Preferences saves the options while this page stays mounted
+  useEffect(() => {
+    getAutoPaste()
+      .then((options) => {
+        autoPasteRef.current = options.enabled;
+      })
+      .catch(() => {});
+
+    const unlisten = listen<AutoPasteOptions>(
+      "auto-paste-changed",
+      (event) => {
+        autoPasteRef.current = event.payload.enabled;
+      },
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
   // listen for clipboard changes to refresh items list
   useEffect(() => {
     const unlisten = listen("clipboard-changed", () => {
@@ -153,6 +195,17 @@ const Home = () => {
     };
   }, []);
 
+  // OCR finishes after the item is added
+  useEffect(() => {
+    const unlisten = listen("item-updated", () => {
+      setRefreshTrigger((prev) => prev + 1);
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
   // listen for hotkey release (hold-to-show mode) to copy selected item
   useEffect(() => {
     const unlisten = listen("hotkey-released", () => {
@@ -164,6 +217,33 @@ const Home = () => {
     };
   }, []);
 
+  // cycle mode: the selection is driven by the hotkey, the backend copies on release
+  useEffect(() => {
+    const unlisten = listen<{ id: number }>("cycle-selected", (event) => {
+      const index = itemsRef.current.findIndex(
+        (item) => item.id === event.payload.id,
+      );
+      setSearchQuery("");
+      setSelectedIndex(index === -1 ? null : index);
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  // the open-search hotkey shows the window with a fresh search
+  useEffect(() => {
+    const unlisten = listen("open-search", () => {
+      setSearchQuery("");
+      searchInputRef.current?.focus();
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
   // refresh items when clipboard changes
   useEffect(() => {
     if (refreshTrigger > 0) {
@@ -210,16 +290,8 @@ const Home = () => {
                 ref={idx === selectedIndex ? selectedItemRef : null}
                 onClick={() => setSelectedIndex(idx)}
                 onDoubleClick={async () => {
-                  await bumpItem(item.id);
-
-                  if (item.item_type === "image" && item.file_path) {
-                    await writeImageToClipboard(item.file_path);
-                  } else {
-                    await writeText(item.content);
-                  }
-
-                  setSearchQuery("");
-                  searchInputRef.current?.blur();
+                  await pickItem(item);
+                  if (autoPasteRef.current) return;
 
                   try {
                     await hideWindow();
@@ -235,7 +307,7 @@ const Home = () => {
               >
                 {item.item_type === "image" && item.file_path ? (
                   <div className="flex items-center gap-3">
-                    <ImagePreview filePath={item.file_path} />
+                    <ImagePreview itemId={item.id} />
                     <span
                       className="text-xs text-gray-500 dark:text-gray-500 line-clamp-1"
                       style={{ whiteSpace: "nowrap" }}
@@ -246,8 +318,15 @@ const Home = () => {
                       />
                     </span>
                   </div>
+                ) : item.item_type === "color" ? (
+                  <div className="flex items-center gap-3">
+                    <ColorSwatch color={item.content} />
+                    <HighlightedText text={item.content} query={searchQuery} />
+                  </div>
                 ) : (
-                  <div className="line-clamp-10">
+                  <div
+                    className={`line-clamp-10 ${item.stale ? "opacity-50 line-through" : ""}`}
+                  >
                     <HighlightedText text={item.content} query={searchQuery} />
                   </div>
                 )}
@@ -10,19 +10,18 @@ import Home from "@yzzo/pages/Home";
 import { Item } from "@yzzo/models/Item";
 
 const mockGetItems = mock(() => Promise.resolve<Item[]>([]));
-const mockBumpItem = mock(() => Promise.resolve());
-const mockWriteText = mock(() => Promise.resolve());
 const mockMinimize = mock(() => Promise.resolve());
-const mockWriteImageToClipboard = mock(() => Promise.resolve());
+const mockCopyItem = mock(() => Promise.resolve());
+const mockPasteItem = mock(() => Promise.resolve());
+const mockGetAutoPaste = mock(() =>
+  Promise.resolve({ enabled: false, shortcut: "Ctrl+V", targets: [] }),
+);
 
 mock.module("@yzzo/api/tauriApi", () => ({
   getItems: mockGetItems,
-  bumpItem: mockBumpItem,
-  writeImageToClipboard: mockWriteImageToClipboard,
-}));
-
-mock.module("@tauri-apps/plugin-clipboard-manager", () => ({
-  writeText: mockWriteText,
+  copyItem: mockCopyItem,
+  pasteItem: mockPasteItem,
+  getAutoPaste: mockGetAutoPaste,
 }));
 
 mock.module("@tauri-apps/api/window", () => ({
@@ -50,8 +49,8 @@ const emitEvent = (event: string, payload?: any) => {
 };
 
 mock.module("@yzzo/components/home/ImagePreview", () => ({
-  default: ({ filePath }: any) => (
-    <div data-testid="image-preview" data-file-path={filePath}>
+  default: ({ itemId }: any) => (
+    <div data-testid="image-preview" data-item-id={itemId}>
       ImagePreview
     </div>
   ),
@@ -335,8 +334,7 @@ describe("Home page", () => {
     beforeEach(() => {
       setupI18nMock("en");
       mockGetItems.mockClear();
-      mockBumpItem.mockClear();
-      mockWriteText.mockClear();
+      mockCopyItem.mockClear();
       mockMinimize.mockClear();
     });
 
@@ -548,13 +546,12 @@ describe("Home page", () => {
       fireEvent.keyDown(window, { key: "Enter" });
 
       await waitFor(() => {
-        expect(mockBumpItem).not.toHaveBeenCalled();
-        expect(mockWriteText).not.toHaveBeenCalled();
+        expect(mockCopyItem).not.toHaveBeenCalled();
         expect(mockMinimize).not.toHaveBeenCalled();
       });
     });
 
-    test("should copy to clipboard, bump item, and minimize window when Enter is pressed on selected item", async () => {
+    test("should copy the item and minimize the window when Enter is pressed on selected item", async () => {
       const mockItems: Item[] = [
         { id: 1, content: "First item", item_type: "text" },
         { id: 2, content: "Second item", item_type: "text" },
@@ -579,12 +576,67 @@ describe("Home page", () => {
       fireEvent.keyDown(window, { key: "Enter" });
 
       await waitFor(() => {
-        expect(mockBumpItem).toHaveBeenCalledWith(2);
-        expect(mockWriteText).toHaveBeenCalledWith("Second item");
+        expect(mockCopyItem).toHaveBeenCalledWith(2);
         expect(mockMinimize).toHaveBeenCalled();
       });
     });
 
+    test("should paste instead of minimizing when auto-paste is on", async () => {
+      const mockItems: Item[] = [
+        { id: 1, content: "First item", item_type: "text" },
+      ];
+
+      mockGetAutoPaste.mockResolvedValueOnce({
+        enabled: true,
+        shortcut: "Ctrl+V",
+        targets: [],
+      });
+      mockPasteItem.mockClear();
+      mockGetItems.mockResolvedValue(mockItems);
+      const { container } = render(<Home />);
+
+      await waitFor(() => {
+        expect(within(container).getByText("First item")).toBeInTheDocument();
+        expect(mockGetAutoPaste).toHaveBeenCalled();
+      });
+
+      fireEvent.keyDown(window, { key: "ArrowDown" });
+      fireEvent.keyDown(window, { key: "Enter" });
+
+      await waitFor(() => {
+        expect(mockPasteItem).toHaveBeenCalledWith(1);
+      });
+      expect(mockCopyItem).not.toHaveBeenCalled();
+      expect(mockMinimize).not.toHaveBeenCalled();
+    });
+
+    test("should paste once auto-paste is turned on in preferences", async () => {
+      const mockItems: Item[] = [
+        { id: 1, content: "First item", item_type: "text" },
+      ];
+
+      mockPasteItem.mockClear();
+      mockCopyItem.mockClear();
+      mockGetItems.mockResolvedValue(mockItems);
+      const { container } = render(<Home />);
+
+      await waitFor(() => {
+        expect(within(container).getByText("First item")).toBeInTheDocument();
+      });
+
+      emitEvent("auto-paste-changed", {
+        payload: { enabled: true, shortcut: "Ctrl+V", targets: [] },
+      });
+
+      fireEvent.keyDown(window, { key: "ArrowDown" });
+      fireEvent.keyDown(window, { key: "Enter" });
+
+      await waitFor(() => {
+        expect(mockPasteItem).toHaveBeenCalledWith(1);
+      });
+      expect(mockCopyItem).not.toHaveBeenCalled();
+    });
+
     test("should focus search input when typing regular characters", async () => {
       const mockItems: Item[] = [
         { id: 1, content: "First item", item_type: "text" },
@@ -607,6 +659,28 @@ describe("Home page", () => {
       });
     });
 
+    test("should focus an empty search input when the open-search hotkey fires", async () => {
+      const mockItems: Item[] = [
+        { id: 1, content: "First item", item_type: "text" },
+      ];
+
+      mockGetItems.mockResolvedValue(mockItems);
+      const { container } = render(<Home />);
+
+      const searchInput = (await waitFor(() =>
+        within(container).getByRole("textbox"),
+      )) as HTMLInputElement;
+      fireEvent.change(searchInput, { target: { value: "old" } });
+      searchInput.blur();
+
+      emitEvent("open-search");
+
+      await waitFor(() => {
+        expect(document.activeElement).toBe(searchInput);
+        expect(searchInput.value).toBe("");
+      });
+    });
+
     test("should not interfere with typing when search input is already focused", async () => {
       const mockItems: Item[] = [
         { id: 1, content: "First item", item_type: "text" },
@@ -697,8 +771,7 @@ describe("Home page", () => {
     beforeEach(() => {
       setupI18nMock("en");
       mockGetItems.mockClear();
-      mockBumpItem.mockClear();
-      mockWriteText.mockClear();
+      mockCopyItem.mockClear();
       mockMinimize.mockClear();
     });
 
@@ -730,7 +803,7 @@ describe("Home page", () => {
       });
     });
 
-    test("should copy to clipboard, bump item, and minimize window when item is double-clicked", async () => {
+    test("should copy the item and minimize the window when item is double-clicked", async () => {
       const mockItems: Item[] = [
         { id: 1, content: "First item", item_type: "text" },
         { id: 2, content: "Second item", item_type: "text" },
@@ -750,8 +823,7 @@ describe("Home page", () => {
       fireEvent.doubleClick(listItems[1]);
 
       await waitFor(() => {
-        expect(mockBumpItem).toHaveBeenCalledWith(2);
-        expect(mockWriteText).toHaveBeenCalledWith("Second item");
+        expect(mockCopyItem).toHaveBeenCalledWith(2);
         expect(mockMinimize).toHaveBeenCalled();
       });
     });
@@ -795,9 +867,7 @@ describe("Home page", () => {
     beforeEach(() => {
       setupI18nMock("en");
       mockGetItems.mockClear();
-      mockBumpItem.mockClear();
-      mockWriteText.mockClear();
-      mockWriteImageToClipboard.mockClear();
+      mockCopyItem.mockClear();
       mockMinimize.mockClear();
     });
 
@@ -817,10 +887,7 @@ describe("Home page", () => {
       await waitFor(() => {
         const imagePreview = within(container).getByTestId("image-preview");
         expect(imagePreview).toBeInTheDocument();
-        expect(imagePreview).toHaveAttribute(
-          "data-file-path",
-          "/path/to/screenshot.png",
-        );
+        expect(imagePreview).toHaveAttribute("data-item-id", "1");
       });
     });
 
@@ -846,14 +913,8 @@ describe("Home page", () => {
       await waitFor(() => {
         const imagePreviews = within(container).getAllByTestId("image-preview");
         expect(imagePreviews).toHaveLength(2);
-        expect(imagePreviews[0]).toHaveAttribute(
-          "data-file-path",
-          "/path/to/screenshot1.png",
-        );
-        expect(imagePreviews[1]).toHaveAttribute(
-          "data-file-path",
-          "/path/to/screenshot2.png",
-        );
+        expect(imagePreviews[0]).toHaveAttribute("data-item-id", "1");
+        expect(imagePreviews[1]).toHaveAttribute("data-item-id", "2");
       });
     });
 
@@ -890,7 +951,7 @@ describe("Home page", () => {
       });
     });
 
-    test("should call writeImageToClipboard when Enter is pressed on selected image item", async () => {
+    test("should copy image items when Enter is pressed", async () => {
       const mockItems: Item[] = [
         {
           id: 1,
@@ -921,16 +982,12 @@ describe("Home page", () => {
       fireEvent.keyDown(window, { key: "Enter" });
 
       await waitFor(() => {
-        expect(mockWriteImageToClipboard).toHaveBeenCalledWith(
-          "/path/to/screenshot.png",
-        );
-        expect(mockWriteText).not.toHaveBeenCalled();
-        expect(mockBumpItem).toHaveBeenCalledWith(1);
+        expect(mockCopyItem).toHaveBeenCalledWith(1);
         expect(mockMinimize).toHaveBeenCalled();
       });
     });
 
-    test("should call writeImageToClipboard when double-clicking an image item", async () => {
+    test("should copy image items when double-clicked", async () => {
       const mockItems: Item[] = [
         {
           id: 1,
@@ -955,16 +1012,12 @@ describe("Home page", () => {
       fireEvent.doubleClick(listItems[0]);
 
       await waitFor(() => {
-        expect(mockWriteImageToClipboard).toHaveBeenCalledWith(
-          "/path/to/screenshot.png",
-        );
-        expect(mockWriteText).not.toHaveBeenCalled();
-        expect(mockBumpItem).toHaveBeenCalledWith(1);
+        expect(mockCopyItem).toHaveBeenCalledWith(1);
         expect(mockMinimize).toHaveBeenCalled();
       });
     });
 
-    test("should use writeText for text items and writeImageToClipboard for image items", async () => {
+    test("should copy text and image items by id", async () => {
       const mockItems: Item[] = [
         {
           id: 1,
@@ -995,25 +1048,17 @@ describe("Home page", () => {
       fireEvent.doubleClick(listItems[0]);
 
       await waitFor(() => {
-        expect(mockWriteText).toHaveBeenCalledWith("Text item");
-        expect(mockWriteImageToClipboard).not.toHaveBeenCalled();
-        expect(mockBumpItem).toHaveBeenCalledWith(1);
+        expect(mockCopyItem).toHaveBeenCalledWith(1);
       });
 
       // Clear mocks
-      mockWriteText.mockClear();
-      mockWriteImageToClipboard.mockClear();
-      mockBumpItem.mockClear();
+      mockCopyItem.mockClear();
 
       // Test image item
       fireEvent.doubleClick(listItems[1]);
 
       await waitFor(() => {
-        expect(mockWriteImageToClipboard).toHaveBeenCalledWith(
-          "/path/to/image.png",
-        );
-        expect(mockWriteText).not.toHaveBeenCalled();
-        expect(mockBumpItem).toHaveBeenCalledWith(2);
+        expect(mockCopyItem).toHaveBeenCalledWith(2);
       });
     });
 
@@ -1062,10 +1107,7 @@ describe("Home page", () => {
       fireEvent.keyDown(window, { key: "Enter" });
 
       await waitFor(() => {
-        expect(mockWriteImageToClipboard).toHaveBeenCalledWith(
-          "/path/to/image2.png",
-        );
-        expect(mockBumpItem).toHaveBeenCalledWith(2);
+        expect(mockCopyItem).toHaveBeenCalledWith(2);
       });
     });
 
@@ -1105,6 +1147,43 @@ describe("Home page", () => {
       expect(within(container).getByText("cherry.png")).toBeInTheDocument();
     });
 
+    test("should filter image items by recognised text", async () => {
+      const mockItems: Item[] = [
+        {
+          id: 1,
+          content: "screenshot.png",
+          item_type: "image",
+          file_path: "/path/to/screenshot.png",
+          ocr_text: "Error: permission denied",
+        },
+        {
+          id: 2,
+          content: "photo.png",
+          item_type: "image",
+          file_path: "/path/to/photo.png",
+        },
+      ];
+
+      mockGetItems.mockResolvedValue(mockItems);
+      const { container } = render(<Home />);
+
+      await waitFor(() => {
+        expect(within(container).getAllByTestId("image-preview")).toHaveLength(
+          2,
+        );
+      });
+
+      const searchInput = within(container).getByRole(
+        "textbox",
+      ) as HTMLInputElement;
+      fireEvent.change(searchInput, { target: { value: "permission" } });
+
+      await waitFor(() => {
+        expect(within(container).getByText("screenshot.png")).toBeInTheDocument();
+        expect(within(container).queryByText("photo.png")).not.toBeInTheDocument();
+      });
+    });
+
     test("should handle image items without file_path gracefully", async () => {
       const mockItems: Item[] = [
         {
@@ -1129,14 +1208,12 @@ describe("Home page", () => {
         ).not.toBeInTheDocument();
       });
 
-      // Double click should not call writeImageToClipboard
+      // Double click still copies it, the backend falls back to the text
       const listItems = within(container).getAllByRole("listitem");
       fireEvent.doubleClick(listItems[0]);
 
       await waitFor(() => {
-        expect(mockWriteImageToClipboard).not.toHaveBeenCalled();
-        expect(mockWriteText).toHaveBeenCalledWith("broken-image.png");
-        expect(mockBumpItem).toHaveBeenCalledWith(1);
+        expect(mockCopyItem).toHaveBeenCalledWith(1);
       });
     });
 
@@ -1216,25 +1293,17 @@ describe("Home page", () => {
       fireEvent.doubleClick(listItems[0]);
 
       await waitFor(() => {
-        expect(mockWriteText).toHaveBeenCalledWith("Text item 1");
-        expect(mockWriteImageToClipboard).not.toHaveBeenCalled();
-        expect(mockBumpItem).toHaveBeenCalledWith(1);
+        expect(mockCopyItem).toHaveBeenCalledWith(1);
       });
 
       // Clear mocks
-      mockWriteText.mockClear();
-      mockWriteImageToClipboard.mockClear();
-      mockBumpItem.mockClear();
+      mockCopyItem.mockClear();
 
       // Test double-clicking image item
       fireEvent.doubleClick(listItems[1]);
 
       await waitFor(() => {
-        expect(mockWriteImageToClipboard).toHaveBeenCalledWith(
-          "/path/to/image1.png",
-        );
-        expect(mockWriteText).not.toHaveBeenCalled();
-        expect(mockBumpItem).toHaveBeenCalledWith(2);
+        expect(mockCopyItem).toHaveBeenCalledWith(2);
       });
     });
   });
@@ -1243,9 +1312,7 @@ describe("Home page", () => {
     beforeEach(() => {
       setupI18nMock("en");
       mockGetItems.mockClear();
-      mockBumpItem.mockClear();
-      mockWriteText.mockClear();
-      mockWriteImageToClipboard.mockClear();
+      mockCopyItem.mockClear();
       mockMinimize.mockClear();
       // Clear event listeners
       Object.keys(eventListeners).forEach((key) => delete eventListeners[key]);
@@ -1278,8 +1345,7 @@ describe("Home page", () => {
       emitEvent("hotkey-released");
 
       await waitFor(() => {
-        expect(mockBumpItem).toHaveBeenCalledWith(2);
-        expect(mockWriteText).toHaveBeenCalledWith("Second item");
+        expect(mockCopyItem).toHaveBeenCalledWith(2);
       });
     });
 
@@ -1314,11 +1380,7 @@ describe("Home page", () => {
       emitEvent("hotkey-released");
 
       await waitFor(() => {
-        expect(mockBumpItem).toHaveBeenCalledWith(1);
-        expect(mockWriteImageToClipboard).toHaveBeenCalledWith(
-          "/path/to/screenshot.png",
-        );
-        expect(mockWriteText).not.toHaveBeenCalled();
+        expect(mockCopyItem).toHaveBeenCalledWith(1);
       });
     });
 
@@ -1339,9 +1401,7 @@ describe("Home page", () => {
       emitEvent("hotkey-released");
 
       await waitFor(() => {
-        expect(mockBumpItem).not.toHaveBeenCalled();
-        expect(mockWriteText).not.toHaveBeenCalled();
-        expect(mockWriteImageToClipboard).not.toHaveBeenCalled();
+        expect(mockCopyItem).not.toHaveBeenCalled();
       });
     });
 
@@ -1357,9 +1417,7 @@ describe("Home page", () => {
       emitEvent("hotkey-released");
 
       await waitFor(() => {
-        expect(mockBumpItem).not.toHaveBeenCalled();
-        expect(mockWriteText).not.toHaveBeenCalled();
-        expect(mockWriteImageToClipboard).not.toHaveBeenCalled();
+        expect(mockCopyItem).not.toHaveBeenCalled();
       });
     });
 
@@ -1390,8 +1448,7 @@ describe("Home page", () => {
       emitEvent("hotkey-released");
 
       await waitFor(() => {
-        expect(mockBumpItem).toHaveBeenCalledWith(3);
-        expect(mockWriteText).toHaveBeenCalledWith("Third item");
+        expect(mockCopyItem).toHaveBeenCalledWith(3);
       });
     });
 
@@ -1430,8 +1487,7 @@ describe("Home page", () => {
       emitEvent("hotkey-released");
 
       await waitFor(() => {
-        expect(mockBumpItem).toHaveBeenCalledWith(1);
-        expect(mockWriteText).toHaveBeenCalledWith("Apple");
+        expect(mockCopyItem).toHaveBeenCalledWith(1);
         expect(searchInput.value).toBe("");
       });
     });
@@ -2,51 +2,139 @@ import React, { useState, useEffect } from "react";
 import { useGlobalHotkey } from "@yzzo/hooks";
 import { Button, Header, Input, Label, Radio } from "@yzzo/components";
 import { useTranslation } from "react-i18next";
-import type { HotkeyBehavior } from "@yzzo/types";
+import type {
+  HotkeyAction,
+  HotkeyBehavior,
+  HotkeyBinding,
+  HotkeyError,
+} from "@yzzo/types";
+import {
+  MODIFIER_KEYS,
+  formatHotkey,
+  getKeyboardLayoutMap,
+  type KeyboardLayoutMap,
+} from "@yzzo/constants";
+import { BORDER_BOTTOM } from "@yzzo/styles/constants";
+import {
+  bindHotkey,
+  getCycleReleaseDetected,
+  getHotkeyBindings,
+  unbindHotkey,
+} from "@yzzo/api/tauriApi";
 
 const behaviorOptions: { value: HotkeyBehavior; labelKey: string }[] = [
   { value: "toggle", labelKey: "components.settings.hotkey.behaviorToggle" },
   { value: "hold", labelKey: "components.settings.hotkey.behaviorHold" },
+  { value: "hybrid", labelKey: "components.settings.hotkey.behaviorHybrid" },
+  { value: "cycle", labelKey: "components.settings.hotkey.behaviorCycle" },
 ];
 
+// milliseconds a press lasts before hybrid mode treats it as holding
+const holdThresholdOptions: { value: string; labelKey: string }[] = [
+  { value: "200", labelKey: "components.settings.hotkey.holdThresholdShort" },
+  { value: "300", labelKey: "components.settings.hotkey.holdThresholdMedium" },
+  { value: "500", labelKey: "components.settings.hotkey.holdThresholdLong" },
+];
+
+type ActionType = HotkeyAction["type"];
+
+const actionOptions: { value: ActionType; labelKey: string }[] = [
+  {
+    value: "toggle_window",
+    labelKey: "components.settings.hotkey.actionToggleWindow",
+  },
+  {
+    value: "paste_recent",
+    labelKey: "components.settings.hotkey.actionPasteRecent",
+  },
+  {
+    value: "paste_previous",
+    labelKey: "components.settings.hotkey.actionPastePrevious",
+  },
+  {
+    value: "pause_capture",
+    labelKey: "components.settings.hotkey.actionPauseCapture",
+  },
+  {
+    value: "clear_clipboard",
+    labelKey: "components.settings.hotkey.actionClearClipboard",
+  },
+  {
+    value: "open_search",
+    labelKey: "components.settings.hotkey.actionOpenSearch",
+  },
+];
+
+// a parse error or a taken hotkey is worth its own message, anything else failed to save
+function hotkeyErrorKey(error: HotkeyError): string {
+  switch (error.kind) {
+    case "parse":
+      return "components.settings.hotkey.errorParse";
+    case "conflict":
+      return "components.settings.hotkey.errorConflict";
+    default:
+      return "components.settings.hotkey.errorSave";
+  }
+}
+
+// which hotkey the keys being pressed are recorded for
+type Recording = "main" | "binding";
+
+// the first step of a chord, recorded before the key that follows it
+interface Leader {
+  combination: string;
+  code?: string;
+}
+
 const Hotkeys: React.FC = () => {
-  const { hotkey, holdBehavior, isLoading, updateHotkey, updateHoldBehavior } =
-    useGlobalHotkey();
-  const [isListening, setIsListening] = useState(false);
+  const {
+    hotkey,
+    behavior,
+    holdThreshold,
+    isLoading,
+    updateHotkey,
+    updateBehavior,
+    updateHoldThreshold,
+  } = useGlobalHotkey();
+  const [recording, setRecording] = useState<Recording | null>(null);
   const [currentCombination, setCurrentCombination] = useState<string>("");
+  // the physical key of the combination, registered whatever the layout calls it
+  const [currentCode, setCurrentCode] = useState<string>();
+  const [cycleReleaseDetected, setCycleReleaseDetected] = useState(true);
+  const [bindings, setBindings] = useState<HotkeyBinding[]>([]);
+  const [actionType, setActionType] = useState<ActionType>("paste_recent");
+  const [pasteIndex, setPasteIndex] = useState("1");
+  const [isChord, setIsChord] = useState(false);
+  const [leader, setLeader] = useState<Leader>();
+  const [layoutMap, setLayoutMap] = useState<KeyboardLayoutMap>();
   const { t } = useTranslation();
 
+  useEffect(() => {
+    getCycleReleaseDetected().then(setCycleReleaseDetected).catch(() => {});
+    getHotkeyBindings().then(setBindings).catch(() => {});
+    getKeyboardLayoutMap().then(setLayoutMap);
+  }, []);
+
   const handleKeyDown = (event: KeyboardEvent) => {
-    if (!isListening) return;
+    if (!recording) return;
     event.preventDefault();
 
-    const modifiers: string[] = [];
-    if (event.metaKey) modifiers.push("Cmd");
-    if (event.ctrlKey) modifiers.push("Ctrl");
-    if (event.altKey) modifiers.push("Alt");
-    if (event.shiftKey) modifiers.push("Shift");
-
-    let mainKey = "";
-    if (!["Control", "Alt", "Shift", "Meta"].includes(event.key)) {
-      let key = event.code;
-      if (key === "Backquote") mainKey = "`";
-      else if (key.startsWith("Key")) mainKey = key.replace("Key", "");
-      else if (key.startsWith("Digit")) mainKey = key.replace("Digit", "");
-    }
-
-    const combo = mainKey
-      ? [...modifiers, mainKey].join("+")
-      : modifiers.join("+") + "+...";
+    const isModifierKey = (MODIFIER_KEYS as readonly string[]).includes(
+      event.key,
+    );
+    const code = isModifierKey ? undefined : event.code;
+    const combo = formatHotkey(event, code, layoutMap);
 
     setCurrentCombination(combo);
+    setCurrentCode(code);
   };
 
   const handleKeyUp = (event: KeyboardEvent) => {
-    if (!isListening) return;
+    if (!recording) return;
     event.preventDefault();
 
     // check if a non-modifier key was released (the main key of the combo)
-    const isModifierKey = ["Control", "Alt", "Shift", "Meta"].includes(
+    const isModifierKey = (MODIFIER_KEYS as readonly string[]).includes(
       event.key,
     );
 
@@ -56,18 +144,34 @@ const Hotkeys: React.FC = () => {
       !currentCombination.endsWith("...")
     ) {
       // a main key was released, save the combination
-      const finalCombo = currentCombination.replace("+...", "");
-
-      setIsListening(false);
-      updateHotkey(finalCombo).catch((error) => {
-        alert("Failed to save hotkey: " + error);
-      });
+      if (recording === "main") {
+        setRecording(null);
+        updateHotkey(currentCombination, currentCode).catch(
+          (error: HotkeyError) => {
+            alert(t(hotkeyErrorKey(error), { message: error.message }));
+          },
+        );
+      } else if (isChord && !leader) {
+        // keep recording for the key after the leader
+        setLeader({ combination: currentCombination, code: currentCode });
+      } else {
+        setRecording(null);
+        setLeader(undefined);
+        if (leader) {
+          addBinding(
+            `${leader.combination}, ${currentCombination}`,
+            leader.code && currentCode && `${leader.code}, ${currentCode}`,
+          );
+        } else {
+          addBinding(currentCombination, currentCode);
+        }
+      }
       setCurrentCombination("");
     }
   };
 
   useEffect(() => {
-    if (isListening) {
+    if (recording) {
       window.addEventListener("keydown", handleKeyDown);
       window.addEventListener("keyup", handleKeyUp);
     }
@@ -75,17 +179,71 @@ const Hotkeys: React.FC = () => {
       window.removeEventListener("keydown", handleKeyDown);
       window.removeEventListener("keyup", handleKeyUp);
     };
-  }, [isListening, currentCombination]);
+  }, [recording, currentCombination, leader]);
+
+  const toggleRecording = (target: Recording) => {
+    setCurrentCombination("");
+    setLeader(undefined);
+    setRecording(recording === target ? null : target);
+  };
+
+  const addBinding = async (combination: string, code?: string) => {
+    // the backend rejects an index out of range with its own message
+    const action: HotkeyAction =
+      actionType === "paste_recent"
+        ? { type: "paste_recent", index: Number(pasteIndex) || 0 }
+        : { type: actionType };
+    try {
+      await bindHotkey(combination, action, code);
+      setBindings(await getHotkeyBindings());
+    } catch (error) {
+      const hotkeyError = error as HotkeyError;
+      alert(t(hotkeyErrorKey(hotkeyError), { message: hotkeyError.message }));
+    }
+  };
+
+  const removeBinding = async (binding: HotkeyBinding) => {
+    try {
+      await unbindHotkey(binding.hotkey);
+      setBindings(await getHotkeyBindings());
+    } catch (error) {
+      alert(
+        t("components.settings.hotkey.errorSave", { message: String(error) }),
+      );
+    }
+  };
+
+  const actionLabel = (action: HotkeyAction) =>
+    action.type === "paste_recent"
+      ? t("components.settings.hotkey.actionPasteItem", {
+          index: action.index,
+        })
+      : t(
+          actionOptions.find((option) => option.value === action.type)
+            ?.labelKey ?? action.type,
+        );
+
+  const recordedValue = (target: Recording, value: string) => {
+    if (recording !== target) return value;
+    const combination =
+      currentCombination ||
+      t("components.settings.hotkey.listeningPlaceholder");
+    return leader ? `${leader.combination}, ${combination}` : combination;
+  };
 
-  const handleChangeHotkey = () => {
-    setIsListening(!isListening);
+  const handleBehaviorChange = async (newBehavior: HotkeyBehavior) => {
+    try {
+      await updateBehavior(newBehavior);
+    } catch (error) {
+      alert("Failed to update hotkey behavior: " + error);
+    }
   };
 
-  const handleHoldBehaviorToggle = async (enabled: boolean) => {
+  const handleThresholdChange = async (threshold: string) => {
     try {
-      await updateHoldBehavior(enabled);
+      await updateHoldThreshold(Number(threshold));
     } catch (error) {
-      alert("Failed to update hold behavior: " + error);
+      alert("Failed to update hold threshold: " + error);
     }
   };
 
@@ -100,7 +258,7 @@ const Hotkeys: React.FC = () => {
         previousRoute={"/settings"}
       />
 
-      <div className="flex flex-col gap-4 p-4">
+      <div className={`flex flex-col gap-4 p-4 ${BORDER_BOTTOM}`}>
         <div className="w-full max-w-80">
           <Label
             label={t("components.settings.hotkey.title")}
@@ -109,20 +267,15 @@ const Hotkeys: React.FC = () => {
           <div className="flex flex-row w-full">
             <Input
               readOnly
-              value={
-                isListening
-                  ? currentCombination ||
-                    t("components.settings.hotkey.listeningPlaceholder")
-                  : hotkey
-              }
+              value={recordedValue("main", hotkey)}
               className="flex-1"
               attachedToButton
             />
             <Button
-              variant={isListening ? "danger" : "default"}
-              onClick={handleChangeHotkey}
+              variant={recording === "main" ? "danger" : "default"}
+              onClick={() => toggleRecording("main")}
               label={
-                isListening
+                recording === "main"
                   ? t("components.settings.hotkey.cancel")
                   : t("components.settings.hotkey.change")
               }
@@ -135,10 +288,92 @@ const Hotkeys: React.FC = () => {
         </div>
         <Radio<HotkeyBehavior>
           label={t("components.settings.hotkey.behavior")}
-          selectedValue={holdBehavior ? "hold" : "toggle"}
+          selectedValue={behavior}
           options={behaviorOptions}
-          onChange={(value) => handleHoldBehaviorToggle(value === "hold")}
+          onChange={handleBehaviorChange}
         />
+        {behavior === "cycle" && !cycleReleaseDetected && (
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            {t("components.settings.hotkey.cycleReleaseUndetected")}
+          </p>
+        )}
+        {behavior === "hybrid" && (
+          <Radio<string>
+            label={t("components.settings.hotkey.holdThreshold")}
+            selectedValue={String(holdThreshold)}
+            options={holdThresholdOptions}
+            onChange={handleThresholdChange}
+          />
+        )}
+      </div>
+
+      <div className="flex flex-col gap-4 p-4">
+        <div className="flex flex-col gap-2">
+          <Label label={t("components.settings.hotkey.bindings")} />
+          {bindings.length === 0 && (
+            <p className="text-xs text-gray-500 dark:text-gray-400">
+              {t("components.settings.hotkey.bindingsEmpty")}
+            </p>
+          )}
+          {bindings.map((binding) => (
+            <div key={binding.hotkey} className="flex gap-2 items-center">
+              <span className="flex-1 text-sm text-neutral-800 dark:text-neutral-200">
+                {binding.hotkey}
+              </span>
+              <span className="flex-1 text-sm text-neutral-800 dark:text-neutral-200">
+                {actionLabel(binding.action)}
+              </span>
+              <Button
+                variant="danger"
+                label={t("components.settings.hotkey.bindingRemove")}
+                onClick={() => removeBinding(binding)}
+              />
+            </div>
+          ))}
+        </div>
+        <Radio<ActionType>
+          label={t("components.settings.hotkey.bindingAction")}
+          selectedValue={actionType}
+          options={actionOptions}
+          onChange={setActionType}
+        />
+        {actionType === "paste_recent" && (
+          <Input
+            label={t("components.settings.hotkey.bindingIndex")}
+            value={pasteIndex}
+            onChange={(e) => setPasteIndex(e.target.value)}
+            className="max-w-80"
+          />
+        )}
+        <label className="flex items-center gap-2 cursor-pointer">
+          <input
+            type="checkbox"
+            checked={isChord}
+            onChange={(e) => setIsChord(e.target.checked)}
+            className="w-4 h-4 accent-secondary cursor-pointer"
+          />
+          <span className="text-sm text-neutral-800 dark:text-neutral-200">
+            {t("components.settings.hotkey.bindingChord")}
+          </span>
+        </label>
+        <div className="flex flex-row w-full max-w-80">
+          <Input
+            readOnly
+            value={recordedValue("binding", "")}
+            className="flex-1"
+            attachedToButton
+          />
+          <Button
+            variant={recording === "binding" ? "danger" : "default"}
+            onClick={() => toggleRecording("binding")}
+            label={
+              recording === "binding"
+                ? t("components.settings.hotkey.cancel")
+                : t("components.settings.hotkey.bindingAdd")
+            }
+            attachedToInput
+          />
+        </div>
       </div>
     </div>
   );
@@ -1,9 +1,22 @@
-import React from "react";
-import { Header, Radio, SettingsItem } from "@yzzo/components";
+import React, { useEffect, useState } from "react";
+import {
+  Button,
+  Header,
+  Input,
+  Radio,
+  SettingsItem,
+} from "@yzzo/components";
 import { useTranslation } from "react-i18next";
 import { useTheme, useLanguage } from "@yzzo/contexts";
 import type { Theme } from "@yzzo/types";
 import { BORDER_BOTTOM } from "@yzzo/styles/constants";
+import {
+  getAutoPaste,
+  getAutoPasteAvailable,
+  setAutoPaste,
+  type AutoPasteOptions,
+  type PasteTarget,
+} from "@yzzo/api/tauriApi";
 
 const themeOptions: { value: Theme; labelKey: string }[] = [
   { value: "auto", labelKey: "components.settings.preferences.themeAuto" },
@@ -15,6 +28,39 @@ const Preferences: React.FC = () => {
   const { t } = useTranslation();
   const { theme, setTheme } = useTheme();
   const { language } = useLanguage();
+  const [autoPaste, setAutoPasteOptions] = useState<AutoPasteOptions | null>(
+    null,
+  );
+
+  useEffect(() => {
+    // the section stays hidden where items can only be copied
+    getAutoPasteAvailable()
+      .then((available) => (available ? getAutoPaste() : null))
+      .then(setAutoPasteOptions)
+      .catch(() => {});
+  }, []);
+
+  // typed shortcuts stay local until saved, the backend rejects half-typed ones
+  const saveAutoPaste = async (options: AutoPasteOptions) => {
+    setAutoPasteOptions(options);
+    try {
+      await setAutoPaste(options);
+    } catch (error) {
+      alert(
+        t("components.settings.preferences.autoPasteErrorSave", {
+          message: String(error),
+        }),
+      );
+    }
+  };
+
+  const updateTarget = (index: number, target: PasteTarget) => {
+    if (!autoPaste) return;
+    setAutoPasteOptions({
+      ...autoPaste,
+      targets: autoPaste.targets.map((t, i) => (i === index ? target : t)),
+    });
+  };
 
   const getLanguageLabel = () => {
     const labels: Record<string, string> = {
@@ -46,6 +92,104 @@ const Preferences: React.FC = () => {
         route="/settings/preferences/language"
         value={getLanguageLabel()}
       />
+
+      {autoPaste && (
+        <div className={`flex flex-col gap-4 p-4 ${BORDER_BOTTOM}`}>
+          <label className="flex items-center gap-2 cursor-pointer">
+            <input
+              type="checkbox"
+              checked={autoPaste.enabled}
+              onChange={(e) =>
+                saveAutoPaste({ ...autoPaste, enabled: e.target.checked })
+              }
+              className="w-4 h-4 accent-secondary cursor-pointer"
+            />
+            <span className="text-sm text-neutral-800 dark:text-neutral-200">
+              {t("components.settings.preferences.autoPaste")}
+            </span>
+          </label>
+
+          {autoPaste.enabled && (
+            <>
+              <Input
+                label={t("components.settings.preferences.autoPasteShortcut")}
+                value={autoPaste.shortcut}
+                onChange={(e) =>
+                  setAutoPasteOptions({
+                    ...autoPaste,
+                    shortcut: e.target.value,
+                  })
+                }
+              />
+
+              <div className="flex flex-col gap-2">
+                <span className="text-sm font-medium">
+                  {t("components.settings.preferences.autoPasteTargets")}
+                </span>
+                {autoPaste.targets.map((target, index) => (
+                  <div key={index} className="flex gap-2 items-center">
+                    <Input
+                      className="flex-1"
+                      value={target.app}
+                      placeholder={t(
+                        "components.settings.preferences.autoPasteApp",
+                      )}
+                      onChange={(e) =>
+                        updateTarget(index, { ...target, app: e.target.value })
+                      }
+                    />
+                    <Input
+                      className="flex-1"
+                      value={target.shortcut}
+                      onChange={(e) =>
+                        updateTarget(index, {
+                          ...target,
+                          shortcut: e.target.value,
+                        })
+                      }
+                    />
+                    <Button
+                      variant="danger"
+                      label={t(
+                        "components.settings.preferences.autoPasteRemove",
+                      )}
+                      onClick={() =>
+                        saveAutoPaste({
+                          ...autoPaste,
+                          targets: autoPaste.targets.filter(
+                            (_, i) => i !== index,
+                          ),
+                        })
+                      }
+                    />
+                  </div>
+                ))}
+              </div>
+
+              <div className="flex gap-2">
+                <Button
+                  size="sm"
+                  label={t("components.settings.preferences.autoPasteAdd")}
+                  onClick={() =>
+                    setAutoPasteOptions({
+                      ...autoPaste,
+                      targets: [
+                        ...autoPaste.targets,
+                        { app: "", shortcut: autoPaste.shortcut },
+                      ],
+                    })
+                  }
+                />
+                <Button
+                  size="sm"
+                  label={t("components.settings.preferences.autoPasteSave")}
+                  onClick={() => saveAutoPaste(autoPaste)}
+                />
+              </div>
+            </>
+          )}
+        </div>
+      )}
     </div>
   );
 };
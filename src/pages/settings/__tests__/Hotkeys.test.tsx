@@ -1,7 +1,174 @@
-import { hasTranslationKey } from "@yzzo/test/utils/i18n-mock";
-import { expect } from "bun:test";
-import { test } from "bun:test";
-import { describe } from "bun:test";
+import { test, expect, describe, beforeEach, mock } from "bun:test";
+import { waitFor, within, fireEvent } from "@testing-library/react";
+import { render } from "@yzzo/test/utils/test-utils";
+import { setupI18nMock, hasTranslationKey } from "@yzzo/test/utils/i18n-mock";
+import Hotkeys from "../Hotkeys";
+
+let mockBehavior = "toggle";
+
+const mockGetCycleReleaseDetected = mock(() => Promise.resolve(true));
+const mockGetHotkeyBindings = mock(() =>
+  Promise.resolve([
+    { hotkey: "Ctrl+Alt+1", action: { type: "paste_recent", index: 1 } },
+  ]),
+);
+const mockBindHotkey = mock(() => Promise.resolve());
+const mockUnbindHotkey = mock(() => Promise.resolve());
+
+mock.module("@yzzo/hooks", () => ({
+  useGlobalHotkey: () => ({
+    hotkey: "Ctrl+Shift+V",
+    behavior: mockBehavior,
+    holdThreshold: 300,
+    isLoading: false,
+    updateHotkey: mock(() => Promise.resolve()),
+    updateBehavior: mock(() => Promise.resolve()),
+    updateHoldThreshold: mock(() => Promise.resolve()),
+  }),
+}));
+
+mock.module("@yzzo/api/tauriApi", () => ({
+  getCycleReleaseDetected: mockGetCycleReleaseDetected,
+  getHotkeyBindings: mockGetHotkeyBindings,
+  bindHotkey: mockBindHotkey,
+  unbindHotkey: mockUnbindHotkey,
+}));
+
+mock.module("@yzzo/components", () => ({
+  Header: ({ title }: any) => <h1>{title}</h1>,
+  Label: ({ label }: any) => <label>{label}</label>,
+  Input: ({ value }: any) => (
+    <input data-testid="input" value={value} readOnly />
+  ),
+  Button: ({ label, onClick }: any) => (
+    <button onClick={onClick}>{label}</button>
+  ),
+  Radio: ({ label }: any) => <fieldset aria-label={label} />,
+}));
+
+describe("Hotkeys page", () => {
+  beforeEach(() => {
+    setupI18nMock("en");
+    mockBehavior = "toggle";
+    mockGetCycleReleaseDetected.mockClear();
+    mockBindHotkey.mockClear();
+    mockUnbindHotkey.mockClear();
+  });
+
+  describe("Action hotkeys", () => {
+    test("should list the bound hotkeys with their actions", async () => {
+      const { container } = render(<Hotkeys />);
+
+      await waitFor(() => {
+        expect(within(container).getByText("Ctrl+Alt+1")).toBeDefined();
+        expect(within(container).getByText("Paste item 1")).toBeDefined();
+      });
+    });
+
+    test("should unbind a removed hotkey", async () => {
+      const { container } = render(<Hotkeys />);
+
+      const remove = await waitFor(() => within(container).getByText("Remove"));
+      fireEvent.click(remove);
+
+      await waitFor(() => {
+        expect(mockUnbindHotkey).toHaveBeenCalledWith("Ctrl+Alt+1");
+      });
+    });
+
+    test("should bind a recorded hotkey to the chosen action", async () => {
+      const { container } = render(<Hotkeys />);
+
+      fireEvent.click(within(container).getByText("Record"));
+      fireEvent.keyDown(window, {
+        key: "3",
+        code: "Digit3",
+        ctrlKey: true,
+        altKey: true,
+      });
+      fireEvent.keyUp(window, { key: "3", code: "Digit3" });
+
+      await waitFor(() => {
+        expect(mockBindHotkey).toHaveBeenCalledWith(
+          "Ctrl+Alt+3",
+          { type: "paste_recent", index: 1 },
+          "Digit3",
+        );
+      });
+    });
+  });
+
+  describe("Chords", () => {
+    test("should list a chord as its leader and the key after it", async () => {
+      mockGetHotkeyBindings.mockResolvedValueOnce([
+        {
+          hotkey: "Ctrl+Alt+V, 3",
+          code: "KeyV, Digit3",
+          action: { type: "paste_recent", index: 3 },
+        },
+      ]);
+      const { container } = render(<Hotkeys />);
+
+      await waitFor(() => {
+        expect(within(container).getByText("Ctrl+Alt+V, 3")).toBeDefined();
+        expect(within(container).getByText("Paste item 3")).toBeDefined();
+      });
+    });
+
+    test("should bind the leader and the key recorded after it", async () => {
+      const { container } = render(<Hotkeys />);
+
+      fireEvent.click(within(container).getByRole("checkbox"));
+      fireEvent.click(within(container).getByText("Record"));
+      fireEvent.keyDown(window, {
+        key: "v",
+        code: "KeyV",
+        ctrlKey: true,
+        altKey: true,
+      });
+      fireEvent.keyUp(window, { key: "v", code: "KeyV" });
+      expect(mockBindHotkey).not.toHaveBeenCalled();
+
+      fireEvent.keyDown(window, { key: "3", code: "Digit3" });
+      fireEvent.keyUp(window, { key: "3", code: "Digit3" });
+
+      await waitFor(() => {
+        expect(mockBindHotkey).toHaveBeenCalledWith(
+          "Ctrl+Alt+V, 3",
+          { type: "paste_recent", index: 1 },
+          "KeyV, Digit3",
+        );
+      });
+    });
+  });
+
+  describe("Cycle behavior", () => {
+    test("should explain the timeout where modifier release is not detected", async () => {
+      mockBehavior = "cycle";
+      mockGetCycleReleaseDetected.mockResolvedValueOnce(false);
+      const { container } = render(<Hotkeys />);
+
+      await waitFor(() => {
+        expect(
+          within(container).getByText(/cannot be detected here/),
+        ).toBeDefined();
+      });
+    });
+
+    test("should not explain the timeout where modifier release is detected", async () => {
+      mockBehavior = "cycle";
+      const { container } = render(<Hotkeys />);
+
+      await waitFor(() => {
+        expect(mockGetCycleReleaseDetected).toHaveBeenCalled();
+      });
+      expect(
+        within(container).queryByText(/cannot be detected here/),
+      ).toBeNull();
+    });
+  });
+});
+
 
 describe("Hotkeys page (Translation keys)", () => {
   describe("common", () => {
@@ -30,6 +197,26 @@ describe("Hotkeys page (Translation keys)", () => {
         "components.settings.hotkey.helperText",
         "components.settings.hotkey.change",
         "components.settings.hotkey.behavior",
+        "components.settings.hotkey.behaviorHybrid",
+        "components.settings.hotkey.holdThreshold",
+        "components.settings.hotkey.cycleReleaseUndetected",
+        "components.settings.hotkey.bindings",
+        "components.settings.hotkey.bindingsEmpty",
+        "components.settings.hotkey.bindingAction",
+        "components.settings.hotkey.bindingIndex",
+        "components.settings.hotkey.bindingAdd",
+        "components.settings.hotkey.bindingChord",
+        "components.settings.hotkey.bindingRemove",
+        "components.settings.hotkey.actionToggleWindow",
+        "components.settings.hotkey.actionPasteRecent",
+        "components.settings.hotkey.actionPasteItem",
+        "components.settings.hotkey.actionPastePrevious",
+        "components.settings.hotkey.actionPauseCapture",
+        "components.settings.hotkey.actionClearClipboard",
+        "components.settings.hotkey.actionOpenSearch",
+        "components.settings.hotkey.errorParse",
+        "components.settings.hotkey.errorConflict",
+        "components.settings.hotkey.errorSave",
       ];
 
       keysToCheck.forEach((key) => {
@@ -44,6 +231,26 @@ describe("Hotkeys page (Translation keys)", () => {
         "components.settings.hotkey.helperText",
         "components.settings.hotkey.change",
         "components.settings.hotkey.behavior",
+        "components.settings.hotkey.behaviorHybrid",
+        "components.settings.hotkey.holdThreshold",
+        "components.settings.hotkey.cycleReleaseUndetected",
+        "components.settings.hotkey.bindings",
+        "components.settings.hotkey.bindingsEmpty",
+        "components.settings.hotkey.bindingAction",
+        "components.settings.hotkey.bindingIndex",
+        "components.settings.hotkey.bindingAdd",
+        "components.settings.hotkey.bindingChord",
+        "components.settings.hotkey.bindingRemove",
+        "components.settings.hotkey.actionToggleWindow",
+        "components.settings.hotkey.actionPasteRecent",
+        "components.settings.hotkey.actionPasteItem",
+        "components.settings.hotkey.actionPastePrevious",
+        "components.settings.hotkey.actionPauseCapture",
+        "components.settings.hotkey.actionClearClipboard",
+        "components.settings.hotkey.actionOpenSearch",
+        "components.settings.hotkey.errorParse",
+        "components.settings.hotkey.errorConflict",
+        "components.settings.hotkey.errorSave",
       ];
 
       keysToCheck.forEach((key) => {
@@ -10,6 +10,22 @@ let mockTheme = "light";
 const mockSetLanguage = mock(() => {});
 let mockLanguage = "system";
 
+const mockGetAutoPaste = mock(() =>
+  Promise.resolve({
+    enabled: false,
+    shortcut: "Ctrl+V",
+    targets: [{ app: "Alacritty", shortcut: "Ctrl+Shift+V" }],
+  }),
+);
+const mockSetAutoPaste = mock(() => Promise.resolve());
+const mockGetAutoPasteAvailable = mock(() => Promise.resolve(true));
+
+mock.module("@yzzo/api/tauriApi", () => ({
+  getAutoPaste: mockGetAutoPaste,
+  getAutoPasteAvailable: mockGetAutoPasteAvailable,
+  setAutoPaste: mockSetAutoPaste,
+}));
+
 mock.module("@yzzo/contexts/ThemeContext", () => ({
   useTheme: () => ({
     theme: mockTheme,
@@ -36,6 +52,12 @@ mock.module("@yzzo/components", () => ({
     </header>
   ),
   Label: ({ label }: any) => <label data-testid="label">{label}</label>,
+  Input: ({ value, onChange }: any) => (
+    <input data-testid="input" value={value} onChange={onChange} />
+  ),
+  Button: ({ label, onClick }: any) => (
+    <button onClick={onClick}>{label}</button>
+  ),
   SettingsItem: ({ name, route, value }: any) => (
     <button data-testid="settings-item" data-route={route}>
       <span>{name}</span>
@@ -51,6 +73,7 @@ describe("Preferences page", () => {
     mockSetTheme.mockClear();
     mockLanguage = "system";
     mockSetLanguage.mockClear();
+    mockSetAutoPaste.mockClear();
   });
 
   describe("Structure", () => {
@@ -182,6 +205,73 @@ describe("Preferences page", () => {
     });
   });
 
+  describe("Auto-paste", () => {
+    test("should save auto-paste when the checkbox is checked", async () => {
+      const { container } = render(<Preferences />);
+
+      const checkbox = await waitFor(() =>
+        within(container).getByRole("checkbox"),
+      );
+      fireEvent.click(checkbox);
+
+      await waitFor(() => {
+        expect(mockSetAutoPaste).toHaveBeenCalledWith({
+          enabled: true,
+          shortcut: "Ctrl+V",
+          targets: [{ app: "Alacritty", shortcut: "Ctrl+Shift+V" }],
+        });
+      });
+    });
+
+    test("should list the shortcut for each application when enabled", async () => {
+      mockGetAutoPaste.mockResolvedValueOnce({
+        enabled: true,
+        shortcut: "Ctrl+V",
+        targets: [{ app: "Alacritty", shortcut: "Ctrl+Shift+V" }],
+      });
+      const { container } = render(<Preferences />);
+
+      await waitFor(() => {
+        const values = within(container)
+          .getAllByTestId("input")
+          .map((input) => (input as HTMLInputElement).value);
+        expect(values).toEqual(["Ctrl+V", "Alacritty", "Ctrl+Shift+V"]);
+      });
+    });
+
+    test("should save a removed application", async () => {
+      mockGetAutoPaste.mockResolvedValueOnce({
+        enabled: true,
+        shortcut: "Ctrl+V",
+        targets: [{ app: "Alacritty", shortcut: "Ctrl+Shift+V" }],
+      });
+      const { container } = render(<Preferences />);
+
+      const remove = await waitFor(() => within(container).getByText("Remove"));
+      fireEvent.click(remove);
+
+      await waitFor(() => {
+        expect(mockSetAutoPaste).toHaveBeenCalledWith({
+          enabled: true,
+          shortcut: "Ctrl+V",
+          targets: [],
+        });
+      });
+    });
+  });
+
+  describe("Auto-paste availability", () => {
+    test("should hide auto-paste where it is not supported", async () => {
+      mockGetAutoPasteAvailable.mockResolvedValueOnce(false);
+      const { container } = render(<Preferences />);
+
+      await waitFor(() => {
+        expect(mockGetAutoPasteAvailable).toHaveBeenCalled();
+      });
+      expect(within(container).queryByRole("checkbox")).toBeNull();
+    });
+  });
+
   describe("Translation keys", () => {
     test("all Preferences page keys should exist in English", () => {
       const keysToCheck = [
@@ -189,6 +279,14 @@ describe("Preferences page", () => {
         "components.settings.preferences.themeLight",
         "components.settings.preferences.themeDark",
         "components.settings.preferences.themeAuto",
+        "components.settings.preferences.autoPaste",
+        "components.settings.preferences.autoPasteShortcut",
+        "components.settings.preferences.autoPasteTargets",
+        "components.settings.preferences.autoPasteApp",
+        "components.settings.preferences.autoPasteAdd",
+        "components.settings.preferences.autoPasteRemove",
+        "components.settings.preferences.autoPasteSave",
+        "components.settings.preferences.autoPasteErrorSave",
       ];
 
       keysToCheck.forEach((key) => {
@@ -202,6 +300,14 @@ describe("Preferences page", () => {
         "components.settings.preferences.themeLight",
         "components.settings.preferences.themeDark",
         "components.settings.preferences.themeAuto",
+        "components.settings.preferences.autoPaste",
+        "components.settings.preferences.autoPasteShortcut",
+        "components.settings.preferences.autoPasteTargets",
+        "components.settings.preferences.autoPasteApp",
+        "components.settings.preferences.autoPasteAdd",
+        "components.settings.preferences.autoPasteRemove",
+        "components.settings.preferences.autoPasteSave",
+        "components.settings.preferences.autoPasteErrorSave",
       ];
 
       keysToCheck.forEach((key) => {
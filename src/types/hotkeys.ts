@@ -1 +1,24 @@
-export type HotkeyBehavior = "toggle" | "hold";
+export type HotkeyBehavior = "toggle" | "hold" | "hybrid" | "cycle";
+
+// mirrors the tagged `HotkeyAction` enum on the backend
+export type HotkeyAction =
+  | { type: "toggle_window" }
+  | { type: "paste_recent"; index: number }
+  | { type: "paste_previous" }
+  | { type: "pause_capture" }
+  | { type: "clear_clipboard" }
+  | { type: "open_search" };
+
+export interface HotkeyBinding {
+  // a single hotkey, or a leader and the key after it: "Ctrl+Alt+V, 3"
+  hotkey: string;
+  // the physical keys registered, e.g. "KeyQ" for A on AZERTY, "KeyV, Digit3" for a chord
+  code?: string;
+  action: HotkeyAction;
+}
+
+// Error returned by `set_hotkey` and `bind_hotkey`.
+export interface HotkeyError {
+  kind: "parse" | "invalid_action" | "conflict" | "database";
+  message: string;
+}
@@ -1,3 +1,8 @@
-export type { HotkeyBehavior } from "./hotkeys";
+export type {
+  HotkeyAction,
+  HotkeyBehavior,
+  HotkeyBinding,
+  HotkeyError,
+} from "./hotkeys";
 export type { Language, SupportedLanguage } from "./language";
 export type { Theme } from "./theme";